@@ -1,18 +1,76 @@
 //! Syntax tree that backs a path. If you just want to use paths, you shouldn't touch this.
 //! This is exposed for users who want to provide things like syntax highlighting of paths
 //! or similar.
+//!
+//! Syntax tree nodes compare equal if they have the same structure and values, regardless of
+//! their source spans, so the same path parsed from differently formatted patterns is equal.
+//!
+//! For example, a simple highlighter which colors names and literals with ANSI escapes:
+//!
+//! ```
+//! # use jsonpath_plus::JsonPath;
+//! use jsonpath_plus::ast::{BracketSelector, RawSelector};
+//!
+//! const NAME: &str = "\x1b[34m";
+//! const LIT: &str = "\x1b[32m";
+//! const RESET: &str = "\x1b[0m";
+//!
+//! fn raw(out: &mut String, selector: &RawSelector) {
+//!     match selector.as_name() {
+//!         Some(name) => out.push_str(&format!("{}{}{}", NAME, name, RESET)),
+//!         None => out.push_str(&selector.to_string()),
+//!     }
+//! }
+//!
+//! fn bracket(out: &mut String, selector: &BracketSelector) {
+//!     match selector.as_literal() {
+//!         Some(lit) => out.push_str(&format!("[{}{}{}]", LIT, lit, RESET)),
+//!         None => out.push_str(&selector.to_string()),
+//!     }
+//! }
+//!
+//! fn highlight(path: &JsonPath) -> String {
+//!     let mut out = String::from("$");
+//!     for seg in path {
+//!         match (seg.as_raw_selector(), seg.as_bracket_selector()) {
+//!             (Some(selector), _) => {
+//!                 out.push_str(if seg.is_recursive() { ".." } else { "." });
+//!                 raw(&mut out, selector);
+//!             }
+//!             (_, Some(selector)) => bracket(&mut out, selector),
+//!             (None, None) => out.push_str(".."),
+//!         }
+//!     }
+//!     if path.is_id() {
+//!         out.push('~');
+//!     }
+//!     out
+//! }
+//!
+//! let path = JsonPath::compile("$.a..*[0]['b']..[?(@.c)]").unwrap();
+//! assert_eq!(
+//!     highlight(&path),
+//!     "$.\x1b[34ma\x1b[0m..*[\x1b[32m0\x1b[0m][\x1b[32m'b'\x1b[0m]..[?(@.c)]",
+//! );
+//! ```
 
 #![cfg_attr(not(feature = "spanned"), allow(dead_code))]
 
 use core::num::NonZeroI64;
+use core::ops::Index;
+use core::slice::SliceIndex;
 
+mod display;
 mod error;
 mod eval;
+mod lint;
 mod parse;
 mod span;
 #[cfg(test)]
 mod tests;
-mod token;
+pub mod token;
+pub mod visit;
+pub mod visit_mut;
 
 pub use error::{FailReason, ParseFail};
 pub use span::Span;
@@ -26,7 +84,33 @@ type Error = ParseFail<char, ()>;
 
 // Atoms
 
+// Spans don't participate in equality, so atoms compare only their values
+macro_rules! value_eq {
+    ($($name:ident),* $(,)?) => {
+        $(
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.val == other.val
+            }
+        }
+
+        impl Eq for $name {}
+        )*
+    };
+}
+
+value_eq!(Ident, BoolLit, IntLit, NonZeroIntLit, StringContent);
+
+impl PartialEq for NullLit {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for NullLit {}
+
 /// A raw identifier, the `foo` in `.foo`
+#[derive(Clone, Debug)]
 pub struct Ident {
     #[cfg(feature = "spanned")]
     span: Span,
@@ -34,6 +118,17 @@ pub struct Ident {
 }
 
 impl Ident {
+    /// Create a new identifier. The name isn't checked, so one containing characters not allowed
+    /// in a dot selector won't display as a valid path
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Ident {
+        Ident {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+            val: name.into(),
+        }
+    }
+
     /// Get the string representation of this identifier
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -42,6 +137,7 @@ impl Ident {
 }
 
 /// A boolean literal, such as `true` or `false`
+#[derive(Clone, Debug)]
 pub struct BoolLit {
     #[cfg(feature = "spanned")]
     span: Span,
@@ -49,6 +145,16 @@ pub struct BoolLit {
 }
 
 impl BoolLit {
+    /// Create a new boolean literal
+    #[must_use]
+    pub fn new(val: bool) -> BoolLit {
+        BoolLit {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+            val,
+        }
+    }
+
     /// Get the boolean representation of this literal
     #[must_use]
     pub fn as_bool(&self) -> bool {
@@ -57,12 +163,31 @@ impl BoolLit {
 }
 
 /// A null literal, the keyword `null`
+#[derive(Clone, Debug)]
 pub struct NullLit {
     #[cfg(feature = "spanned")]
     span: Span,
 }
 
+impl NullLit {
+    /// Create a new null literal
+    #[must_use]
+    pub fn new() -> NullLit {
+        NullLit {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+        }
+    }
+}
+
+impl Default for NullLit {
+    fn default() -> Self {
+        NullLit::new()
+    }
+}
+
 /// An integer literal, such as `-3`
+#[derive(Clone, Debug)]
 pub struct IntLit {
     #[cfg(feature = "spanned")]
     span: Span,
@@ -70,6 +195,16 @@ pub struct IntLit {
 }
 
 impl IntLit {
+    /// Create a new integer literal
+    #[must_use]
+    pub fn new(val: i64) -> IntLit {
+        IntLit {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+            val,
+        }
+    }
+
     /// Get the integer representation of this literal
     #[must_use]
     pub fn as_int(&self) -> i64 {
@@ -78,6 +213,7 @@ impl IntLit {
 }
 
 /// A non-zero integer literal, any integer not `0`
+#[derive(Clone, Debug)]
 pub struct NonZeroIntLit {
     #[cfg(feature = "spanned")]
     span: Span,
@@ -85,6 +221,16 @@ pub struct NonZeroIntLit {
 }
 
 impl NonZeroIntLit {
+    /// Create a new non-zero integer literal
+    #[must_use]
+    pub fn new(val: NonZeroI64) -> NonZeroIntLit {
+        NonZeroIntLit {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+            val,
+        }
+    }
+
     /// Get the integer representation of this literal
     #[must_use]
     pub fn as_int(&self) -> NonZeroI64 {
@@ -92,13 +238,25 @@ impl NonZeroIntLit {
     }
 }
 
+#[derive(Clone, Debug)]
 struct StringContent {
     #[cfg(feature = "spanned")]
     span: Span,
     val: String,
 }
 
+impl StringContent {
+    fn new(val: String) -> StringContent {
+        StringContent {
+            #[cfg(feature = "spanned")]
+            span: Span::synthesized(),
+            val,
+        }
+    }
+}
+
 /// An apostrophe-delimited string
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SingleStringLit {
     start: token::SingleQuote,
     content: StringContent,
@@ -106,6 +264,22 @@ pub struct SingleStringLit {
 }
 
 impl SingleStringLit {
+    /// Get the opening and closing apostrophes of this string literal
+    #[must_use]
+    pub fn quote_tokens(&self) -> (&token::SingleQuote, &token::SingleQuote) {
+        (&self.start, &self.end)
+    }
+
+    /// Create a new apostrophe-delimited string literal
+    #[must_use]
+    pub fn new(content: impl Into<String>) -> SingleStringLit {
+        SingleStringLit {
+            start: token::SingleQuote::new(),
+            content: StringContent::new(content.into()),
+            end: token::SingleQuote::new(),
+        }
+    }
+
     /// Get the content of this string literal
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -114,6 +288,7 @@ impl SingleStringLit {
 }
 
 /// A quote-delimite string
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DoubleStringLit {
     start: token::DoubleQuote,
     content: StringContent,
@@ -121,6 +296,22 @@ pub struct DoubleStringLit {
 }
 
 impl DoubleStringLit {
+    /// Get the opening and closing quotes of this string literal
+    #[must_use]
+    pub fn quote_tokens(&self) -> (&token::DoubleQuote, &token::DoubleQuote) {
+        (&self.start, &self.end)
+    }
+
+    /// Create a new quote-delimited string literal
+    #[must_use]
+    pub fn new(content: impl Into<String>) -> DoubleStringLit {
+        DoubleStringLit {
+            start: token::DoubleQuote::new(),
+            content: StringContent::new(content.into()),
+            end: token::DoubleQuote::new(),
+        }
+    }
+
     /// Get the content of this string literal
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -129,6 +320,7 @@ impl DoubleStringLit {
 }
 
 /// Any string literal, whether single or double quote delimited
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StringLit {
     /// A single-quoted string literal
     Single(SingleStringLit),
@@ -137,6 +329,22 @@ pub enum StringLit {
 }
 
 impl StringLit {
+    /// Create a new single-quoted string literal
+    #[must_use]
+    pub fn new(content: impl Into<String>) -> StringLit {
+        StringLit::Single(SingleStringLit::new(content))
+    }
+
+    /// Get the source span of the content of this string literal, excluding its delimiters
+    #[cfg(feature = "spanned")]
+    #[must_use]
+    pub fn content_span(&self) -> Span {
+        match self {
+            StringLit::Single(s) => s.content.span(),
+            StringLit::Double(s) => s.content.span(),
+        }
+    }
+
     /// Get the content of this string literal
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -153,6 +361,7 @@ impl StringLit {
 /// recompilation of the same pattern many times.
 #[must_use = "A path does nothing on its own, call `find` or `find_str` to evaluate the path on a \
               value"]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Path {
     dollar: token::Dollar,
     segments: Vec<Segment>,
@@ -160,15 +369,158 @@ pub struct Path {
 }
 
 impl Path {
+    /// Create a new path from the root `$` with the provided segments
+    pub fn new(segments: Vec<Segment>) -> Path {
+        Path {
+            dollar: token::Dollar::new(),
+            segments,
+            tilde: None,
+        }
+    }
+
     /// A slice of the segments this path contains
     #[must_use]
     pub fn segments(&self) -> &[Segment] {
         &self.segments
     }
+
+    /// The segments this path contains, which can be modified, added or removed in place
+    pub fn segments_mut(&mut self) -> &mut Vec<Segment> {
+        &mut self.segments
+    }
+
+    /// A slice of the segments this path contains. Equivalent to [`Path::segments`]
+    #[must_use]
+    pub fn as_segments_slice(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Create a new path containing only the segments of this path for which the provided
+    /// function returns `true`
+    pub fn filter_segments(&self, mut f: impl FnMut(&Segment) -> bool) -> Path {
+        self.map_segments(|seg| if f(seg) { Some(seg.clone()) } else { None })
+    }
+
+    /// Create a new path by replacing each segment of this path with the result of the provided
+    /// function. Returning `None` removes the segment from the new path
+    pub fn map_segments(&self, f: impl FnMut(&Segment) -> Option<Segment>) -> Path {
+        Path {
+            dollar: self.dollar.clone(),
+            segments: self.segments.iter().filter_map(f).collect(),
+            tilde: self.tilde.clone(),
+        }
+    }
+
+    /// Whether this path references the IDs of the matched items, or the items themselves
+    #[must_use]
+    pub fn is_id(&self) -> bool {
+        self.tilde.is_some()
+    }
+
+    /// Get the root `$` token this path starts with
+    #[must_use]
+    pub fn dollar_token(&self) -> &token::Dollar {
+        &self.dollar
+    }
+
+    /// Get the `~` token this path ends with, if it references IDs
+    #[must_use]
+    pub fn tilde_token(&self) -> Option<&token::Tilde> {
+        self.tilde.as_ref()
+    }
+
+    /// Whether this path is absolute. A full path always starts at the root `$`, so this is
+    /// always `true`
+    #[must_use]
+    pub fn is_absolute(&self) -> bool {
+        true
+    }
+
+    /// Whether this path can match at most one item in any input, because every segment selects
+    /// at most one item
+    #[must_use]
+    pub fn could_match_at_most_one(&self) -> bool {
+        self.segments.iter().all(Segment::is_deterministic)
+    }
+
+    /// Whether any selector in this path is a filter, including filters in unions and in nested
+    /// sub-paths
+    #[must_use]
+    pub fn has_filter(&self) -> bool {
+        self.segments.iter().any(Segment::has_filter)
+    }
+
+    /// Whether this path has at least one segment, and every segment selects only by filters,
+    /// such as `$[?(@.a)][?(@.b), ?(@.c)]`
+    #[must_use]
+    pub fn is_filter_only(&self) -> bool {
+        !self.segments.is_empty() && self.segments.iter().all(Segment::is_filter_only)
+    }
+
+    /// Whether this path selects exactly the root value, because it is `$` with no segments and
+    /// no tilde. Deleting or replacing with such a path affects the whole document
+    #[must_use]
+    pub fn would_select_root(&self) -> bool {
+        self.segments.is_empty() && self.tilde.is_none()
+    }
+
+    /// The maximum depth below the root this path can traverse, or `None` if it contains a
+    /// recursive descent and so is unbounded. Each segment counts as one level, and sub-paths
+    /// inside selectors and filters count from the depth they're evaluated at, with root-based
+    /// sub-paths starting over from the root. For example, `$.a.b.c` has a depth of 3, and
+    /// `$.a[?(@.b.c)]` has a depth of 4
+    #[must_use]
+    pub fn depth(&self) -> Option<usize> {
+        segments_depth(&self.segments, 0)
+    }
+}
+
+/// The maximum depth reached by a sequence of segments applied at a starting depth, including
+/// any sub-paths they contain, or `None` if it's unbounded
+fn segments_depth(segments: &[Segment], start: usize) -> Option<usize> {
+    segments
+        .iter()
+        .enumerate()
+        .try_fold(start + segments.len(), |max, (idx, seg)| {
+            Some(max.max(seg.nested_depth(start + idx)?))
+        })
+}
+
+impl<'a> IntoIterator for &'a Path {
+    type Item = &'a Segment;
+    type IntoIter = core::slice::Iter<'a, Segment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.iter()
+    }
+}
+
+impl IntoIterator for Path {
+    type Item = Segment;
+    type IntoIter = std::vec::IntoIter<Segment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.into_iter()
+    }
+}
+
+impl AsRef<[Segment]> for Path {
+    fn as_ref(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl<I: SliceIndex<[Segment]>> Index<I> for Path {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.segments[index]
+    }
 }
 
 /// A sub-path, such as in a filter or as a bracket selector. Can be based off the root or the
 /// current location
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SubPath {
     kind: PathKind,
     segments: Vec<Segment>,
@@ -188,15 +540,61 @@ impl SubPath {
         &self.segments
     }
 
+    /// The segments this path contains, which can be modified, added or removed in place
+    pub fn segments_mut(&mut self) -> &mut Vec<Segment> {
+        &mut self.segments
+    }
+
     /// Whether this path references the IDs of the matched items, or the items themselves
     #[must_use]
     pub fn is_id(&self) -> bool {
         self.tilde.is_some()
     }
+
+    /// Get the `~` token this path ends with, if it references IDs
+    #[must_use]
+    pub fn tilde_token(&self) -> Option<&token::Tilde> {
+        self.tilde.as_ref()
+    }
+
+    /// Whether this path is based on the root `$`, rather than relative to the current item `@`
+    #[must_use]
+    pub fn is_absolute(&self) -> bool {
+        self.kind.is_root()
+    }
+
+    fn depth_from(&self, cur: usize) -> Option<usize> {
+        let start = if self.kind.is_relative() { cur } else { 0 };
+        segments_depth(&self.segments, start)
+    }
+
+    /// Convert this sub-path into a standalone path. A relative path has the segments of the
+    /// provided root path prepended to its own, while a root-based path ignores the root path.
+    /// Whether the result references IDs is taken from this sub-path only
+    pub fn to_absolute(&self, root_path: &Path) -> Path {
+        match &self.kind {
+            PathKind::Root(dollar) => Path {
+                dollar: dollar.clone(),
+                segments: self.segments.clone(),
+                tilde: self.tilde.clone(),
+            },
+            PathKind::Relative(_) => Path {
+                dollar: root_path.dollar.clone(),
+                segments: root_path
+                    .segments
+                    .iter()
+                    .chain(&self.segments)
+                    .cloned()
+                    .collect(),
+                tilde: self.tilde.clone(),
+            },
+        }
+    }
 }
 
 /// The kind of a sub-path. Either root-based or relative
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PathKind {
     /// A root-based path
     Root(token::Dollar),
@@ -220,6 +618,7 @@ impl PathKind {
 
 /// A single segement selector in a path
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Segment {
     /// A dot followed by a simple selector, `.a`
     Dot(token::Dot, RawSelector),
@@ -229,8 +628,124 @@ pub enum Segment {
     Recursive(token::DotDot, Option<RawSelector>),
 }
 
+impl Segment {
+    /// Create a dot segment selecting a name, `.name`
+    #[must_use]
+    pub fn name(name: impl Into<String>) -> Segment {
+        Segment::Dot(token::Dot::new(), RawSelector::Name(Ident::new(name)))
+    }
+
+    /// Create a bracket segment selecting a name with a string literal, `['name']`. Unlike
+    /// [`Segment::name`], this can select names containing any characters
+    #[must_use]
+    pub fn key(name: impl Into<String>) -> Segment {
+        Segment::Bracket(
+            token::Bracket::new(),
+            BracketSelector::Literal(BracketLit::String(StringLit::new(name))),
+        )
+    }
+
+    /// Create a bracket segment selecting an index, `[0]`
+    #[must_use]
+    pub fn index(idx: i64) -> Segment {
+        Segment::Bracket(
+            token::Bracket::new(),
+            BracketSelector::Literal(BracketLit::Int(IntLit::new(idx))),
+        )
+    }
+
+    /// Whether this is a recursive descent segment, `..` optionally followed by a selector
+    #[must_use]
+    pub fn is_recursive(&self) -> bool {
+        matches!(self, Segment::Recursive(..))
+    }
+
+    /// Get the selector following the dot of a dot or recursive segment, or None
+    #[must_use]
+    pub fn as_raw_selector(&self) -> Option<&RawSelector> {
+        match self {
+            Segment::Dot(_, selector) | Segment::Recursive(_, Some(selector)) => Some(selector),
+            Segment::Bracket(..) | Segment::Recursive(_, None) => None,
+        }
+    }
+
+    /// Get the selector inside the brackets of a bracket segment, or None
+    #[must_use]
+    pub fn as_bracket_selector(&self) -> Option<&BracketSelector> {
+        if let Segment::Bracket(_, selector) = self {
+            Some(selector)
+        } else {
+            None
+        }
+    }
+
+    /// Get the source span of the selector of this segment, excluding the dots or brackets around
+    /// it. A recursive descent without a selector has no selector span
+    #[cfg(feature = "spanned")]
+    #[must_use]
+    pub fn selector_span(&self) -> Option<Span> {
+        match self {
+            Segment::Dot(_, selector) | Segment::Recursive(_, Some(selector)) => {
+                Some(selector.span())
+            }
+            Segment::Bracket(_, selector) => Some(selector.span()),
+            Segment::Recursive(_, None) => None,
+        }
+    }
+
+    /// Whether this segment always selects at most one item from each item it's applied to, such
+    /// as a name or a single index
+    #[must_use]
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            Segment::Dot(_, RawSelector::Name(_) | RawSelector::Parent(_)) => true,
+            Segment::Bracket(_, BracketSelector::Literal(_) | BracketSelector::Parent(_)) => true,
+            Segment::Bracket(_, selector) => selector.always_returns_empty(),
+            Segment::Dot(..) | Segment::Recursive(..) => false,
+        }
+    }
+
+    /// Whether this segment provably never selects anything, such as an empty slice or an index
+    /// too large for any array to contain
+    #[must_use]
+    pub fn always_returns_empty(&self) -> bool {
+        match self {
+            Segment::Bracket(_, selector) => selector.always_returns_empty(),
+            Segment::Dot(..) | Segment::Recursive(..) => false,
+        }
+    }
+
+    fn has_filter(&self) -> bool {
+        match self {
+            Segment::Bracket(_, selector) => selector.has_filter(),
+            Segment::Dot(..) | Segment::Recursive(..) => false,
+        }
+    }
+
+    fn is_filter_only(&self) -> bool {
+        match self {
+            Segment::Bracket(_, BracketSelector::Filter(_)) => true,
+            Segment::Bracket(_, BracketSelector::Union(components)) => components
+                .iter()
+                .all(|c| matches!(c, UnionComponent::Filter(_))),
+            Segment::Dot(..) | Segment::Bracket(..) | Segment::Recursive(..) => false,
+        }
+    }
+
+    /// The maximum depth reached by sub-paths in this segment when it's applied to items at the
+    /// provided depth, or `None` if it's unbounded
+    fn nested_depth(&self, cur: usize) -> Option<usize> {
+        match self {
+            Segment::Dot(..) => Some(0),
+            Segment::Bracket(_, selector) => selector.nested_depth(cur),
+            Segment::Recursive(..) => None,
+        }
+    }
+}
+
 /// The raw selector following a dot
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RawSelector {
     /// A wildcard selector to get all children, `.*`
     Wildcard(token::Star),
@@ -240,8 +755,33 @@ pub enum RawSelector {
     Name(Ident),
 }
 
+impl RawSelector {
+    /// Whether this selector is a wildcard
+    #[must_use]
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, RawSelector::Wildcard(_))
+    }
+
+    /// Whether this selector is a parent selector
+    #[must_use]
+    pub fn is_parent(&self) -> bool {
+        matches!(self, RawSelector::Parent(_))
+    }
+
+    /// Get this selector as a name, or None
+    #[must_use]
+    pub fn as_name(&self) -> Option<&Ident> {
+        if let RawSelector::Name(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
 /// A range for selecting keys from an array from a start to an end key, with an extra parameter to
 /// select every Nth key
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StepRange {
     start: Option<IntLit>,
     colon1: token::Colon,
@@ -269,6 +809,12 @@ impl StepRange {
         self.step.as_ref()
     }
 
+    /// Get the colons separating the start, end and step of this range
+    #[must_use]
+    pub fn colon_tokens(&self) -> (&token::Colon, &token::Colon) {
+        (&self.colon1, &self.colon2)
+    }
+
     /// Get the user-provided literal start for this range
     #[must_use]
     pub fn start(&self) -> Option<i64> {
@@ -286,9 +832,21 @@ impl StepRange {
     pub fn step(&self) -> Option<NonZeroI64> {
         self.step.as_ref().map(|a| a.as_int())
     }
+
+    /// Get the indices this range selects from an array of the provided length, in the order
+    /// they're selected
+    ///
+    /// With a negative step, selection walks down from the start, which defaults to the last
+    /// item, to just above the end, which defaults to before the first item. So `[3:0:-1]`
+    /// selects indices `3, 2, 1`, and `[::-1]` selects every item in reverse.
+    #[must_use]
+    pub fn to_explicit_indices(&self, len: usize) -> Vec<usize> {
+        self.indices(len).collect()
+    }
 }
 
 /// A range for selecting keys from an array from a start to an end key
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Range {
     start: Option<IntLit>,
     colon: token::Colon,
@@ -308,6 +866,12 @@ impl Range {
         self.end.as_ref()
     }
 
+    /// Get the colon separating the start and end of this range
+    #[must_use]
+    pub fn colon_token(&self) -> &token::Colon {
+        &self.colon
+    }
+
     /// Get the user-provided literal start for this range
     #[must_use]
     pub fn start(&self) -> Option<i64> {
@@ -319,10 +883,18 @@ impl Range {
     pub fn end(&self) -> Option<i64> {
         self.end.as_ref().map(|a| a.as_int())
     }
+
+    /// Get the indices this range selects from an array of the provided length, in the order
+    /// they're selected
+    #[must_use]
+    pub fn to_explicit_indices(&self, len: usize) -> Vec<usize> {
+        self.indices(len).collect()
+    }
 }
 
 /// A component of a bracket union selector
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum UnionComponent {
     /// A range selector with explicit step
     StepRange(StepRange),
@@ -338,6 +910,81 @@ pub enum UnionComponent {
     Literal(BracketLit),
 }
 
+impl UnionComponent {
+    fn has_filter(&self) -> bool {
+        match self {
+            UnionComponent::Filter(_) => true,
+            UnionComponent::Path(path) => path.segments.iter().any(Segment::has_filter),
+            _ => false,
+        }
+    }
+
+    fn nested_depth(&self, cur: usize) -> Option<usize> {
+        match self {
+            UnionComponent::Path(path) => path.depth_from(cur),
+            // Filters are evaluated on the children of the current item
+            UnionComponent::Filter(filter) => filter.inner.depth_from(cur + 1),
+            _ => Some(0),
+        }
+    }
+
+    /// Whether this selector is a parent selector
+    #[must_use]
+    pub fn is_parent(&self) -> bool {
+        matches!(self, UnionComponent::Parent(_))
+    }
+
+    /// Get this selector as a range with explicit step, or None
+    #[must_use]
+    pub fn as_step_range(&self) -> Option<&StepRange> {
+        if let UnionComponent::StepRange(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a range with implicit step, or None
+    #[must_use]
+    pub fn as_range(&self) -> Option<&Range> {
+        if let UnionComponent::Range(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a filter, or None
+    #[must_use]
+    pub fn as_filter(&self) -> Option<&Filter> {
+        if let UnionComponent::Filter(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a sub-path, or None
+    #[must_use]
+    pub fn as_path(&self) -> Option<&SubPath> {
+        if let UnionComponent::Path(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a literal, or None
+    #[must_use]
+    pub fn as_literal(&self) -> Option<&BracketLit> {
+        if let UnionComponent::Literal(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
 impl TryFrom<BracketSelector> for UnionComponent {
     type Error = ();
 
@@ -356,8 +1003,11 @@ impl TryFrom<BracketSelector> for UnionComponent {
 
 /// The inside of a bracket selector segment
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BracketSelector {
-    /// A union of multiple selectors, `[1, 3, 9]`
+    /// A union of multiple selectors, `[1, 3, 9]`. The matches of each component are returned
+    /// in the order the components are written, so `[2, 0]` returns the third item before the
+    /// first. Items matched by more than one component are returned once for each
     Union(Vec<UnionComponent>),
     /// A range selector with explicit step, `[1:5:2]`
     StepRange(StepRange),
@@ -375,8 +1025,104 @@ pub enum BracketSelector {
     Literal(BracketLit),
 }
 
+impl BracketSelector {
+    fn has_filter(&self) -> bool {
+        match self {
+            BracketSelector::Union(components) => components.iter().any(UnionComponent::has_filter),
+            BracketSelector::Filter(_) => true,
+            BracketSelector::Path(path) => path.segments.iter().any(Segment::has_filter),
+            _ => false,
+        }
+    }
+
+    fn nested_depth(&self, cur: usize) -> Option<usize> {
+        match self {
+            BracketSelector::Union(components) => components
+                .iter()
+                .try_fold(0, |max, c| Some(max.max(c.nested_depth(cur)?))),
+            BracketSelector::Path(path) => path.depth_from(cur),
+            // Filters are evaluated on the children of the current item
+            BracketSelector::Filter(filter) => filter.inner.depth_from(cur + 1),
+            _ => Some(0),
+        }
+    }
+
+    /// Whether this selector is a wildcard
+    #[must_use]
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, BracketSelector::Wildcard(_))
+    }
+
+    /// Whether this selector is a parent selector
+    #[must_use]
+    pub fn is_parent(&self) -> bool {
+        matches!(self, BracketSelector::Parent(_))
+    }
+
+    /// Get this selector as a union, or None
+    #[must_use]
+    pub fn as_union(&self) -> Option<&[UnionComponent]> {
+        if let BracketSelector::Union(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a range with explicit step, or None
+    #[must_use]
+    pub fn as_step_range(&self) -> Option<&StepRange> {
+        if let BracketSelector::StepRange(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a range with implicit step, or None
+    #[must_use]
+    pub fn as_range(&self) -> Option<&Range> {
+        if let BracketSelector::Range(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a sub-path, or None
+    #[must_use]
+    pub fn as_path(&self) -> Option<&SubPath> {
+        if let BracketSelector::Path(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a filter, or None
+    #[must_use]
+    pub fn as_filter(&self) -> Option<&Filter> {
+        if let BracketSelector::Filter(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Get this selector as a literal, or None
+    #[must_use]
+    pub fn as_literal(&self) -> Option<&BracketLit> {
+        if let BracketSelector::Literal(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
 /// A literal selector inside of brackets, `0` or `'a'`
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BracketLit {
     /// An integer literal, see [`IntLit`]
     Int(IntLit),
@@ -419,6 +1165,7 @@ impl BracketLit {
 }
 
 /// A filter selector inside of brackets, `?(...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Filter {
     question: token::Question,
     paren: token::Paren,
@@ -431,10 +1178,28 @@ impl Filter {
     pub fn expression(&self) -> &FilterExpr {
         &self.inner
     }
+
+    /// The inner expression of this filter, which can be modified in place
+    pub fn expression_mut(&mut self) -> &mut FilterExpr {
+        &mut self.inner
+    }
+
+    /// The `?` token this filter starts with
+    #[must_use]
+    pub fn question_token(&self) -> &token::Question {
+        &self.question
+    }
+
+    /// The parentheses around the expression of this filter
+    #[must_use]
+    pub fn paren_token(&self) -> &token::Paren {
+        &self.paren
+    }
 }
 
 /// A literal inside an expression
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ExprLit {
     /// An integer literal, see [`IntLit`]
     Int(IntLit),
@@ -504,6 +1269,7 @@ impl ExprLit {
 
 /// An expression inside a filter directive, or any sub-expression in that tree
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FilterExpr {
     /// An expression with an unary operator before it, such as `!(true)`
     Unary(UnOp, Box<FilterExpr>),
@@ -517,8 +1283,86 @@ pub enum FilterExpr {
     Parens(token::Paren, Box<FilterExpr>),
 }
 
+impl FilterExpr {
+    /// Get the expression inside any number of parentheses
+    #[must_use]
+    pub fn unparen(&self) -> &FilterExpr {
+        match self {
+            FilterExpr::Parens(_, inner) => inner.unparen(),
+            _ => self,
+        }
+    }
+
+    fn depth_from(&self, cur: usize) -> Option<usize> {
+        match self {
+            FilterExpr::Unary(_, inner) | FilterExpr::Parens(_, inner) => inner.depth_from(cur),
+            FilterExpr::Binary(left, _, right) => {
+                Some(left.depth_from(cur)?.max(right.depth_from(cur)?))
+            }
+            FilterExpr::Path(path) => path.depth_from(cur),
+            FilterExpr::Lit(_) => Some(0),
+        }
+    }
+
+    fn as_bool_lit(&self) -> Option<bool> {
+        match self.unparen() {
+            FilterExpr::Lit(lit) => lit.as_bool(),
+            _ => None,
+        }
+    }
+
+    /// Apply algebraic simplifications to this expression, simplifying sub-expressions first. The
+    /// rules applied are:
+    ///
+    /// - `!(!x)` becomes `x`
+    /// - `x && true` and `x || false` become `x`
+    /// - `x && false` becomes `false`, and `x || true` becomes `true`
+    /// - Parentheses around literals, paths and other parentheses are removed
+    ///
+    /// The boolean rules also apply with the operands swapped. They assume `x` evaluates to a
+    /// boolean - if it instead fails to evaluate, such as a sub-path which matches nothing, the
+    /// original expression fails where the simplified one may not. Simplifying an already
+    /// simplified expression doesn't change it.
+    #[must_use]
+    pub fn simplify(&self) -> FilterExpr {
+        match self {
+            FilterExpr::Unary(op, inner) => {
+                let inner = inner.simplify();
+                if let (UnOp::Not(_), FilterExpr::Unary(UnOp::Not(_), x)) = (op, inner.unparen()) {
+                    return (**x).clone();
+                }
+                FilterExpr::Unary(op.clone(), Box::new(inner))
+            }
+            FilterExpr::Binary(lhs, op, rhs) => {
+                let lhs = lhs.simplify();
+                let rhs = rhs.simplify();
+                match (op, lhs.as_bool_lit(), rhs.as_bool_lit()) {
+                    (BinOp::And(_), _, Some(true))
+                    | (BinOp::And(_), Some(false), _)
+                    | (BinOp::Or(_), _, Some(false))
+                    | (BinOp::Or(_), Some(true), _) => lhs,
+                    (BinOp::And(_), Some(true), _)
+                    | (BinOp::And(_), _, Some(false))
+                    | (BinOp::Or(_), Some(false), _)
+                    | (BinOp::Or(_), _, Some(true)) => rhs,
+                    _ => FilterExpr::Binary(Box::new(lhs), op.clone(), Box::new(rhs)),
+                }
+            }
+            FilterExpr::Parens(paren, inner) => {
+                let inner = inner.simplify();
+                match inner {
+                    FilterExpr::Path(_) | FilterExpr::Lit(_) | FilterExpr::Parens(..) => inner,
+                    _ => FilterExpr::Parens(paren.clone(), Box::new(inner)),
+                }
+            }
+            FilterExpr::Path(_) | FilterExpr::Lit(_) => self.clone(),
+        }
+    }
+}
+
 /// An unary operator in an expression
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum UnOp {
     /// `-`
     Neg(token::Dash),
@@ -528,6 +1372,7 @@ pub enum UnOp {
 
 /// A binary operator in an expression
 #[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BinOp {
     /// `&&`
     And(token::DoubleAnd),
@@ -0,0 +1,252 @@
+use super::*;
+use core::fmt;
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl fmt::Display for BoolLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl fmt::Display for NullLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "null")
+    }
+}
+
+impl fmt::Display for IntLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl fmt::Display for NonZeroIntLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+/// Write string content between the provided delimiters, escaping any delimiters or backslashes
+/// it contains
+fn write_delimited(f: &mut fmt::Formatter<'_>, content: &str, delimiter: char) -> fmt::Result {
+    write!(f, "{}", delimiter)?;
+    for c in content.chars() {
+        if c == delimiter || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "{}", delimiter)
+}
+
+impl fmt::Display for SingleStringLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_delimited(f, self.as_str(), '\'')
+    }
+}
+
+impl fmt::Display for DoubleStringLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_delimited(f, self.as_str(), '"')
+    }
+}
+
+impl fmt::Display for StringLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StringLit::Single(s) => write!(f, "{}", s),
+            StringLit::Double(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "$")?;
+        for seg in &self.segments {
+            write!(f, "{}", seg)?;
+        }
+        if self.tilde.is_some() {
+            write!(f, "~")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SubPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        for seg in &self.segments {
+            write!(f, "{}", seg)?;
+        }
+        if self.tilde.is_some() {
+            write!(f, "~")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PathKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathKind::Root(_) => write!(f, "$"),
+            PathKind::Relative(_) => write!(f, "@"),
+        }
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Dot(_, selector) => write!(f, ".{}", selector),
+            Segment::Bracket(_, selector) => write!(f, "{}", selector),
+            Segment::Recursive(_, Some(selector)) => write!(f, "..{}", selector),
+            Segment::Recursive(_, None) => write!(f, ".."),
+        }
+    }
+}
+
+impl fmt::Display for RawSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawSelector::Wildcard(_) => write!(f, "*"),
+            RawSelector::Parent(_) => write!(f, "^"),
+            RawSelector::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Write an optional bound of a range, which is left empty when missing
+fn write_bound(f: &mut fmt::Formatter<'_>, bound: Option<&impl fmt::Display>) -> fmt::Result {
+    match bound {
+        Some(bound) => write!(f, "{}", bound),
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for StepRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, self.start.as_ref())?;
+        write!(f, ":")?;
+        write_bound(f, self.end.as_ref())?;
+        write!(f, ":")?;
+        write_bound(f, self.step.as_ref())
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, self.start.as_ref())?;
+        write!(f, ":")?;
+        write_bound(f, self.end.as_ref())
+    }
+}
+
+impl fmt::Display for UnionComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnionComponent::StepRange(range) => write!(f, "{}", range),
+            UnionComponent::Range(range) => write!(f, "{}", range),
+            UnionComponent::Parent(_) => write!(f, "^"),
+            UnionComponent::Path(path) => write!(f, "{}", path),
+            UnionComponent::Filter(filter) => write!(f, "{}", filter),
+            UnionComponent::Literal(lit) => write!(f, "{}", lit),
+        }
+    }
+}
+
+impl fmt::Display for BracketSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        match self {
+            BracketSelector::Union(components) => {
+                for (idx, component) in components.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", component)?;
+                }
+            }
+            BracketSelector::StepRange(range) => write!(f, "{}", range)?,
+            BracketSelector::Range(range) => write!(f, "{}", range)?,
+            BracketSelector::Wildcard(_) => write!(f, "*")?,
+            BracketSelector::Parent(_) => write!(f, "^")?,
+            BracketSelector::Path(path) => write!(f, "{}", path)?,
+            BracketSelector::Filter(filter) => write!(f, "{}", filter)?,
+            BracketSelector::Literal(lit) => write!(f, "{}", lit)?,
+        }
+        write!(f, "]")
+    }
+}
+
+impl fmt::Display for BracketLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BracketLit::Int(i) => write!(f, "{}", i),
+            BracketLit::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "?({})", self.inner)
+    }
+}
+
+impl fmt::Display for ExprLit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprLit::Int(i) => write!(f, "{}", i),
+            ExprLit::String(s) => write!(f, "{}", s),
+            ExprLit::Bool(b) => write!(f, "{}", b),
+            ExprLit::Null(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterExpr::Unary(op, inner) => write!(f, "{}{}", op, inner),
+            FilterExpr::Binary(lhs, op, rhs) => write!(f, "{} {} {}", lhs, op, rhs),
+            FilterExpr::Path(path) => write!(f, "{}", path),
+            FilterExpr::Lit(lit) => write!(f, "{}", lit),
+            FilterExpr::Parens(_, inner) => write!(f, "({})", inner),
+        }
+    }
+}
+
+impl fmt::Display for UnOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnOp::Neg(_) => write!(f, "-"),
+            UnOp::Not(_) => write!(f, "!"),
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinOp::And(_) => "&&",
+            BinOp::Or(_) => "||",
+            BinOp::Eq(_) => "==",
+            BinOp::Le(_) => "<=",
+            BinOp::Lt(_) => "<",
+            BinOp::Gt(_) => ">",
+            BinOp::Ge(_) => ">=",
+            BinOp::Add(_) => "+",
+            BinOp::Sub(_) => "-",
+            BinOp::Mul(_) => "*",
+            BinOp::Div(_) => "/",
+            BinOp::Rem(_) => "%",
+        };
+        write!(f, "{}", op)
+    }
+}
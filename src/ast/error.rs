@@ -1,9 +1,10 @@
 use super::Span;
 
+use core::fmt;
 use std::collections::BTreeSet;
 
 /// The cause of a parse failure
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum FailReason<I> {
     /// An unexpected token at a span
     Unexpected(Span),
@@ -23,6 +24,25 @@ pub enum FailReason<I> {
 }
 
 impl<I> FailReason<I> {
+    /// Get the primary source span of this failure reason. For multiple merged reasons, this is
+    /// the span covering all of them
+    ///
+    /// # Panics
+    ///
+    /// - If this is a [`FailReason::MultiReason`] containing no reasons
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            FailReason::Unexpected(span) | FailReason::Custom(span, _) => *span,
+            FailReason::Unclosed { found_span, .. } => *found_span,
+            FailReason::MultiReason(reasons) => reasons
+                .iter()
+                .map(FailReason::span)
+                .reduce(Span::join)
+                .expect("MultiReason should contain at least one reason"),
+        }
+    }
+
     fn into_vec(self) -> Vec<FailReason<I>> {
         match self {
             FailReason::MultiReason(v) => v,
@@ -31,8 +51,41 @@ impl<I> FailReason<I> {
     }
 }
 
+impl<I: fmt::Display> fmt::Display for FailReason<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailReason::Unexpected(span) => {
+                write!(f, "Unexpected input at {}..{}", span.start(), span.end())
+            }
+            FailReason::Unclosed {
+                unclosed_span,
+                delimiter,
+                ..
+            } => write!(
+                f,
+                "Unclosed delimiter '{}' at {}..{}",
+                delimiter,
+                unclosed_span.start(),
+                unclosed_span.end()
+            ),
+            FailReason::Custom(span, msg) => {
+                write!(f, "{} at {}..{}", msg, span.start(), span.end())
+            }
+            FailReason::MultiReason(reasons) => {
+                for (idx, reason) in reasons.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// A single parse failure error
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ParseFail<I: Ord, L> {
     reason: FailReason<I>,
     expected: BTreeSet<Option<I>>,
@@ -55,6 +108,45 @@ impl<I: Ord, L> ParseFail<I, L> {
     pub fn reason(&self) -> &FailReason<I> {
         &self.reason
     }
+
+    /// Get the source span of this parse failure
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.reason.span()
+    }
+
+    /// Get the set of inputs which would have been valid at the failure location. `None`
+    /// represents the end of input
+    pub fn expected(&self) -> &BTreeSet<Option<I>> {
+        &self.expected
+    }
+
+    /// Get the input which was found at the failure location, or `None` if the end of input was
+    /// reached
+    pub fn found(&self) -> Option<&I> {
+        self.found.as_ref()
+    }
+
+    /// Convert an unexpected input failure caused by a missing closing delimiter into an unclosed
+    /// delimiter failure, pointing at the opening delimiter
+    pub(crate) fn into_unclosed(self, unclosed_span: Span, delimiter: I, end: &I) -> Self {
+        match self.reason {
+            FailReason::Unexpected(found_span)
+                if found_span.start() > unclosed_span.start()
+                    && self.expected.iter().any(|e| e.as_ref() == Some(end)) =>
+            {
+                ParseFail {
+                    reason: FailReason::Unclosed {
+                        found_span,
+                        unclosed_span,
+                        delimiter,
+                    },
+                    ..self
+                }
+            }
+            _ => self,
+        }
+    }
 }
 
 impl<I: Ord, L> chumsky::Error<I> for ParseFail<I, L> {
@@ -119,3 +211,29 @@ impl<I: Ord, L> chumsky::Error<I> for ParseFail<I, L> {
         }
     }
 }
+
+impl<I: Ord + fmt::Display, L> fmt::Display for ParseFail<I, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)?;
+
+        match &self.found {
+            Some(found) => write!(f, ", found '{}'", found)?,
+            None => write!(f, ", found end of input")?,
+        }
+
+        if !self.expected.is_empty() {
+            write!(f, ", expected one of ")?;
+            for (idx, expected) in self.expected.iter().enumerate() {
+                if idx != 0 {
+                    write!(f, ", ")?;
+                }
+                match expected {
+                    Some(expected) => write!(f, "'{}'", expected)?,
+                    None => write!(f, "end of input")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
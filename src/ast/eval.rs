@@ -1,50 +1,118 @@
+use super::visit::{walk_bracket_selector, walk_path, walk_union_component, Visit};
 use super::*;
-use crate::eval::EvalCtx;
+use crate::error::{EvalError, EvalErrorKind, JsonTy};
+use crate::eval::{flatten_recur, EvalCtx};
+use core::cmp::Ordering;
+use core::iter::{Rev, StepBy};
+use core::ops::ControlFlow;
 use either::Either;
 use std::borrow::Cow;
 
-use crate::utils::ValueExt;
-use serde_json::Value;
+use crate::json::{Json, JsonArray, JsonNumber, JsonObject, JsonRef};
 
-fn flatten_recur<'a>(collect: &mut Vec<&'a Value>, a: &'a Value) {
-    collect.push(a);
-    a.iter().for_each(|a| flatten_recur(collect, a));
+/// Visitor which looks for any parent selector, including inside sub-paths and filters
+#[derive(Default)]
+struct ParentFinder(bool);
+
+impl Visit<'_> for ParentFinder {
+    fn visit_raw_selector(&mut self, node: &RawSelector) {
+        self.0 |= matches!(node, RawSelector::Parent(_));
+    }
+
+    fn visit_bracket_selector(&mut self, node: &BracketSelector) {
+        self.0 |= matches!(node, BracketSelector::Parent(_));
+        walk_bracket_selector(node, self);
+    }
+
+    fn visit_union_component(&mut self, node: &UnionComponent) {
+        self.0 |= matches!(node, UnionComponent::Parent(_));
+        walk_union_component(node, self);
+    }
 }
 
 impl Path {
     pub(crate) fn has_parent(&self) -> bool {
-        for op in &self.segments {
-            let result = match op {
-                Segment::Dot(_, RawSelector::Parent(_))
-                | Segment::Recursive(_, Some(RawSelector::Parent(_)))
-                | Segment::Bracket(_, BracketSelector::Parent(_)) => true,
-                Segment::Bracket(_, BracketSelector::Path(p)) => p.has_parent(),
-                Segment::Bracket(_, BracketSelector::Filter(f)) => f.has_parent(),
-                _ => false,
-            };
-
-            if result {
-                return true;
-            }
-        }
-        false
+        let mut finder = ParentFinder::default();
+        walk_path(self, &mut finder);
+        finder.0
     }
 
-    pub(crate) fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    pub(crate) fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         for op in &self.segments {
             op.eval(ctx);
         }
+        // Tilde at the top level isn't yet supported due to API design questions, so it matches
+        // nothing
+        if let Some(_tilde) = &self.tilde {
+            let err = EvalError::from(EvalErrorKind::Unsupported("tilde at the top level"));
+            #[cfg(feature = "spanned")]
+            let err = err.with_span(_tilde.span());
+            ctx.record_error(err);
+            ctx.set_matched(Vec::new());
+        }
+    }
+
+    /// Call the provided function on each match of this path in the same order as
+    /// `Path::eval`, stopping early if it breaks. Segments after the last union map each item
+    /// independently, so they're evaluated depth-first one item at a time, and each match is
+    /// passed on as soon as it's found. Unions group their results by component across every
+    /// item, so the segments up to and including the last one are evaluated in full
+    pub(crate) fn eval_each<'a, 'b, T: Json, B>(
+        &self,
+        ctx: &mut EvalCtx<'a, 'b, T>,
+        mut f: impl FnMut(&EvalCtx<'a, 'b, T>, &'a T) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
         if self.tilde.is_some() {
-            unimplemented!(
-                "Tilde at the top level isn't yet supported due to API design questions. Please \
-                raise an issue with your use case"
-            )
+            self.eval(ctx);
+            return ControlFlow::Continue(());
+        }
+
+        let split = self
+            .segments
+            .iter()
+            .rposition(Segment::is_union)
+            .map_or(0, |idx| idx + 1);
+        let (full, lazy) = self.segments.split_at(split);
+        for op in full {
+            op.eval(ctx);
+        }
+        let matched = ctx.get_matched().to_vec();
+        each_match(ctx, lazy, matched, &mut f)
+    }
+
+    /// Find the first match of this path, without evaluating any more of the value than needed
+    pub(crate) fn eval_first<'a, T: Json>(&self, ctx: &mut EvalCtx<'a, '_, T>) -> Option<&'a T> {
+        match self.eval_each(ctx, |_, item| ControlFlow::Break(item)) {
+            ControlFlow::Break(item) => Some(item),
+            ControlFlow::Continue(()) => None,
         }
     }
 }
 
+fn each_match<'a, 'b, T: Json, B>(
+    ctx: &mut EvalCtx<'a, 'b, T>,
+    segments: &[Segment],
+    matched: Vec<&'a T>,
+    f: &mut impl FnMut(&EvalCtx<'a, 'b, T>, &'a T) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    let Some((op, rest)) = segments.split_first() else {
+        return matched.into_iter().try_for_each(|item| f(ctx, item));
+    };
+    for item in matched {
+        ctx.set_matched(vec![item]);
+        op.eval(ctx);
+        let next = ctx.get_matched().to_vec();
+        each_match(ctx, rest, next, f)?;
+    }
+    ControlFlow::Continue(())
+}
+
 impl Segment {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn is_union(&self) -> bool {
+        matches!(self, Segment::Bracket(_, BracketSelector::Union(_)))
+    }
+
+    pub(crate) fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         match self {
             Segment::Dot(_, op) => op.eval(ctx),
             Segment::Bracket(_, op) => op.eval(ctx),
@@ -63,91 +131,160 @@ impl Segment {
 }
 
 impl RawSelector {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         match self {
             RawSelector::Wildcard(_) => ctx.apply_matched(|_, a| a.iter()),
             RawSelector::Parent(_) => {
                 ctx.apply_matched(|ctx, a| ctx.parent_of(a));
             }
-            RawSelector::Name(name) => ctx.apply_matched(|_, a| match a {
-                Value::Object(m) => m.get(name.as_str()),
+            RawSelector::Name(name) => ctx.apply_matched(|_, a| match a.as_ref() {
+                JsonRef::Object(m) => m.get(name.as_str()),
                 _ => None,
             }),
         }
     }
 }
 
+fn as_bool<T: Json>(val: &T) -> Result<bool, EvalErrorKind> {
+    match val.as_ref() {
+        JsonRef::Bool(b) => Ok(b),
+        _ => Err(EvalErrorKind::mismatched(JsonTy::Bool, val)),
+    }
+}
+
+fn as_number<T: Json>(val: &T) -> Result<JsonNumber, EvalErrorKind> {
+    match val.as_ref() {
+        JsonRef::Number(n) => Ok(n),
+        _ => Err(EvalErrorKind::mismatched(JsonTy::Number, val)),
+    }
+}
+
+fn as_f64<T: Json>(val: &T) -> Result<f64, EvalErrorKind> {
+    as_number(val).map(JsonNumber::as_f64)
+}
+
+/// Compare two numbers, exactly if both are integers
+fn compare<T: Json>(lhs: &T, rhs: &T) -> Result<Option<Ordering>, EvalErrorKind> {
+    Ok(as_number(lhs)?.partial_cmp(&as_number(rhs)?))
+}
+
+/// Apply an arithmetic operation, using checked integer arithmetic if both sides are integers
+/// and falling back to floating point otherwise. Integers too large for an `i64` use unsigned
+/// arithmetic, so they keep their exact value
+fn arith<'a, T: Json>(
+    lhs: &T,
+    rhs: &T,
+    int_op: impl FnOnce(i64, i64) -> Option<i64>,
+    uint_op: impl FnOnce(u64, u64) -> Option<u64>,
+    float_op: impl FnOnce(f64, f64) -> f64,
+) -> Result<Cow<'a, T>, EvalError> {
+    let lhs = as_number(lhs)?;
+    let rhs = as_number(rhs)?;
+
+    if let Some((lhs, rhs)) = lhs.as_i64().zip(rhs.as_i64()) {
+        int_op(lhs, rhs)
+            .map(|i| Cow::Owned(T::from_i64(i)))
+            .ok_or_else(|| EvalErrorKind::Overflow.into())
+    } else if let Some((lhs, rhs)) = lhs.as_u64().zip(rhs.as_u64()) {
+        uint_op(lhs, rhs)
+            .map(|u| Cow::Owned(T::from_u64(u)))
+            .ok_or_else(|| EvalErrorKind::Overflow.into())
+    } else {
+        Ok(Cow::Owned(T::from_f64(float_op(
+            lhs.as_f64(),
+            rhs.as_f64(),
+        ))))
+    }
+}
+
 fn step_handle(val: i64) -> (bool, usize) {
+    let step = usize::try_from(val.unsigned_abs()).unwrap_or(usize::MAX);
+    (val < 0, step)
+}
+
+/// Resolve a possibly negative index against an array length. Negative indices count back from
+/// the end, and are `None` if they reach past the start. Indices too large for a `usize` saturate
+/// rather than wrapping, so they stay past the end of any array
+fn idx_handle(val: i64, len: usize) -> Option<usize> {
     if val < 0 {
-        (true, val.abs() as usize)
+        usize::try_from(val.unsigned_abs())
+            .ok()
+            .and_then(|back| len.checked_sub(back))
     } else {
-        (false, val as usize)
+        Some(usize::try_from(val).unwrap_or(usize::MAX))
     }
 }
 
-fn idx_handle(val: i64, slice: &[Value]) -> Option<usize> {
-    if val < 0 {
-        slice.len().checked_sub(val.abs() as usize)
+fn range_bounds(len: usize, start: i64, end: i64) -> core::ops::Range<usize> {
+    let start = idx_handle(start, len).unwrap_or(0);
+    let end = idx_handle(end, len).unwrap_or(0);
+
+    if start > end || start > len {
+        0..0
     } else {
-        Some(val as usize)
+        start..usize::min(end, len)
     }
 }
 
-fn range(slice: &[Value], start: usize, end: usize) -> &[Value] {
-    if start > end || start > slice.len() {
-        &[]
-    } else if end >= slice.len() {
-        &slice[start..]
+/// Bounds for a slice with a negative step, which walks down from `start` to just above `end`.
+/// The returned range is the selected span in ascending order, to be iterated in reverse
+fn rev_range_bounds(len: usize, start: Option<i64>, end: Option<i64>) -> core::ops::Range<usize> {
+    // Both bounds are shifted up by one, so an end before the first item is represented by zero
+    let bound = |val: i64| idx_handle(val, len).map_or(0, |i| usize::min(i.saturating_add(1), len));
+    let upper = start.map_or(len, bound);
+    let lower = end.map_or(0, bound);
+
+    if lower > upper {
+        0..0
     } else {
-        &slice[start..end]
+        lower..upper
     }
 }
 
+type StepIndices = Either<StepBy<Rev<core::ops::Range<usize>>>, StepBy<core::ops::Range<usize>>>;
+
 impl StepRange {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
-        let start = self.start.as_ref().map_or(0, |i| i.as_int());
-        let end = self.end.as_ref().map_or(i64::MAX, |i| i.as_int());
+    pub(crate) fn indices(&self, len: usize) -> StepIndices {
+        let start = self.start.as_ref().map(|i| i.as_int());
+        let end = self.end.as_ref().map(|i| i.as_int());
         let step = self.step.as_ref().map_or(1, |i| i.as_int().get());
 
         let (rev, step) = step_handle(step);
 
-        ctx.apply_matched(|_, a| match a {
-            Value::Array(v) => {
-                let start = idx_handle(start, v).unwrap_or(0);
-                let end = idx_handle(end, v).unwrap_or(0);
-
-                let iter = range(v, start, end).iter();
+        if rev {
+            Either::Left(rev_range_bounds(len, start, end).rev().step_by(step))
+        } else {
+            let bounds = range_bounds(len, start.unwrap_or(0), end.unwrap_or(i64::MAX));
+            Either::Right(bounds.step_by(step))
+        }
+    }
 
-                if rev {
-                    Either::Left(iter.rev().step_by(step))
-                } else {
-                    Either::Right(iter.step_by(step))
-                }
-            }
-            _ => Either::Right([].iter().step_by(1)),
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
+        ctx.apply_matched(|_, a| match a.as_ref() {
+            JsonRef::Array(v) => Either::Left(self.indices(v.len()).filter_map(|i| v.get(i))),
+            _ => Either::Right(core::iter::empty()),
         });
     }
 }
 
 impl Range {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    pub(crate) fn indices(&self, len: usize) -> core::ops::Range<usize> {
         let start = self.start.as_ref().map_or(0, |i| i.as_int());
         let end = self.end.as_ref().map_or(i64::MAX, |i| i.as_int());
 
-        ctx.apply_matched(|_, a| match a {
-            Value::Array(v) => {
-                let start = idx_handle(start, v).unwrap_or(0);
-                let end = idx_handle(end, v).unwrap_or(0);
+        range_bounds(len, start, end)
+    }
 
-                range(v, start, end)
-            }
-            _ => &[],
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
+        ctx.apply_matched(|_, a| match a.as_ref() {
+            JsonRef::Array(v) => Either::Left(self.indices(v.len()).filter_map(|i| v.get(i))),
+            _ => Either::Right(core::iter::empty()),
         });
     }
 }
 
 impl UnionComponent {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         match self {
             UnionComponent::StepRange(step_range) => step_range.eval(ctx),
             UnionComponent::Range(range) => range.eval(ctx),
@@ -168,9 +305,11 @@ impl UnionComponent {
 }
 
 impl BracketSelector {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         match self {
             BracketSelector::Union(components) => {
+                // Results are grouped by component in source order, which is part of the
+                // documented behavior of unions
                 let mut new_matched = Vec::new();
                 let old_matched = ctx.get_matched().to_owned();
                 for component in components {
@@ -200,14 +339,14 @@ impl BracketSelector {
 }
 
 impl BracketLit {
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         match self {
-            BracketLit::Int(i) => ctx.apply_matched(|_, a| match a {
-                Value::Array(v) => idx_handle(i.as_int(), v).and_then(|idx| v.get(idx)),
+            BracketLit::Int(i) => ctx.apply_matched(|_, a| match a.as_ref() {
+                JsonRef::Array(v) => idx_handle(i.as_int(), v.len()).and_then(|idx| v.get(idx)),
                 _ => None,
             }),
-            BracketLit::String(s) => ctx.apply_matched(|_, a| match a {
-                Value::Object(m) => m.get(s.as_str()),
+            BracketLit::String(s) => ctx.apply_matched(|_, a| match a.as_ref() {
+                JsonRef::Object(m) => m.get(s.as_str()),
                 _ => None,
             }),
         }
@@ -215,25 +354,11 @@ impl BracketLit {
 }
 
 impl SubPath {
-    pub(crate) fn has_parent(&self) -> bool {
-        for op in &self.segments {
-            let result = match op {
-                Segment::Dot(_, RawSelector::Parent(_))
-                | Segment::Recursive(_, Some(RawSelector::Parent(_)))
-                | Segment::Bracket(_, BracketSelector::Parent(_)) => true,
-                Segment::Bracket(_, BracketSelector::Path(p)) => p.has_parent(),
-                Segment::Bracket(_, BracketSelector::Filter(f)) => f.has_parent(),
-                _ => false,
-            };
-
-            if result {
-                return true;
-            }
-        }
-        false
-    }
-
-    fn eval_expr<'a>(&self, ctx: &EvalCtx<'a, '_>, a: &'a Value) -> Option<Cow<'a, Value>> {
+    fn eval_expr<'a, T: Json>(
+        &self,
+        ctx: &EvalCtx<'a, '_, T>,
+        a: &'a T,
+    ) -> Result<Cow<'a, T>, EvalError> {
         let relative = match self.kind {
             PathKind::Root(_) => false,
             PathKind::Relative(_) => true,
@@ -241,26 +366,30 @@ impl SubPath {
 
         let new_root = if relative { a } else { ctx.root() };
 
-        let mut new_ctx = EvalCtx::new_parents(new_root, ctx.all_parents());
+        let mut new_ctx = ctx.child(new_root);
         for op in &self.segments {
             op.eval(&mut new_ctx);
         }
+        if let Some(err) = new_ctx.take_error() {
+            return Err(err);
+        }
         let matched = new_ctx.into_matched();
 
-        if matched.len() == 1 {
-            let matched = if self.tilde.is_some() {
-                Cow::Owned(ctx.idx_of(matched[0])?.into())
-            } else {
-                Cow::Borrowed(matched[0])
-            };
-
-            Some(matched)
-        } else {
-            None
+        match matched.len() {
+            0 => Err(EvalErrorKind::MatchedNone.into()),
+            1 => {
+                if self.tilde.is_some() {
+                    let id = ctx.idx_of(matched[0]).ok_or(EvalErrorKind::NoId)?;
+                    Ok(Cow::Owned(T::from_idx(id)))
+                } else {
+                    Ok(Cow::Borrowed(matched[0]))
+                }
+            }
+            len => Err(EvalErrorKind::MatchedMany(len).into()),
         }
     }
 
-    fn eval_match(&self, ctx: &mut EvalCtx<'_, '_>) {
+    fn eval_match<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         let relative = match self.kind {
             PathKind::Root(_) => false,
             PathKind::Relative(_) => true,
@@ -269,10 +398,13 @@ impl SubPath {
         ctx.set_matched(ctx.apply_matched_ref(|ctx, a| {
             let new_root = if relative { a } else { ctx.root() };
 
-            let mut new_ctx = EvalCtx::new_parents(new_root, ctx.all_parents());
+            let mut new_ctx = ctx.child(new_root);
             for op in &self.segments {
                 op.eval(&mut new_ctx);
             }
+            if let Some(err) = new_ctx.take_error() {
+                ctx.record_error(err);
+            }
 
             let id = self.tilde.is_some();
 
@@ -281,82 +413,128 @@ impl SubPath {
                 .into_iter()
                 .map(move |a| {
                     if id {
-                        Cow::Owned(ctx.idx_of(a).unwrap().into())
+                        Cow::Owned(T::from_idx(ctx.idx_of(a).unwrap()))
                     } else {
                         Cow::Borrowed(a)
                     }
                 })
-                .flat_map(move |mat| match a {
-                    Value::Array(v) => {
-                        let idx = match &*mat {
-                            Value::Number(n) => idx_handle(n.as_i64().unwrap(), v),
+                .flat_map(move |mat| match a.as_ref() {
+                    JsonRef::Array(v) => {
+                        let idx = match (*mat).as_ref() {
+                            JsonRef::Number(n) => n.as_i64().and_then(|i| idx_handle(i, v.len())),
                             _ => None,
                         };
                         idx.and_then(|i| v.get(i))
                     }
-                    Value::Object(m) => {
-                        let idx = match &*mat {
-                            Value::String(s) => Some(s.to_string()),
-                            Value::Number(n) => Some(n.to_string()),
-                            _ => None,
-                        };
-
-                        idx.and_then(|i| m.get(&i))
-                    }
+                    JsonRef::Object(m) => match (*mat).as_ref() {
+                        JsonRef::String(s) => m.get(&s),
+                        JsonRef::Number(n) => m.get(&n.to_string()),
+                        _ => None,
+                    },
                     _ => None,
                 })
         }));
     }
 }
 
+impl SubPath {
+    /// Evaluate this sub-path as an expression, as it would be inside a filter. Relative paths
+    /// start at `current`, while root paths start at `root`. Parent and tilde selectors resolve
+    /// relative to `root`, so `current` should be an item inside it. The sub-path must match
+    /// exactly one item
+    ///
+    /// # Errors
+    ///
+    /// - If the sub-path matches no items, or more than one item
+    /// - If a tilde is used on an item with no index
+    /// - If a nested filter fails to evaluate
+    pub fn eval_against<'a, T: Json>(
+        &self,
+        root: &'a T,
+        current: &'a T,
+    ) -> Result<Cow<'a, T>, EvalError> {
+        let mut ctx = EvalCtx::new(root);
+        ctx.prepopulate_parents();
+        self.eval_expr(&ctx, current)
+    }
+}
+
 impl Filter {
-    fn has_parent(&self) -> bool {
-        self.inner.has_parent()
+    /// Test whether a single value passes this filter, with `root` being the value `$` refers
+    /// to. A filter which evaluates to anything other than `true` doesn't pass
+    ///
+    /// # Errors
+    ///
+    /// - If the filter expression fails to evaluate, such as by comparing a number to a string
+    pub fn test_value<'a, T: Json>(&self, root: &'a T, current: &'a T) -> Result<bool, EvalError> {
+        let mut ctx = EvalCtx::new(root);
+        ctx.prepopulate_parents();
+        let out = self.inner.eval_expr(&ctx, current)?;
+        Ok(matches!((*out).as_ref(), JsonRef::Bool(true)))
     }
+}
 
-    fn eval(&self, ctx: &mut EvalCtx<'_, '_>) {
+impl Filter {
+    fn eval<T: Json>(&self, ctx: &mut EvalCtx<'_, '_, T>) {
         ctx.set_matched(ctx.apply_matched_ref(|ctx, a| {
-            a.iter().filter(|&a| {
-                self.inner
-                    .eval_expr(ctx, a)
-                    .map_or(false, |c| c.as_bool() == Some(true))
+            a.iter().filter(|&a| match self.inner.eval_expr(ctx, a) {
+                Ok(c) => matches!((*c).as_ref(), JsonRef::Bool(true)),
+                Err(err) => {
+                    let options = ctx.options();
+                    if options.is_strict_filter_errors()
+                        || (options.is_strict_arithmetic() && err.is_arithmetic())
+                    {
+                        ctx.record_error(err);
+                    }
+                    false
+                }
             })
         }));
     }
 }
 
 impl FilterExpr {
-    fn has_parent(&self) -> bool {
+    /// The span to blame for an error raised directly by this expression - the operator for
+    /// unary and binary expressions, otherwise the whole expression
+    #[cfg(feature = "spanned")]
+    fn error_span(&self) -> Span {
         match self {
-            FilterExpr::Unary(_, inner) => inner.has_parent(),
-            FilterExpr::Binary(left, _, right) => left.has_parent() || right.has_parent(),
-            FilterExpr::Parens(_, inner) => inner.has_parent(),
-            FilterExpr::Path(p) => p.has_parent(),
-            _ => false,
+            FilterExpr::Unary(op, _) => op.span(),
+            FilterExpr::Binary(_, op, _) => op.span(),
+            _ => self.span(),
         }
     }
 
-    fn eval_expr<'a>(&self, ctx: &EvalCtx<'a, '_>, val: &'a Value) -> Option<Cow<'a, Value>> {
+    pub(crate) fn eval_expr<'a, T: Json>(
+        &self,
+        ctx: &EvalCtx<'a, '_, T>,
+        val: &'a T,
+    ) -> Result<Cow<'a, T>, EvalError> {
+        let out = self.eval_expr_inner(ctx, val);
+        #[cfg(feature = "spanned")]
+        let out = out.map_err(|err| err.with_span(self.error_span()));
+        out
+    }
+
+    fn eval_expr_inner<'a, T: Json>(
+        &self,
+        ctx: &EvalCtx<'a, '_, T>,
+        val: &'a T,
+    ) -> Result<Cow<'a, T>, EvalError> {
         match self {
             FilterExpr::Unary(op, inner) => {
                 let inner = inner.eval_expr(ctx, val)?;
 
                 match op {
-                    UnOp::Neg(_) => match &*inner {
-                        Value::Number(n) => {
-                            let out = n
-                                .as_i64()
-                                .map(|i| Value::from(-i))
-                                .or_else(|| n.as_u64().map(|i| Value::from(-(i as i64))))
-                                .or_else(|| n.as_f64().map(|f| Value::from(-f)));
-                            Some(Cow::Owned(out.unwrap()))
-                        }
-                        _ => None,
-                    },
-                    UnOp::Not(_) => match &*inner {
-                        Value::Bool(b) => Some(Cow::Owned(Value::from(!b))),
-                        _ => None,
-                    },
+                    UnOp::Neg(_) => {
+                        let n = as_number(&*inner)?;
+                        let out = n
+                            .as_i64()
+                            .and_then(i64::checked_neg)
+                            .map_or_else(|| T::from_f64(-n.as_f64()), T::from_i64);
+                        Ok(Cow::Owned(out))
+                    }
+                    UnOp::Not(_) => Ok(Cow::Owned(T::from_bool(!as_bool(&*inner)?))),
                 }
             }
             FilterExpr::Binary(lhs, op, rhs) => {
@@ -365,89 +543,96 @@ impl FilterExpr {
 
                 match op {
                     BinOp::And(_) => {
-                        let lhs = lhs.as_bool()?;
-                        let rhs = rhs.as_bool()?;
-                        Some(Cow::Owned(Value::Bool(lhs && rhs)))
+                        let lhs = as_bool(&*lhs)?;
+                        let rhs = as_bool(&*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(lhs && rhs)))
                     }
                     BinOp::Or(_) => {
-                        let lhs = lhs.as_bool()?;
-                        let rhs = rhs.as_bool()?;
-                        Some(Cow::Owned(Value::Bool(lhs || rhs)))
+                        let lhs = as_bool(&*lhs)?;
+                        let rhs = as_bool(&*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(lhs || rhs)))
                     }
 
-                    BinOp::Eq(_) => Some(Cow::Owned(Value::Bool(lhs == rhs))),
+                    BinOp::Eq(_) => Ok(Cow::Owned(T::from_bool(lhs.json_eq(&rhs)))),
                     BinOp::Le(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::Bool(lhs <= rhs)))
+                        let ord = compare(&*lhs, &*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(matches!(
+                            ord,
+                            Some(Ordering::Less | Ordering::Equal)
+                        ))))
                     }
                     BinOp::Lt(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::Bool(lhs < rhs)))
+                        let ord = compare(&*lhs, &*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(matches!(
+                            ord,
+                            Some(Ordering::Less)
+                        ))))
                     }
                     BinOp::Gt(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::Bool(lhs > rhs)))
+                        let ord = compare(&*lhs, &*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(matches!(
+                            ord,
+                            Some(Ordering::Greater)
+                        ))))
                     }
                     BinOp::Ge(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::Bool(lhs >= rhs)))
+                        let ord = compare(&*lhs, &*rhs)?;
+                        Ok(Cow::Owned(T::from_bool(matches!(
+                            ord,
+                            Some(Ordering::Greater | Ordering::Equal)
+                        ))))
                     }
 
-                    BinOp::Add(_) => {
-                        if lhs.is_f64() && rhs.is_f64() {
-                            let lhs = lhs.as_f64()?;
-                            let rhs = rhs.as_f64()?;
-
-                            Some(Cow::Owned(Value::from(lhs + rhs)))
-                        } else if lhs.is_string() && rhs.is_string() {
-                            let lhs = lhs.as_str()?;
-                            let rhs = rhs.as_str()?;
-
-                            Some(Cow::Owned(Value::String(format!("{lhs}{rhs}"))))
-                        } else {
-                            None
+                    BinOp::Add(_) => match ((*lhs).as_ref(), (*rhs).as_ref()) {
+                        (JsonRef::String(lhs), JsonRef::String(rhs)) => {
+                            Ok(Cow::Owned(T::from_string(format!("{lhs}{rhs}"))))
                         }
-                    }
+                        _ => arith(&*lhs, &*rhs, i64::checked_add, u64::checked_add, |l, r| {
+                            l + r
+                        }),
+                    },
                     BinOp::Sub(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::from(lhs - rhs)))
+                        arith(&*lhs, &*rhs, i64::checked_sub, u64::checked_sub, |l, r| {
+                            l - r
+                        })
                     }
                     BinOp::Mul(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
-
-                        Some(Cow::Owned(Value::from(lhs * rhs)))
+                        arith(&*lhs, &*rhs, i64::checked_mul, u64::checked_mul, |l, r| {
+                            l * r
+                        })
                     }
                     BinOp::Div(_) => {
-                        let lhs = lhs.as_f64()?;
-                        let rhs = rhs.as_f64()?;
+                        let lhs = as_f64(&*lhs)?;
+                        let rhs = as_f64(&*rhs)?;
 
-                        Some(Cow::Owned(Value::from(lhs / rhs)))
+                        Ok(Cow::Owned(T::from_f64(lhs / rhs)))
                     }
                     BinOp::Rem(_) => {
-                        let lhs = lhs.as_i64()?;
-                        let rhs = rhs.as_i64()?;
+                        let lhs = as_number(&*lhs)?;
+                        let rhs = as_number(&*rhs)?;
+
+                        if rhs.as_u64() == Some(0) {
+                            return Err(EvalErrorKind::DivideByZero.into());
+                        }
 
-                        Some(Cow::Owned(Value::from(lhs % rhs)))
+                        if let Some((lhs, rhs)) = lhs.as_i64().zip(rhs.as_i64()) {
+                            lhs.checked_rem(rhs)
+                                .map(|i| Cow::Owned(T::from_i64(i)))
+                                .ok_or_else(|| EvalErrorKind::Overflow.into())
+                        } else if let Some((lhs, rhs)) = lhs.as_u64().zip(rhs.as_u64()) {
+                            Ok(Cow::Owned(T::from_u64(lhs % rhs)))
+                        } else {
+                            Err(EvalErrorKind::OutOfRange.into())
+                        }
                     }
                 }
             }
             FilterExpr::Path(path) => path.eval_expr(ctx, val),
-            FilterExpr::Lit(lit) => Some(Cow::Owned(match lit {
-                ExprLit::Int(i) => Value::from(i.as_int()),
-                ExprLit::String(s) => Value::from(s.as_str()),
-                ExprLit::Bool(b) => Value::from(b.as_bool()),
-                ExprLit::Null(_) => Value::Null,
+            FilterExpr::Lit(lit) => Ok(Cow::Owned(match lit {
+                ExprLit::Int(i) => T::from_i64(i.as_int()),
+                ExprLit::String(s) => T::from_string(s.as_str().to_string()),
+                ExprLit::Bool(b) => T::from_bool(b.as_bool()),
+                ExprLit::Null(_) => T::null(),
             })),
             FilterExpr::Parens(_, inner) => inner.eval_expr(ctx, val),
         }
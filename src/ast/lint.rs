@@ -0,0 +1,222 @@
+use super::*;
+use crate::warning::{CompileWarning, WarningKind};
+
+#[cfg(feature = "spanned")]
+fn warn(kind: WarningKind, message: &str, item: &impl Spanned) -> CompileWarning {
+    CompileWarning::new(kind, message, item.span())
+}
+
+#[cfg(not(feature = "spanned"))]
+fn warn<T>(kind: WarningKind, message: &str, _item: &T) -> CompileWarning {
+    CompileWarning::new(kind, message)
+}
+
+/// Whether a slice with the given bounds and a positive step can never select anything,
+/// regardless of the length of the array
+fn is_empty_slice(start: Option<i64>, end: Option<i64>) -> bool {
+    match (start, end) {
+        (Some(start), Some(end)) => (start < 0) == (end < 0) && start >= end,
+        _ => false,
+    }
+}
+
+fn lint_segments(segments: &[Segment], out: &mut Vec<CompileWarning>) {
+    for (idx, segment) in segments.iter().enumerate() {
+        if let (Segment::Recursive(_, None), Some(next @ Segment::Recursive(..))) =
+            (segment, segments.get(idx + 1))
+        {
+            out.push(warn(
+                WarningKind::RedundantRecursive,
+                "Recursive descent is immediately followed by another recursive descent",
+                next,
+            ));
+        }
+        segment.lint(out);
+    }
+}
+
+impl Path {
+    pub(crate) fn lint(&self, out: &mut Vec<CompileWarning>) {
+        lint_segments(&self.segments, out);
+    }
+}
+
+impl SubPath {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        lint_segments(&self.segments, out);
+    }
+}
+
+impl Segment {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        if let Segment::Bracket(_, selector) = self {
+            selector.lint(out);
+        }
+    }
+}
+
+impl StepRange {
+    /// Whether this range can never select anything, regardless of the length of the array
+    pub(crate) fn is_always_empty(&self) -> bool {
+        let positive = self.step().is_none_or(|step| step.get() > 0);
+        positive && is_empty_slice(self.start(), self.end())
+    }
+
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        if self.is_always_empty() {
+            out.push(warn(
+                WarningKind::EmptySlice,
+                "Slice start is not before its end, so it will never select any items",
+                self,
+            ));
+        }
+    }
+}
+
+impl Range {
+    /// Whether this range can never select anything, regardless of the length of the array
+    pub(crate) fn is_always_empty(&self) -> bool {
+        is_empty_slice(self.start(), self.end())
+    }
+
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        if self.is_always_empty() {
+            out.push(warn(
+                WarningKind::EmptySlice,
+                "Slice start is not before its end, so it will never select any items",
+                self,
+            ));
+        }
+    }
+}
+
+impl UnionComponent {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        match self {
+            UnionComponent::StepRange(step_range) => step_range.lint(out),
+            UnionComponent::Range(range) => range.lint(out),
+            UnionComponent::Path(path) => path.lint(out),
+            UnionComponent::Filter(filter) => filter.lint(out),
+            UnionComponent::Parent(_) | UnionComponent::Literal(_) => (),
+        }
+    }
+}
+
+impl BracketSelector {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        match self {
+            BracketSelector::Union(components) => {
+                for (idx, component) in components.iter().enumerate() {
+                    if let UnionComponent::Literal(lit) = component {
+                        let duplicate = components[..idx].iter().any(|prev| match prev {
+                            UnionComponent::Literal(prev) => prev.same_value(lit),
+                            _ => false,
+                        });
+                        if duplicate {
+                            out.push(warn(
+                                WarningKind::DuplicateUnion,
+                                "Union contains the same literal more than once",
+                                lit,
+                            ));
+                        }
+                    }
+                    component.lint(out);
+                }
+            }
+            BracketSelector::StepRange(step_range) => step_range.lint(out),
+            BracketSelector::Range(range) => range.lint(out),
+            BracketSelector::Path(path) => path.lint(out),
+            BracketSelector::Filter(filter) => filter.lint(out),
+            BracketSelector::Wildcard(_)
+            | BracketSelector::Parent(_)
+            | BracketSelector::Literal(_) => (),
+        }
+    }
+}
+
+impl BracketLit {
+    fn same_value(&self, other: &BracketLit) -> bool {
+        match (self, other) {
+            (BracketLit::Int(left), BracketLit::Int(right)) => left.as_int() == right.as_int(),
+            (BracketLit::String(left), BracketLit::String(right)) => {
+                left.as_str() == right.as_str()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Filter {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        self.inner.lint(out);
+    }
+}
+
+impl FilterExpr {
+    fn lint(&self, out: &mut Vec<CompileWarning>) {
+        match self {
+            FilterExpr::Unary(_, inner) | FilterExpr::Parens(_, inner) => inner.lint(out),
+            FilterExpr::Binary(lhs, op, rhs) => {
+                let comparison = matches!(
+                    op,
+                    BinOp::Eq(_) | BinOp::Le(_) | BinOp::Lt(_) | BinOp::Gt(_) | BinOp::Ge(_)
+                );
+                if comparison
+                    && matches!(**lhs, FilterExpr::Lit(_))
+                    && matches!(**rhs, FilterExpr::Lit(_))
+                {
+                    out.push(warn(
+                        WarningKind::ConstantFilter,
+                        "Filter compares two literals, so its result is always the same",
+                        self,
+                    ));
+                }
+                lhs.lint(out);
+                rhs.lint(out);
+            }
+            FilterExpr::Path(path) => path.lint(out),
+            FilterExpr::Lit(_) => (),
+        }
+    }
+}
+
+impl BracketSelector {
+    /// Whether this selector can never select anything, regardless of the input
+    pub(crate) fn always_returns_empty(&self) -> bool {
+        match self {
+            BracketSelector::Union(components) => {
+                components.iter().all(UnionComponent::always_returns_empty)
+            }
+            BracketSelector::StepRange(step_range) => step_range.is_always_empty(),
+            BracketSelector::Range(range) => range.is_always_empty(),
+            BracketSelector::Literal(lit) => lit.always_returns_empty(),
+            BracketSelector::Wildcard(_)
+            | BracketSelector::Parent(_)
+            | BracketSelector::Path(_)
+            | BracketSelector::Filter(_) => false,
+        }
+    }
+}
+
+impl UnionComponent {
+    fn always_returns_empty(&self) -> bool {
+        match self {
+            UnionComponent::StepRange(step_range) => step_range.is_always_empty(),
+            UnionComponent::Range(range) => range.is_always_empty(),
+            UnionComponent::Literal(lit) => lit.always_returns_empty(),
+            UnionComponent::Parent(_) | UnionComponent::Path(_) | UnionComponent::Filter(_) => {
+                false
+            }
+        }
+    }
+}
+
+impl BracketLit {
+    /// Whether this literal is an index too large for any array to contain
+    fn always_returns_empty(&self) -> bool {
+        match self {
+            BracketLit::Int(i) => i.as_int().unsigned_abs() >= isize::MAX as u64,
+            BracketLit::String(_) => false,
+        }
+    }
+}
@@ -47,8 +47,10 @@ impl NonZeroIntLit {
 
 impl StringContent {
     fn parser(delimiter: char) -> impl Parser<Input, StringContent, Error = Error> {
-        none_of::<_, _, Error>([delimiter])
-            .or(just(format!("\\{}", delimiter)).to(delimiter))
+        // A backslash escapes the delimiter or another backslash, and is kept as-is otherwise
+        just::<_, _, Error>('\\')
+            .ignore_then(one_of([delimiter, '\\']))
+            .or(none_of([delimiter]))
             .repeated()
             .map_with_span(|content, _span| StringContent {
                 #[cfg(feature = "spanned")]
@@ -11,6 +11,11 @@ pub struct Span {
 }
 
 impl Span {
+    /// The empty span given to syntax created outside the parser
+    pub(crate) fn synthesized() -> Span {
+        Span { start: 0, end: 0 }
+    }
+
     pub(crate) fn join(self, other: Span) -> Span {
         let start = usize::min(self.start, other.start);
         let end = usize::max(self.end, other.end);
@@ -25,17 +30,102 @@ impl Span {
         self.end
     }
 
+    /// Get this span as a range of character offsets into the source string
+    #[must_use]
+    pub fn as_range(self) -> ops::Range<usize> {
+        self.start..self.end
+    }
+
+    /// Whether the provided character offset lies within this span
+    #[must_use]
+    pub fn contains(self, offset: usize) -> bool {
+        offset >= self.start && offset < self.end
+    }
+
+    /// The number of characters this span covers
+    #[must_use]
+    pub fn len(self) -> usize {
+        self.end - self.start
+    }
+
+    /// Whether this span covers no characters
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+
     /// Get the string slice of this span on the source string. Note the provided string must be
     /// the whole source string for this method to be meaningful.
+    ///
+    /// # Panics
+    ///
+    /// - If this span lies outside the provided source string
     #[must_use]
     pub fn get_span(self, source: &str) -> &str {
-        let start = source.char_indices().nth(self.start);
-
-        let end = source.char_indices().nth(self.end);
+        self.try_get_span(source).expect("Invalid source for span")
+    }
 
-        let ((start, _), (end, _)) = start.zip(end).expect("Invalid source for span");
+    /// Get the string slice of this span on the source string, or `None` if this span lies
+    /// outside it
+    #[must_use]
+    pub fn try_get_span(self, source: &str) -> Option<&str> {
+        source.get(self.try_to_byte_range(source)?)
+    }
 
-        &source[start..end]
+    /// Get this span as a range of byte offsets into the source string, rather than character
+    /// offsets
+    ///
+    /// # Panics
+    ///
+    /// - If this span lies outside the provided source string
+    #[must_use]
+    pub fn to_byte_range(self, source: &str) -> ops::Range<usize> {
+        self.try_to_byte_range(source)
+            .expect("Invalid source for span")
+    }
+
+    fn try_to_byte_range(self, source: &str) -> Option<ops::Range<usize>> {
+        let mut offsets = source
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain([source.len()]);
+        let start = offsets.nth(self.start)?;
+        let end = if self.end > self.start {
+            offsets.nth(self.end - self.start - 1)?
+        } else {
+            start
+        };
+        Some(start..end)
+    }
+
+    /// Get the zero-based line and column of the start and end of this span in the source string.
+    /// Lines are separated by `\n`, and columns count characters from the start of the line
+    ///
+    /// # Panics
+    ///
+    /// - If this span lies outside the provided source string
+    #[must_use]
+    pub fn to_line_col(self, source: &str) -> ((usize, usize), (usize, usize)) {
+        assert!(
+            self.start <= self.end && self.end <= source.chars().count(),
+            "Invalid source for span"
+        );
+
+        let mut line_col = (0, 0);
+        let mut start = line_col;
+        for (idx, c) in source.chars().take(self.end).enumerate() {
+            if idx == self.start {
+                start = line_col;
+            }
+            line_col = match c {
+                '\n' => (line_col.0 + 1, 0),
+                _ => (line_col.0, line_col.1 + 1),
+            };
+        }
+        if self.start == self.end {
+            start = line_col;
+        }
+        (start, line_col)
     }
 }
 
@@ -17,6 +17,11 @@ fn test_span_multibyte_string() {
 
     let lit_span = lit.span();
     assert_eq!(lit_span.get_span(path_str), "'ඞ'");
+    assert_eq!(lit_span.to_byte_range(path_str), 2..7);
+
+    let seg_span = path.segments()[0].span();
+    assert_eq!(seg_span.get_span(path_str), "['ඞ']");
+    assert_eq!(seg_span.to_line_col(path_str), ((0, 1), (0, 6)));
 }
 
 #[test]
@@ -37,3 +42,73 @@ fn test_filter_span() {
     let filter_expr_span = filter.expression().span();
     assert_eq!(filter_expr_span.get_span(path_str), "@ == true");
 }
+
+#[test]
+#[cfg(feature = "spanned")]
+fn test_eval_error_span() {
+    use crate::eval::EvalCtx;
+    use serde_json::json;
+
+    let path_str = "$[?(@.a && !@.b)]";
+    let path = Path::compile(path_str).unwrap();
+
+    let filter = if let Segment::Bracket(_, BracketSelector::Filter(filter)) = &path.segments()[0] {
+        filter
+    } else {
+        panic!("First segment wasn't a filter")
+    };
+
+    let value = json!({"a": true, "b": 1});
+    let ctx = EvalCtx::new(&value);
+    let err = filter.expression().eval_expr(&ctx, &value).unwrap_err();
+
+    assert_eq!(err.span().unwrap().get_span(path_str), "!");
+
+    let value = json!({"b": false});
+    let ctx = EvalCtx::new(&value);
+    let err = filter.expression().eval_expr(&ctx, &value).unwrap_err();
+
+    assert_eq!(err.span().unwrap().get_span(path_str), "@.a");
+}
+
+#[test]
+#[cfg(feature = "spanned")]
+fn test_tilde_error_span() {
+    use serde_json::json;
+
+    let path_str = "$.a~";
+    let path = crate::JsonPath::compile(path_str).unwrap();
+    let value = json!({"a": 1});
+    let err = path
+        .try_find_with(&value, crate::EvalOptions::new())
+        .unwrap_err();
+
+    assert_eq!(err.span().unwrap().get_span(path_str), "~");
+}
+
+#[test]
+#[cfg(feature = "spanned")]
+fn test_accessor_spans() {
+    let path_str = "$.a..[\"bc\"][?(@)]~";
+    let path = Path::compile(path_str).unwrap();
+
+    assert_eq!(path.dollar_token().span().get_span(path_str), "$");
+    assert_eq!(path.tilde_token().unwrap().span().get_span(path_str), "~");
+
+    let selectors = path
+        .segments()
+        .iter()
+        .map(|seg| seg.selector_span().map(|span| span.get_span(path_str)))
+        .collect::<Vec<_>>();
+    assert_eq!(selectors, [Some("a"), None, Some("\"bc\""), Some("?(@)")]);
+
+    let lit = path[2].as_bracket_selector().unwrap().as_literal().unwrap();
+    let BracketLit::String(lit) = lit else {
+        panic!("Literal wasn't a string")
+    };
+    assert_eq!(lit.content_span().get_span(path_str), "bc");
+
+    let filter = path[3].as_bracket_selector().unwrap().as_filter().unwrap();
+    assert_eq!(filter.question_token().span().get_span(path_str), "?");
+    assert_eq!(filter.paren_token().span().get_span(path_str), "(@)");
+}
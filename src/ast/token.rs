@@ -1,8 +1,10 @@
+//! The punctuation tokens of a path. Tokens carry nothing but their source span, so those created
+//! outside the parser have an empty span at the start of the source.
+
 use super::{Error, Input};
 use chumsky::primitive::just;
 use chumsky::Parser;
 
-#[cfg(feature = "spanned")]
 use super::Span;
 
 // 'Wrapping' tokens
@@ -10,15 +12,64 @@ use super::Span;
 macro_rules! wrapping_tokens {
         ($($name:ident($start:literal, $end:literal));* $(;)?) => {
             $(
+            #[doc = concat!("A pair of `", $start, "` and `", $end, "` delimiters")]
             #[cfg(feature = "spanned")]
+            #[derive(Clone)]
             pub struct $name(Span, Span);
+            #[doc = concat!("A pair of `", $start, "` and `", $end, "` delimiters")]
             #[cfg(not(feature = "spanned"))]
+            #[derive(Clone)]
             pub struct $name(());
 
+            // Tokens carry no value besides their span, so any two of the same kind are equal
+            impl PartialEq for $name {
+                fn eq(&self, _other: &Self) -> bool {
+                    true
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl core::fmt::Debug for $name {
+                #[cfg(feature = "spanned")]
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.debug_tuple(stringify!($name)).field(&self.0).field(&self.1).finish()
+                }
+
+                #[cfg(not(feature = "spanned"))]
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    write!(f, stringify!($name))
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
             impl $name {
+                /// Create a new token, with an empty span
+                #[cfg(feature = "spanned")]
+                #[must_use]
+                pub fn new() -> Self {
+                    $name(Span::synthesized(), Span::synthesized())
+                }
+
+                /// Create a new token
+                #[cfg(not(feature = "spanned"))]
+                #[must_use]
+                pub fn new() -> Self {
+                    $name(())
+                }
+
                 #[cfg(feature = "spanned")]
                 pub(super) fn parser<T>(item: impl Parser<Input, T, Error = Error>) -> impl Parser<Input, (Self, T), Error = Error> {
                     item.delimited_by(just($start), just($end))
+                        .map_err_with_span(|err: Error, span: Span| {
+                            let start = span.start()..(span.start() + 1);
+                            err.into_unclosed(start.into(), $start, &$end)
+                        })
                         .map_with_span(|inner, span| {
                             let start = span.start()..(span.start() + 1);
                             let end = (span.end() - 1)..span.end();
@@ -30,6 +81,10 @@ macro_rules! wrapping_tokens {
                 #[cfg(not(feature = "spanned"))]
                 pub(super) fn parser<T>(item: impl Parser<Input, T, Error = Error>) -> impl Parser<Input, (Self, T), Error = Error> {
                     item.delimited_by(just($start), just($end))
+                        .map_err_with_span(|err: Error, span: Span| {
+                            let start = span.start()..(span.start() + 1);
+                            err.into_unclosed(start.into(), $start, &$end)
+                        })
                         .map(|inner| {
                             ($name(()), inner)
                         })
@@ -57,12 +112,57 @@ wrapping_tokens! {
 macro_rules! simple_tokens {
         ($($name:ident($just:literal));* $(;)?) => {
             $(
+            #[doc = concat!("The `", $just, "` token")]
             #[cfg(feature = "spanned")]
+            #[derive(Clone)]
             pub struct $name(Span);
+            #[doc = concat!("The `", $just, "` token")]
             #[cfg(not(feature = "spanned"))]
+            #[derive(Clone)]
             pub struct $name(());
 
+            // Tokens carry no value besides their span, so any two of the same kind are equal
+            impl PartialEq for $name {
+                fn eq(&self, _other: &Self) -> bool {
+                    true
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl core::fmt::Debug for $name {
+                #[cfg(feature = "spanned")]
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.debug_tuple(stringify!($name)).field(&self.0).finish()
+                }
+
+                #[cfg(not(feature = "spanned"))]
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    write!(f, stringify!($name))
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
             impl $name {
+                /// Create a new token, with an empty span
+                #[cfg(feature = "spanned")]
+                #[must_use]
+                pub fn new() -> Self {
+                    $name(Span::synthesized())
+                }
+
+                /// Create a new token
+                #[cfg(not(feature = "spanned"))]
+                #[must_use]
+                pub fn new() -> Self {
+                    $name(())
+                }
+
                 #[cfg(feature = "spanned")]
                 pub(super) fn parser() -> impl Parser<Input, Self, Error = Error> {
                     just::<_, _, Error>($just)
@@ -0,0 +1,249 @@
+//! A visitor for walking a syntax tree by reference. Implement [`Visit`], overriding the methods
+//! for the nodes of interest, then start the walk with [`walk_path`] or by calling a `visit_*`
+//! method on the root node. Every method defaults to recursing into the children of its node, so
+//! an overriding method should call the matching `walk_*` function to keep visiting below it.
+//!
+//! ```
+//! # use jsonpath_plus::JsonPath;
+//! use jsonpath_plus::ast::visit::{walk_path, Visit};
+//! use jsonpath_plus::ast::Ident;
+//!
+//! #[derive(Default)]
+//! struct Idents<'ast>(Vec<&'ast str>);
+//!
+//! impl<'ast> Visit<'ast> for Idents<'ast> {
+//!     fn visit_ident(&mut self, node: &'ast Ident) {
+//!         self.0.push(node.as_str());
+//!     }
+//! }
+//!
+//! let path = JsonPath::compile("$.a..b[?(@.c == $.d.e)]").unwrap();
+//! let mut idents = Idents::default();
+//! walk_path(&path, &mut idents);
+//! assert_eq!(idents.0, ["a", "b", "c", "d", "e"]);
+//! ```
+
+use super::*;
+
+/// Trait for visiting the nodes of a syntax tree. See the [module docs](self) for usage
+pub trait Visit<'ast> {
+    /// Visit a full path
+    fn visit_path(&mut self, node: &'ast Path) {
+        walk_path(node, self);
+    }
+
+    /// Visit a sub-path, in a bracket selector or filter
+    fn visit_sub_path(&mut self, node: &'ast SubPath) {
+        walk_sub_path(node, self);
+    }
+
+    /// Visit the kind of a sub-path
+    fn visit_path_kind(&mut self, _node: &'ast PathKind) {}
+
+    /// Visit a single segment of a path
+    fn visit_segment(&mut self, node: &'ast Segment) {
+        walk_segment(node, self);
+    }
+
+    /// Visit the selector following a dot
+    fn visit_raw_selector(&mut self, node: &'ast RawSelector) {
+        walk_raw_selector(node, self);
+    }
+
+    /// Visit the inside of a bracket segment
+    fn visit_bracket_selector(&mut self, node: &'ast BracketSelector) {
+        walk_bracket_selector(node, self);
+    }
+
+    /// Visit a component of a bracket union
+    fn visit_union_component(&mut self, node: &'ast UnionComponent) {
+        walk_union_component(node, self);
+    }
+
+    /// Visit a range with an explicit step
+    fn visit_step_range(&mut self, node: &'ast StepRange) {
+        walk_step_range(node, self);
+    }
+
+    /// Visit a range with an implicit step
+    fn visit_range(&mut self, node: &'ast Range) {
+        walk_range(node, self);
+    }
+
+    /// Visit a literal inside brackets
+    fn visit_bracket_lit(&mut self, node: &'ast BracketLit) {
+        walk_bracket_lit(node, self);
+    }
+
+    /// Visit a filter selector
+    fn visit_filter(&mut self, node: &'ast Filter) {
+        walk_filter(node, self);
+    }
+
+    /// Visit an expression inside a filter
+    fn visit_filter_expr(&mut self, node: &'ast FilterExpr) {
+        walk_filter_expr(node, self);
+    }
+
+    /// Visit a literal inside an expression
+    fn visit_expr_lit(&mut self, node: &'ast ExprLit) {
+        walk_expr_lit(node, self);
+    }
+
+    /// Visit an unary operator
+    fn visit_un_op(&mut self, _node: &'ast UnOp) {}
+
+    /// Visit a binary operator
+    fn visit_bin_op(&mut self, _node: &'ast BinOp) {}
+
+    /// Visit an identifier
+    fn visit_ident(&mut self, _node: &'ast Ident) {}
+
+    /// Visit a string literal
+    fn visit_string_lit(&mut self, _node: &'ast StringLit) {}
+
+    /// Visit an integer literal
+    fn visit_int_lit(&mut self, _node: &'ast IntLit) {}
+
+    /// Visit a non-zero integer literal, the step of a range
+    fn visit_non_zero_int_lit(&mut self, _node: &'ast NonZeroIntLit) {}
+
+    /// Visit a boolean literal
+    fn visit_bool_lit(&mut self, _node: &'ast BoolLit) {}
+
+    /// Visit a null literal
+    fn visit_null_lit(&mut self, _node: &'ast NullLit) {}
+}
+
+/// Visit each segment of a path
+pub fn walk_path<'ast, V: Visit<'ast> + ?Sized>(node: &'ast Path, visitor: &mut V) {
+    for seg in &node.segments {
+        visitor.visit_segment(seg);
+    }
+}
+
+/// Visit the kind and then each segment of a sub-path
+pub fn walk_sub_path<'ast, V: Visit<'ast> + ?Sized>(node: &'ast SubPath, visitor: &mut V) {
+    visitor.visit_path_kind(&node.kind);
+    for seg in &node.segments {
+        visitor.visit_segment(seg);
+    }
+}
+
+/// Visit the selector of a segment, if it has one
+pub fn walk_segment<'ast, V: Visit<'ast> + ?Sized>(node: &'ast Segment, visitor: &mut V) {
+    match node {
+        Segment::Dot(_, selector) | Segment::Recursive(_, Some(selector)) => {
+            visitor.visit_raw_selector(selector);
+        }
+        Segment::Bracket(_, selector) => visitor.visit_bracket_selector(selector),
+        Segment::Recursive(_, None) => (),
+    }
+}
+
+/// Visit the identifier of a name selector
+pub fn walk_raw_selector<'ast, V: Visit<'ast> + ?Sized>(node: &'ast RawSelector, visitor: &mut V) {
+    match node {
+        RawSelector::Name(name) => visitor.visit_ident(name),
+        RawSelector::Wildcard(_) | RawSelector::Parent(_) => (),
+    }
+}
+
+/// Visit the contents of a bracket selector
+pub fn walk_bracket_selector<'ast, V: Visit<'ast> + ?Sized>(
+    node: &'ast BracketSelector,
+    visitor: &mut V,
+) {
+    match node {
+        BracketSelector::Union(components) => {
+            for component in components {
+                visitor.visit_union_component(component);
+            }
+        }
+        BracketSelector::StepRange(range) => visitor.visit_step_range(range),
+        BracketSelector::Range(range) => visitor.visit_range(range),
+        BracketSelector::Path(path) => visitor.visit_sub_path(path),
+        BracketSelector::Filter(filter) => visitor.visit_filter(filter),
+        BracketSelector::Literal(lit) => visitor.visit_bracket_lit(lit),
+        BracketSelector::Wildcard(_) | BracketSelector::Parent(_) => (),
+    }
+}
+
+/// Visit the contents of a union component
+pub fn walk_union_component<'ast, V: Visit<'ast> + ?Sized>(
+    node: &'ast UnionComponent,
+    visitor: &mut V,
+) {
+    match node {
+        UnionComponent::StepRange(range) => visitor.visit_step_range(range),
+        UnionComponent::Range(range) => visitor.visit_range(range),
+        UnionComponent::Path(path) => visitor.visit_sub_path(path),
+        UnionComponent::Filter(filter) => visitor.visit_filter(filter),
+        UnionComponent::Literal(lit) => visitor.visit_bracket_lit(lit),
+        UnionComponent::Parent(_) => (),
+    }
+}
+
+/// Visit the start, end and step of a range, where present
+pub fn walk_step_range<'ast, V: Visit<'ast> + ?Sized>(node: &'ast StepRange, visitor: &mut V) {
+    if let Some(start) = &node.start {
+        visitor.visit_int_lit(start);
+    }
+    if let Some(end) = &node.end {
+        visitor.visit_int_lit(end);
+    }
+    if let Some(step) = &node.step {
+        visitor.visit_non_zero_int_lit(step);
+    }
+}
+
+/// Visit the start and end of a range, where present
+pub fn walk_range<'ast, V: Visit<'ast> + ?Sized>(node: &'ast Range, visitor: &mut V) {
+    if let Some(start) = &node.start {
+        visitor.visit_int_lit(start);
+    }
+    if let Some(end) = &node.end {
+        visitor.visit_int_lit(end);
+    }
+}
+
+/// Visit the literal inside a bracket literal
+pub fn walk_bracket_lit<'ast, V: Visit<'ast> + ?Sized>(node: &'ast BracketLit, visitor: &mut V) {
+    match node {
+        BracketLit::Int(i) => visitor.visit_int_lit(i),
+        BracketLit::String(s) => visitor.visit_string_lit(s),
+    }
+}
+
+/// Visit the expression of a filter
+pub fn walk_filter<'ast, V: Visit<'ast> + ?Sized>(node: &'ast Filter, visitor: &mut V) {
+    visitor.visit_filter_expr(&node.inner);
+}
+
+/// Visit the operators and operands of an expression, in source order
+pub fn walk_filter_expr<'ast, V: Visit<'ast> + ?Sized>(node: &'ast FilterExpr, visitor: &mut V) {
+    match node {
+        FilterExpr::Unary(op, inner) => {
+            visitor.visit_un_op(op);
+            visitor.visit_filter_expr(inner);
+        }
+        FilterExpr::Binary(lhs, op, rhs) => {
+            visitor.visit_filter_expr(lhs);
+            visitor.visit_bin_op(op);
+            visitor.visit_filter_expr(rhs);
+        }
+        FilterExpr::Path(path) => visitor.visit_sub_path(path),
+        FilterExpr::Lit(lit) => visitor.visit_expr_lit(lit),
+        FilterExpr::Parens(_, inner) => visitor.visit_filter_expr(inner),
+    }
+}
+
+/// Visit the literal inside an expression literal
+pub fn walk_expr_lit<'ast, V: Visit<'ast> + ?Sized>(node: &'ast ExprLit, visitor: &mut V) {
+    match node {
+        ExprLit::Int(i) => visitor.visit_int_lit(i),
+        ExprLit::String(s) => visitor.visit_string_lit(s),
+        ExprLit::Bool(b) => visitor.visit_bool_lit(b),
+        ExprLit::Null(n) => visitor.visit_null_lit(n),
+    }
+}
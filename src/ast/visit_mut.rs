@@ -0,0 +1,257 @@
+//! A visitor for walking a syntax tree by mutable reference, to rewrite it in place. Implement
+//! [`VisitMut`], overriding the methods for the nodes of interest, then start the walk with
+//! [`walk_path_mut`] or by calling a `visit_*_mut` method on the root node. As with
+//! [`Visit`](super::visit::Visit), every method defaults to recursing into the children of its
+//! node. Replacement nodes can be built with the constructors on each node, such as
+//! [`Segment::index`], and have empty spans.
+//!
+//! ```
+//! # use jsonpath_plus::JsonPath;
+//! use jsonpath_plus::ast::visit_mut::{walk_path_mut, walk_segment_mut, VisitMut};
+//! use jsonpath_plus::ast::{Ident, RawSelector, Segment};
+//!
+//! struct Migrate;
+//!
+//! impl VisitMut for Migrate {
+//!     fn visit_ident_mut(&mut self, node: &mut Ident) {
+//!         if node.as_str() == "user" {
+//!             *node = Ident::new("account");
+//!         }
+//!     }
+//!
+//!     fn visit_segment_mut(&mut self, node: &mut Segment) {
+//!         match node {
+//!             Segment::Dot(_, RawSelector::Name(name)) if name.as_str() == "env" => {
+//!                 *node = Segment::index(0);
+//!             }
+//!             _ => walk_segment_mut(node, self),
+//!         }
+//!     }
+//! }
+//!
+//! let mut path = JsonPath::compile("$.user.env[?(@.user == 1)]").unwrap();
+//! walk_path_mut(&mut path, &mut Migrate);
+//! assert_eq!(path.to_string(), "$.account[0][?(@.account == 1)]");
+//! ```
+
+use super::*;
+
+/// Trait for visiting the nodes of a syntax tree mutably. See the [module docs](self) for usage
+pub trait VisitMut {
+    /// Visit a full path
+    fn visit_path_mut(&mut self, node: &mut Path) {
+        walk_path_mut(node, self);
+    }
+
+    /// Visit a sub-path, in a bracket selector or filter
+    fn visit_sub_path_mut(&mut self, node: &mut SubPath) {
+        walk_sub_path_mut(node, self);
+    }
+
+    /// Visit the kind of a sub-path
+    fn visit_path_kind_mut(&mut self, _node: &mut PathKind) {}
+
+    /// Visit a single segment of a path
+    fn visit_segment_mut(&mut self, node: &mut Segment) {
+        walk_segment_mut(node, self);
+    }
+
+    /// Visit the selector following a dot
+    fn visit_raw_selector_mut(&mut self, node: &mut RawSelector) {
+        walk_raw_selector_mut(node, self);
+    }
+
+    /// Visit the inside of a bracket segment
+    fn visit_bracket_selector_mut(&mut self, node: &mut BracketSelector) {
+        walk_bracket_selector_mut(node, self);
+    }
+
+    /// Visit a component of a bracket union
+    fn visit_union_component_mut(&mut self, node: &mut UnionComponent) {
+        walk_union_component_mut(node, self);
+    }
+
+    /// Visit a range with an explicit step
+    fn visit_step_range_mut(&mut self, node: &mut StepRange) {
+        walk_step_range_mut(node, self);
+    }
+
+    /// Visit a range with an implicit step
+    fn visit_range_mut(&mut self, node: &mut Range) {
+        walk_range_mut(node, self);
+    }
+
+    /// Visit a literal inside brackets
+    fn visit_bracket_lit_mut(&mut self, node: &mut BracketLit) {
+        walk_bracket_lit_mut(node, self);
+    }
+
+    /// Visit a filter selector
+    fn visit_filter_mut(&mut self, node: &mut Filter) {
+        walk_filter_mut(node, self);
+    }
+
+    /// Visit an expression inside a filter
+    fn visit_filter_expr_mut(&mut self, node: &mut FilterExpr) {
+        walk_filter_expr_mut(node, self);
+    }
+
+    /// Visit a literal inside an expression
+    fn visit_expr_lit_mut(&mut self, node: &mut ExprLit) {
+        walk_expr_lit_mut(node, self);
+    }
+
+    /// Visit an unary operator
+    fn visit_un_op_mut(&mut self, _node: &mut UnOp) {}
+
+    /// Visit a binary operator
+    fn visit_bin_op_mut(&mut self, _node: &mut BinOp) {}
+
+    /// Visit an identifier
+    fn visit_ident_mut(&mut self, _node: &mut Ident) {}
+
+    /// Visit a string literal
+    fn visit_string_lit_mut(&mut self, _node: &mut StringLit) {}
+
+    /// Visit an integer literal
+    fn visit_int_lit_mut(&mut self, _node: &mut IntLit) {}
+
+    /// Visit a non-zero integer literal, the step of a range
+    fn visit_non_zero_int_lit_mut(&mut self, _node: &mut NonZeroIntLit) {}
+
+    /// Visit a boolean literal
+    fn visit_bool_lit_mut(&mut self, _node: &mut BoolLit) {}
+
+    /// Visit a null literal
+    fn visit_null_lit_mut(&mut self, _node: &mut NullLit) {}
+}
+
+/// Visit each segment of a path
+pub fn walk_path_mut<V: VisitMut + ?Sized>(node: &mut Path, visitor: &mut V) {
+    for seg in &mut node.segments {
+        visitor.visit_segment_mut(seg);
+    }
+}
+
+/// Visit the kind and then each segment of a sub-path
+pub fn walk_sub_path_mut<V: VisitMut + ?Sized>(node: &mut SubPath, visitor: &mut V) {
+    visitor.visit_path_kind_mut(&mut node.kind);
+    for seg in &mut node.segments {
+        visitor.visit_segment_mut(seg);
+    }
+}
+
+/// Visit the selector of a segment, if it has one
+pub fn walk_segment_mut<V: VisitMut + ?Sized>(node: &mut Segment, visitor: &mut V) {
+    match node {
+        Segment::Dot(_, selector) | Segment::Recursive(_, Some(selector)) => {
+            visitor.visit_raw_selector_mut(selector);
+        }
+        Segment::Bracket(_, selector) => visitor.visit_bracket_selector_mut(selector),
+        Segment::Recursive(_, None) => (),
+    }
+}
+
+/// Visit the identifier of a name selector
+pub fn walk_raw_selector_mut<V: VisitMut + ?Sized>(node: &mut RawSelector, visitor: &mut V) {
+    match node {
+        RawSelector::Name(name) => visitor.visit_ident_mut(name),
+        RawSelector::Wildcard(_) | RawSelector::Parent(_) => (),
+    }
+}
+
+/// Visit the contents of a bracket selector
+pub fn walk_bracket_selector_mut<V: VisitMut + ?Sized>(
+    node: &mut BracketSelector,
+    visitor: &mut V,
+) {
+    match node {
+        BracketSelector::Union(components) => {
+            for component in components {
+                visitor.visit_union_component_mut(component);
+            }
+        }
+        BracketSelector::StepRange(range) => visitor.visit_step_range_mut(range),
+        BracketSelector::Range(range) => visitor.visit_range_mut(range),
+        BracketSelector::Path(path) => visitor.visit_sub_path_mut(path),
+        BracketSelector::Filter(filter) => visitor.visit_filter_mut(filter),
+        BracketSelector::Literal(lit) => visitor.visit_bracket_lit_mut(lit),
+        BracketSelector::Wildcard(_) | BracketSelector::Parent(_) => (),
+    }
+}
+
+/// Visit the contents of a union component
+pub fn walk_union_component_mut<V: VisitMut + ?Sized>(node: &mut UnionComponent, visitor: &mut V) {
+    match node {
+        UnionComponent::StepRange(range) => visitor.visit_step_range_mut(range),
+        UnionComponent::Range(range) => visitor.visit_range_mut(range),
+        UnionComponent::Path(path) => visitor.visit_sub_path_mut(path),
+        UnionComponent::Filter(filter) => visitor.visit_filter_mut(filter),
+        UnionComponent::Literal(lit) => visitor.visit_bracket_lit_mut(lit),
+        UnionComponent::Parent(_) => (),
+    }
+}
+
+/// Visit the start, end and step of a range, where present
+pub fn walk_step_range_mut<V: VisitMut + ?Sized>(node: &mut StepRange, visitor: &mut V) {
+    if let Some(start) = &mut node.start {
+        visitor.visit_int_lit_mut(start);
+    }
+    if let Some(end) = &mut node.end {
+        visitor.visit_int_lit_mut(end);
+    }
+    if let Some(step) = &mut node.step {
+        visitor.visit_non_zero_int_lit_mut(step);
+    }
+}
+
+/// Visit the start and end of a range, where present
+pub fn walk_range_mut<V: VisitMut + ?Sized>(node: &mut Range, visitor: &mut V) {
+    if let Some(start) = &mut node.start {
+        visitor.visit_int_lit_mut(start);
+    }
+    if let Some(end) = &mut node.end {
+        visitor.visit_int_lit_mut(end);
+    }
+}
+
+/// Visit the literal inside a bracket literal
+pub fn walk_bracket_lit_mut<V: VisitMut + ?Sized>(node: &mut BracketLit, visitor: &mut V) {
+    match node {
+        BracketLit::Int(i) => visitor.visit_int_lit_mut(i),
+        BracketLit::String(s) => visitor.visit_string_lit_mut(s),
+    }
+}
+
+/// Visit the expression of a filter
+pub fn walk_filter_mut<V: VisitMut + ?Sized>(node: &mut Filter, visitor: &mut V) {
+    visitor.visit_filter_expr_mut(&mut node.inner);
+}
+
+/// Visit the operators and operands of an expression, in source order
+pub fn walk_filter_expr_mut<V: VisitMut + ?Sized>(node: &mut FilterExpr, visitor: &mut V) {
+    match node {
+        FilterExpr::Unary(op, inner) => {
+            visitor.visit_un_op_mut(op);
+            visitor.visit_filter_expr_mut(inner);
+        }
+        FilterExpr::Binary(lhs, op, rhs) => {
+            visitor.visit_filter_expr_mut(lhs);
+            visitor.visit_bin_op_mut(op);
+            visitor.visit_filter_expr_mut(rhs);
+        }
+        FilterExpr::Path(path) => visitor.visit_sub_path_mut(path),
+        FilterExpr::Lit(lit) => visitor.visit_expr_lit_mut(lit),
+        FilterExpr::Parens(_, inner) => visitor.visit_filter_expr_mut(inner),
+    }
+}
+
+/// Visit the literal inside an expression literal
+pub fn walk_expr_lit_mut<V: VisitMut + ?Sized>(node: &mut ExprLit, visitor: &mut V) {
+    match node {
+        ExprLit::Int(i) => visitor.visit_int_lit_mut(i),
+        ExprLit::String(s) => visitor.visit_string_lit_mut(s),
+        ExprLit::Bool(b) => visitor.visit_bool_lit_mut(b),
+        ExprLit::Null(n) => visitor.visit_null_lit_mut(n),
+    }
+}
@@ -0,0 +1,99 @@
+//! Stripping of JSON5-style comments from paths, enabled by the `json5` feature
+
+enum Item {
+    /// A character of the pattern, with its position and whether it's inside a string literal
+    Char(usize, char, bool),
+    /// A comment which was removed
+    Comment,
+}
+
+/// Split a pattern into its characters outside of comments, with `//` line comments and
+/// `/* */` block comments being replaced by markers. Comment markers inside string literals are
+/// left alone, as are unterminated block comments so they fail to parse
+fn tokenize(pattern: &str) -> Vec<Item> {
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut items = Vec::with_capacity(chars.len());
+    let mut quote = None;
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        if let Some(q) = quote {
+            items.push(Item::Char(idx, c, true));
+            if c == '\\' {
+                if let Some(&next) = chars.get(idx + 1) {
+                    items.push(Item::Char(idx + 1, next, true));
+                    idx += 1;
+                }
+            } else if c == q {
+                quote = None;
+            }
+            idx += 1;
+            continue;
+        }
+
+        match (c, chars.get(idx + 1)) {
+            ('\'' | '"', _) => {
+                quote = Some(c);
+                items.push(Item::Char(idx, c, true));
+            }
+            ('/', Some('/')) => {
+                idx = chars[idx..]
+                    .iter()
+                    .position(|&c| c == '\n')
+                    .map_or(chars.len(), |end| idx + end);
+                items.push(Item::Comment);
+                continue;
+            }
+            ('/', Some('*')) => {
+                let end = chars[idx + 2..]
+                    .windows(2)
+                    .position(|pair| pair == ['*', '/']);
+                match end {
+                    Some(end) => {
+                        idx += end + 4;
+                        items.push(Item::Comment);
+                        continue;
+                    }
+                    None => items.push(Item::Char(idx, c, false)),
+                }
+            }
+            _ => items.push(Item::Char(idx, c, false)),
+        }
+        idx += 1;
+    }
+
+    items
+}
+
+/// Remove comments from a pattern, returning the remaining characters along with their
+/// positions in the original pattern. Runs of whitespace outside string literals which are next
+/// to a comment or contain a line break are also removed, so a path can be split across lines
+pub(crate) fn strip_indexed(pattern: &str) -> Vec<(usize, char)> {
+    let mut out = Vec::with_capacity(pattern.len());
+    let mut run = Vec::new();
+    let mut drop_run = false;
+
+    for item in tokenize(pattern) {
+        match item {
+            Item::Comment => drop_run = true,
+            Item::Char(idx, c, false) if c.is_whitespace() => {
+                drop_run |= c == '\n';
+                run.push((idx, c));
+            }
+            Item::Char(idx, c, _) => {
+                if !drop_run {
+                    out.append(&mut run);
+                }
+                run.clear();
+                drop_run = false;
+                out.push((idx, c));
+            }
+        }
+    }
+    if !drop_run {
+        out.append(&mut run);
+    }
+
+    out
+}
@@ -1,15 +1,18 @@
 //! Errors returned by fallible methods
 
 use core::fmt;
+use core::ops::Range;
 use std::error;
-use std::error::Error;
+use std::io;
 
 use crate::ast::ParseFail;
-use crate::Idx;
+use crate::ast::Span;
+use crate::idx::{Idx, IdxPath};
+use crate::json::Json;
 use serde_json::Value;
 
 /// Error returned by a failure to parse a provided JSON Path
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ParseError {
     src: String,
     errs: Vec<ParseFail<char, ()>>,
@@ -22,24 +25,226 @@ impl ParseError {
             errs,
         }
     }
+
+    /// Combine this error with another, keeping the failures of both. The longer of the two
+    /// source paths is kept, so spans of failures from the other path may not line up with it
+    #[must_use]
+    pub fn merge(mut self, other: ParseError) -> ParseError {
+        if other.src.len() > self.src.len() {
+            self.src = other.src;
+        }
+        self.errs.extend(other.errs);
+        self
+    }
+
+    /// Combine many errors into one, as if by [`ParseError::merge`]. If no errors are provided,
+    /// the result has an empty source and no failures
+    #[must_use]
+    pub fn from_many(errors: Vec<ParseError>) -> ParseError {
+        errors
+            .into_iter()
+            .reduce(ParseError::merge)
+            .unwrap_or_else(|| ParseError::new("", Vec::new()))
+    }
+
+    /// Get the individual failures which make up this error
+    #[must_use]
+    pub fn errors(&self) -> &[ParseFail<char, ()>] {
+        &self.errs
+    }
+
+    /// Get the most relevant location of this error, as a range of character offsets into the
+    /// source path. This is the failure which starts furthest into the path, as that is usually
+    /// the most informative one.
+    #[must_use]
+    pub fn primary_span(&self) -> Option<Range<usize>> {
+        self.errs
+            .iter()
+            .map(ParseFail::span)
+            .max_by_key(|span| span.start())
+            .map(Span::as_range)
+    }
+
+    /// Get the locations of all failures in this error, as ranges of character offsets into the
+    /// source path
+    #[must_use]
+    pub fn all_spans(&self) -> Vec<Range<usize>> {
+        self.errs
+            .iter()
+            .map(ParseFail::span)
+            .map(Span::as_range)
+            .collect()
+    }
+
+    /// Render this error with the source path followed by each failure, underlining the
+    /// location of the failure with carets. Empty spans, such as an unexpected end of input, are
+    /// still marked by a single caret.
+    ///
+    /// ```text
+    /// Error Parsing JSON Path:
+    /// $.a[1
+    ///      ^
+    /// Unclosed delimiter '[' at 3..4, found end of input, expected one of ',', ':', ']'
+    /// ```
+    #[must_use]
+    pub fn to_string_with_caret(&self) -> String {
+        let mut out = format!("Error Parsing JSON Path:\n{}\n", self.src);
+        for err in &self.errs {
+            let span = err.span();
+            out.push_str(&" ".repeat(span.start()));
+            out.push_str(&"^".repeat(span.len().max(1)));
+            out.push('\n');
+            out.push_str(&err.to_string());
+            out.push('\n');
+        }
+        out
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Error Parsing JSON Path:")?;
         writeln!(f, "{}", self.src)?;
-        for _err in &self.errs {
-            todo!();
-            // writeln!(f, "{}", err)?;
+        for err in &self.errs {
+            writeln!(f, "{}", err)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {
+    /// Parse errors aren't caused by another error, so this always returns `None`
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+/// Error returned by a failure to parse one or more of a set of JSON Paths
+#[derive(Debug)]
+pub struct ParseErrors {
+    errs: Vec<Option<ParseError>>,
+}
+
+impl ParseErrors {
+    pub(crate) fn new(errs: Vec<Option<ParseError>>) -> ParseErrors {
+        ParseErrors { errs }
+    }
+
+    /// Get the errors for each provided path, parallel to the provided paths. Entries are `None`
+    /// for paths which parsed successfully
+    #[must_use]
+    pub fn errors(&self) -> &[Option<ParseError>] {
+        &self.errs
+    }
+
+    /// Iterate over only the failed paths, yielding their index in the provided paths along with
+    /// the error
+    pub fn failed(&self) -> impl Iterator<Item = (usize, &ParseError)> {
+        self.errs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, err)| err.as_ref().map(|err| (idx, err)))
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Error Parsing {} of {} JSON Paths:",
+            self.failed().count(),
+            self.errs.len()
+        )?;
+        for (idx, err) in self.failed() {
+            writeln!(f, "  {}: {}", idx, err.src)?;
+            for fail in &err.errs {
+                writeln!(f, "    {}", fail)?;
+            }
         }
         Ok(())
     }
 }
 
-impl error::Error for ParseError {}
+impl error::Error for ParseErrors {}
+
+/// Enum for any error that might occur while using a JSON path - a failure to read or parse the
+/// path, a failure to deserialize JSON data, or a failure to evaluate the path
+///
+/// The [`source`](error::Error::source) of this error is always the error it wraps. Parse and
+/// evaluation errors have no source of their own, so the chain ends there, while JSON and I/O
+/// errors continue with whatever source they report.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Error was a failure to parse JSON Path
+    Parse(ParseError),
+    /// Error was a failure to deserialize JSON data
+    Json(serde_json::Error),
+    /// Error was a failure to evaluate JSON Path
+    Eval(EvalError),
+    /// Error was a failure to read a JSON Path
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Json(err) => write!(f, "{}", err),
+            Error::Eval(err) => write!(f, "{}", err),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Parse(p) => Some(p),
+            Error::Json(j) => Some(j),
+            Error::Eval(e) => Some(e),
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<EvalError> for Error {
+    fn from(err: EvalError) -> Self {
+        Error::Eval(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[allow(deprecated)]
+impl From<ParseOrJsonError> for Error {
+    fn from(err: ParseOrJsonError) -> Self {
+        match err {
+            ParseOrJsonError::Parse(err) => Error::Parse(err),
+            ParseOrJsonError::Json(err) => Error::Json(err),
+        }
+    }
+}
 
 /// Enum for an error that might be either a failure to parse a JSON path, or failure to deserialize
 /// JSON data
+#[deprecated(note = "use `error::Error` instead, which also covers evaluation failures")]
 #[derive(Debug)]
 pub enum ParseOrJsonError {
     /// Error was a failure to parse JSON Path
@@ -48,6 +253,7 @@ pub enum ParseOrJsonError {
     Json(serde_json::Error),
 }
 
+#[allow(deprecated)]
 impl fmt::Display for ParseOrJsonError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -57,8 +263,9 @@ impl fmt::Display for ParseOrJsonError {
     }
 }
 
+#[allow(deprecated)]
 impl error::Error for ParseOrJsonError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             ParseOrJsonError::Parse(p) => Some(p),
             ParseOrJsonError::Json(j) => Some(j),
@@ -66,20 +273,68 @@ impl error::Error for ParseOrJsonError {
     }
 }
 
+#[allow(deprecated)]
 impl From<ParseError> for ParseOrJsonError {
     fn from(err: ParseError) -> Self {
         ParseOrJsonError::Parse(err)
     }
 }
 
+#[allow(deprecated)]
 impl From<serde_json::Error> for ParseOrJsonError {
     fn from(err: serde_json::Error) -> Self {
         ParseOrJsonError::Json(err)
     }
 }
 
+/// Error returned by [`JsonPath::find_one_typed`] when there isn't exactly one match, or the
+/// match fails to deserialize
+///
+/// [`JsonPath::find_one_typed`]: crate::JsonPath::find_one_typed
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FindTypedError {
+    /// The path matched no items
+    NoMatch,
+    /// The path matched more than one item
+    ManyMatches(usize),
+    /// The matched item couldn't be deserialized into the requested type
+    Deserialize(serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for FindTypedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FindTypedError::NoMatch => write!(f, "Expected one match, but found none"),
+            FindTypedError::ManyMatches(count) => {
+                write!(f, "Expected one match, but found {} matches", count)
+            }
+            FindTypedError::Deserialize(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl error::Error for FindTypedError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FindTypedError::Deserialize(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for FindTypedError {
+    fn from(err: serde_json::Error) -> Self {
+        FindTypedError::Deserialize(err)
+    }
+}
+
 /// Type of a JSON Value for error info
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum JsonTy {
     /// `null`
     Null,
@@ -121,7 +376,26 @@ impl From<&Value> for JsonTy {
     }
 }
 
-/// Error returned by a failure to resolve a path of indices
+/// The reason resolution failed, before it's attached to the resolved prefix of the path
+#[derive(Debug)]
+pub(crate) enum ResolveErrorKind {
+    MismatchedTy { expected: JsonTy, actual: JsonTy },
+    MissingIdx(Idx),
+    ExceedsLimit { idx: usize, limit: usize },
+}
+
+impl ResolveErrorKind {
+    pub(crate) fn mismatched(expected: JsonTy, got: &Value) -> ResolveErrorKind {
+        ResolveErrorKind::MismatchedTy {
+            expected,
+            actual: got.into(),
+        }
+    }
+}
+
+/// Error returned by a failure to resolve a path of indices. Each variant carries the prefix of
+/// the path which was successfully resolved before the failure
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum ResolveError {
     /// Expected next item in the path to be a specific type, but it wasn't
@@ -130,41 +404,299 @@ pub enum ResolveError {
         expected: JsonTy,
         /// Type that was found
         actual: JsonTy,
+        /// Prefix of the path resolved before the failure
+        resolved: IdxPath,
     },
     /// Expected an index to exist, but it didn't
-    MissingIdx(Idx),
+    MissingIdx {
+        /// Index that was missing
+        idx: Idx,
+        /// Prefix of the path resolved before the failure
+        resolved: IdxPath,
+    },
+    /// Creating an index would have grown an array past the allowed length
+    ExceedsLimit {
+        /// Index that would have been created
+        idx: usize,
+        /// Maximum length arrays may be grown to
+        limit: usize,
+        /// Prefix of the path resolved before the failure
+        resolved: IdxPath,
+    },
 }
 
 impl ResolveError {
-    pub(crate) fn mismatched(expected: JsonTy, got: &Value) -> ResolveError {
-        ResolveError::MismatchedTy {
-            expected,
-            actual: got.into(),
+    pub(crate) fn new(kind: ResolveErrorKind, resolved: &[Idx]) -> ResolveError {
+        let resolved = IdxPath::new(resolved.to_vec());
+        match kind {
+            ResolveErrorKind::MismatchedTy { expected, actual } => ResolveError::MismatchedTy {
+                expected,
+                actual,
+                resolved,
+            },
+            ResolveErrorKind::MissingIdx(idx) => ResolveError::MissingIdx { idx, resolved },
+            ResolveErrorKind::ExceedsLimit { idx, limit } => ResolveError::ExceedsLimit {
+                idx,
+                limit,
+                resolved,
+            },
+        }
+    }
+
+    /// Get the zero-based index into the path at which resolution failed
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.resolved().len()
+    }
+
+    /// Get the prefix of the path which was successfully resolved before the failure
+    #[must_use]
+    pub fn resolved(&self) -> &IdxPath {
+        match self {
+            ResolveError::MismatchedTy { resolved, .. }
+            | ResolveError::MissingIdx { resolved, .. }
+            | ResolveError::ExceedsLimit { resolved, .. } => resolved,
         }
     }
 }
 
 impl fmt::Display for ResolveError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to resolve path at depth {} (`{}`): ",
+            self.depth(),
+            self.resolved()
+        )?;
         match self {
-            ResolveError::MismatchedTy { expected, actual } => {
-                write!(
-                    f,
-                    "Resolved path expected type {}, instead got type {}",
-                    expected, actual
-                )
+            ResolveError::MismatchedTy {
+                expected, actual, ..
+            } => {
+                write!(f, "expected {}, found {}", expected, actual)
             }
-            ResolveError::MissingIdx(idx) => {
+            ResolveError::MissingIdx { idx, .. } => {
                 let idx = match idx {
                     Idx::Array(i) => i as &dyn fmt::Debug,
                     Idx::Object(i) => i as &dyn fmt::Debug,
                 };
+                write!(f, "expected an index {:?}, but it didn't exist", idx)
+            }
+            ResolveError::ExceedsLimit { idx, limit, .. } => {
+                write!(
+                    f,
+                    "creating index {} would grow an array past the limit of {}",
+                    idx, limit
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for ResolveError {}
+
+/// The kind of an [`IdxPathParseError`]
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdxPathParseErrorKind {
+    /// Found a character which isn't valid at this position
+    UnexpectedChar(char),
+    /// The input ended before the path was complete
+    UnexpectedEnd,
+    /// The input used a JSON Path feature which can't be part of a path of indices, such as a
+    /// wildcard or filter
+    Unsupported(&'static str),
+    /// An array index was too large to represent
+    InvalidIndex,
+    /// A quoted key contained an invalid escape sequence
+    InvalidEscape,
+}
+
+impl fmt::Display for IdxPathParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdxPathParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            IdxPathParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            IdxPathParseErrorKind::Unsupported(feature) => {
+                write!(f, "{} are not supported in index paths", feature)
+            }
+            IdxPathParseErrorKind::InvalidIndex => write!(f, "array index out of range"),
+            IdxPathParseErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+        }
+    }
+}
+
+/// Error returned by a failure to parse an [`IdxPath`] from a string
+#[derive(Debug)]
+pub struct IdxPathParseError {
+    kind: IdxPathParseErrorKind,
+    position: usize,
+}
+
+impl IdxPathParseError {
+    pub(crate) fn new(kind: IdxPathParseErrorKind, position: usize) -> IdxPathParseError {
+        IdxPathParseError { kind, position }
+    }
+
+    /// Get the kind of this error
+    #[must_use]
+    pub fn kind(&self) -> &IdxPathParseErrorKind {
+        &self.kind
+    }
+
+    /// Get the byte offset in the input at which parsing failed
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for IdxPathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse index path at position {}: {}",
+            self.position, self.kind
+        )
+    }
+}
+
+impl error::Error for IdxPathParseError {}
+
+/// The kind of an [`EvalError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EvalErrorKind {
+    /// The path used a feature that isn't supported in the position it appeared
+    Unsupported(&'static str),
+    /// An operation expected a value of a specific type, but got a value of a different type
+    MismatchedTypes {
+        /// Type that was expected
+        expected: JsonTy,
+        /// Type that was found
+        found: JsonTy,
+    },
+    /// A numeric value was outside the range the operation could represent
+    OutOfRange,
+    /// A sub-path used as a value matched no items
+    MatchedNone,
+    /// A sub-path used as a value matched more than one item
+    MatchedMany(usize),
+    /// An ID was requested for an item which has no parent, such as the root
+    NoId,
+    /// Integer arithmetic overflowed
+    Overflow,
+    /// An integer remainder had a zero denominator
+    DivideByZero,
+}
+
+impl EvalErrorKind {
+    pub(crate) fn mismatched<T: Json>(expected: JsonTy, got: &T) -> EvalErrorKind {
+        EvalErrorKind::MismatchedTypes {
+            expected,
+            found: got.ty(),
+        }
+    }
+}
+
+impl fmt::Display for EvalErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalErrorKind::Unsupported(feature) => {
+                write!(f, "Unsupported feature in path: {}", feature)
+            }
+            EvalErrorKind::MismatchedTypes { expected, found } => {
                 write!(
                     f,
-                    "Resolved path expected an index {:?}, but it didn't exist",
-                    idx
+                    "Expression expected type {}, instead got type {}",
+                    expected, found
                 )
             }
+            EvalErrorKind::OutOfRange => write!(f, "Numeric value out of range"),
+            EvalErrorKind::MatchedNone => {
+                write!(f, "Sub-path expected one match, but found none")
+            }
+            EvalErrorKind::MatchedMany(count) => write!(
+                f,
+                "Sub-path expected one match, but found {} matches",
+                count
+            ),
+            EvalErrorKind::NoId => write!(f, "Requested the ID of an item with no parent"),
+            EvalErrorKind::Overflow => write!(f, "Integer arithmetic overflowed"),
+            EvalErrorKind::DivideByZero => write!(f, "Attempted to divide by zero"),
         }
     }
 }
+
+/// Error returned by a failure to evaluate part of a JSON Path on a value
+#[derive(Debug)]
+pub struct EvalError {
+    kind: EvalErrorKind,
+    #[cfg(feature = "spanned")]
+    span: Option<Span>,
+}
+
+impl EvalError {
+    /// Get the kind of this error
+    #[must_use]
+    pub fn kind(&self) -> &EvalErrorKind {
+        &self.kind
+    }
+
+    /// Whether this error was caused by an operation receiving a value of the wrong type
+    #[must_use]
+    pub fn is_type_mismatch(&self) -> bool {
+        matches!(self.kind, EvalErrorKind::MismatchedTypes { .. })
+    }
+
+    /// Whether this error was caused by the structure of the value, such as a sub-path matching
+    /// no items or more than one item, or an ID being requested for the root
+    #[must_use]
+    pub fn is_structural(&self) -> bool {
+        matches!(
+            self.kind,
+            EvalErrorKind::MatchedNone | EvalErrorKind::MatchedMany(_) | EvalErrorKind::NoId
+        )
+    }
+
+    /// Whether this error was caused by integer arithmetic overflowing, or a division by zero
+    #[must_use]
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self.kind,
+            EvalErrorKind::Overflow | EvalErrorKind::DivideByZero
+        )
+    }
+
+    /// Get the source span of the expression or sub-path which caused this error, if known
+    #[cfg(feature = "spanned")]
+    #[must_use]
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Attach a span to this error, if it doesn't already have one. Errors are spanned as they
+    /// propagate outwards, so the innermost span is kept.
+    #[cfg(feature = "spanned")]
+    pub(crate) fn with_span(mut self, span: Span) -> EvalError {
+        self.span.get_or_insert(span);
+        self
+    }
+}
+
+impl From<EvalErrorKind> for EvalError {
+    fn from(kind: EvalErrorKind) -> Self {
+        EvalError {
+            kind,
+            #[cfg(feature = "spanned")]
+            span: None,
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl error::Error for EvalError {}
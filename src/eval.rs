@@ -1,16 +1,30 @@
+use core::cell::Cell;
 use core::hash::{Hash, Hasher};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+use crate::error::EvalError;
 use crate::idx::{Idx, IdxPath};
-use crate::utils::ValueExt;
+use crate::json::{Json, JsonArray, JsonObject, JsonRef};
 use serde_json::Value;
 
-pub type ValueMap<'a> = HashMap<RefKey<'a, Value>, &'a Value>;
+pub type ValueMap<'a, T = Value> = HashMap<RefKey<'a, T>, &'a T>;
+
+pub fn flatten_recur<'a, T: Json>(collect: &mut Vec<&'a T>, a: &'a T) {
+    collect.push(a);
+    a.iter().for_each(|a| flatten_recur(collect, a));
+}
 
-#[derive(Clone)]
 pub struct RefKey<'a, T>(&'a T);
 
+impl<T> Clone for RefKey<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RefKey<'_, T> {}
+
 impl<'a, T> PartialEq for RefKey<'a, T> {
     fn eq(&self, other: &Self) -> bool {
         core::ptr::eq(self.0, other.0)
@@ -25,119 +39,198 @@ impl<'a, T> Hash for RefKey<'a, T> {
     }
 }
 
-pub struct EvalCtx<'a, 'b> {
-    root: &'a Value,
-    cur_matched: Vec<&'a Value>,
-    parents: Cow<'b, ValueMap<'a>>,
+/// Options controlling how a path is evaluated, see [`JsonPath::try_find_with`]
+///
+/// [`JsonPath::try_find_with`]: crate::JsonPath::try_find_with
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvalOptions {
+    strict_filter_errors: bool,
+    strict_arithmetic: bool,
+}
+
+impl EvalOptions {
+    /// Create the default options. Filters which fail to evaluate on an item don't match it
+    #[must_use]
+    pub fn new() -> EvalOptions {
+        EvalOptions::default()
+    }
+
+    /// Set whether a filter which fails to evaluate on an item, such as by comparing a number to
+    /// a string, causes evaluation to fail instead of not matching the item
+    #[must_use]
+    pub fn strict_filter_errors(mut self, strict: bool) -> EvalOptions {
+        self.strict_filter_errors = strict;
+        self
+    }
+
+    /// Whether filter errors cause evaluation to fail
+    #[must_use]
+    pub fn is_strict_filter_errors(&self) -> bool {
+        self.strict_filter_errors
+    }
+
+    /// Set whether integer overflow and division by zero in a filter cause evaluation to fail
+    /// instead of not matching the item. This applies even if other filter errors aren't strict
+    #[must_use]
+    pub fn strict_arithmetic(mut self, strict: bool) -> EvalOptions {
+        self.strict_arithmetic = strict;
+        self
+    }
+
+    /// Whether arithmetic errors cause evaluation to fail
+    #[must_use]
+    pub fn is_strict_arithmetic(&self) -> bool {
+        self.strict_arithmetic
+    }
 }
 
-impl<'a, 'b> EvalCtx<'a, 'b> {
-    pub fn new(root: &'a Value) -> EvalCtx<'a, 'b> {
+pub struct EvalCtx<'a, 'b, T: Json = Value> {
+    root: &'a T,
+    cur_matched: Vec<&'a T>,
+    parents: Cow<'b, ValueMap<'a, T>>,
+    has_parents: bool,
+    options: EvalOptions,
+    error: Cell<Option<EvalError>>,
+}
+
+impl<'a, 'b, T: Json> EvalCtx<'a, 'b, T> {
+    pub fn new(root: &'a T) -> EvalCtx<'a, 'b, T> {
         EvalCtx {
             root,
             cur_matched: vec![root],
             parents: Cow::Owned(HashMap::new()),
+            has_parents: false,
+            options: EvalOptions::default(),
+            error: Cell::new(None),
         }
     }
 
-    pub fn new_parents<'c>(root: &'a Value, parents: &'c ValueMap<'a>) -> EvalCtx<'a, 'c> {
+    /// Create a context for evaluating a sub-path, sharing the parents and options of this one
+    pub fn child<'c>(&'c self, root: &'a T) -> EvalCtx<'a, 'c, T> {
         EvalCtx {
             root,
             cur_matched: vec![root],
-            parents: Cow::Borrowed(parents),
+            parents: Cow::Borrowed(&self.parents),
+            has_parents: self.has_parents,
+            options: self.options,
+            error: Cell::new(None),
         }
     }
 
-    fn parents_recur(parents: &mut HashMap<RefKey<'a, Value>, &'a Value>, parent: &'a Value) {
+    fn parents_recur(parents: &mut ValueMap<'a, T>, parent: &'a T) {
         parent.iter().for_each(|child| {
             parents.insert(RefKey(child), parent);
-            EvalCtx::parents_recur(parents, child)
+            EvalCtx::<T>::parents_recur(parents, child)
         })
     }
 
     pub fn prepopulate_parents(&mut self) {
         Self::parents_recur(self.parents.to_mut(), self.root);
+        self.has_parents = true;
+    }
+
+    pub fn set_options(&mut self, options: EvalOptions) {
+        self.options = options;
+    }
+
+    pub fn options(&self) -> EvalOptions {
+        self.options
+    }
+
+    /// Record an error which should fail evaluation. Only the first error is kept
+    pub fn record_error(&self, err: EvalError) {
+        let prev = self.error.take();
+        self.error.set(prev.or(Some(err)));
+    }
+
+    pub fn take_error(&self) -> Option<EvalError> {
+        self.error.take()
     }
 
-    pub fn root(&self) -> &'a Value {
+    pub fn root(&self) -> &'a T {
         self.root
     }
 
-    pub fn all_parents(&self) -> &HashMap<RefKey<'a, Value>, &'a Value> {
-        &*self.parents
+    pub fn all_parents(&self) -> &ValueMap<'a, T> {
+        &self.parents
     }
 
-    pub fn idx_of(&self, val: &'a Value) -> Option<Idx> {
+    pub fn idx_of(&self, val: &'a T) -> Option<Idx> {
         let parent = self.parent_of(val)?;
-        match parent {
-            Value::Array(v) => v.iter().enumerate().find_map(|(idx, p)| {
-                if core::ptr::eq(p, val) {
-                    Some(Idx::Array(idx))
-                } else {
-                    None
-                }
-            }),
-            Value::Object(m) => m.iter().find_map(|(idx, p)| {
-                if core::ptr::eq(p, val) {
-                    Some(Idx::Object(idx.to_string()))
-                } else {
-                    None
-                }
-            }),
+        match parent.as_ref() {
+            JsonRef::Array(v) => v.iter().position(|p| core::ptr::eq(p, val)).map(Idx::Array),
+            JsonRef::Object(m) => m
+                .iter()
+                .find(|(_, p)| core::ptr::eq(*p, val))
+                .map(|(idx, _)| Idx::Object(idx.to_string())),
             _ => None,
         }
     }
 
-    pub fn parent_of(&self, val: &'a Value) -> Option<&'a Value> {
+    pub fn parent_of(&self, val: &'a T) -> Option<&'a T> {
         self.all_parents().get(&RefKey(val)).copied()
     }
 
-    pub fn get_matched(&self) -> &[&'a Value] {
+    pub fn get_matched(&self) -> &[&'a T] {
         &self.cur_matched
     }
 
     #[inline]
-    pub fn set_matched(&mut self, matched: Vec<&'a Value>) {
+    pub fn set_matched(&mut self, matched: Vec<&'a T>) {
         self.cur_matched = matched;
     }
 
     #[inline]
-    pub fn apply_matched_ref<'c, T>(
-        &'c self,
-        f: impl Fn(&'c Self, &'a Value) -> T,
-    ) -> Vec<&'a Value>
+    pub fn apply_matched_ref<'c, I>(&'c self, f: impl Fn(&'c Self, &'a T) -> I) -> Vec<&'a T>
     where
-        T: IntoIterator<Item = &'a Value>,
+        I: IntoIterator<Item = &'a T>,
     {
         self.cur_matched.iter().flat_map(|&i| f(self, i)).collect()
     }
 
     #[inline]
-    pub fn apply_matched<T>(&mut self, f: impl Fn(&Self, &'a Value) -> T)
+    pub fn apply_matched<I>(&mut self, f: impl Fn(&Self, &'a T) -> I)
     where
-        T: IntoIterator<Item = &'a Value>,
+        I: IntoIterator<Item = &'a T>,
     {
         self.cur_matched = self.apply_matched_ref(f);
     }
 
+    pub fn path_of(&self, val: &'a T) -> IdxPath {
+        let mut cur = val;
+        let mut out = Vec::new();
+        while let Some(p) = self.parent_of(cur) {
+            out.push(self.idx_of(cur).unwrap());
+            cur = p;
+        }
+        out.reverse();
+        IdxPath::new(out)
+    }
+
     pub fn paths_matched(&self) -> Vec<IdxPath> {
         self.cur_matched
             .iter()
-            .copied()
-            .map(|a| {
-                let mut cur = a;
-                let mut out = Vec::new();
-                while let Some(p) = self.parent_of(cur) {
-                    out.push(self.idx_of(cur).unwrap());
-                    cur = p;
-                }
-                out.reverse();
-                IdxPath::new(out)
-            })
+            .map(|&val| self.path_of(val))
+            .collect()
+    }
+
+    /// Consume this context, returning the path to each matched value along with the value
+    ///
+    /// # Panics
+    ///
+    /// - If parents weren't prepopulated, as paths can't be built without them
+    pub fn into_paths_and_values(self) -> Vec<(IdxPath, &'a T)> {
+        assert!(
+            self.has_parents,
+            "parents must be prepopulated to find the paths of matched values"
+        );
+        self.cur_matched
+            .iter()
+            .map(|&val| (self.path_of(val), val))
             .collect()
     }
 
-    pub fn into_matched(self) -> Vec<&'a Value> {
+    pub fn into_matched(self) -> Vec<&'a T> {
         self.cur_matched
     }
 }
@@ -1,12 +1,21 @@
 //! Items related to shortest-path indexing of JSON objects
 
-use crate::error::{JsonTy, ResolveError};
+use crate::error::{
+    IdxPathParseError, IdxPathParseErrorKind, JsonTy, ResolveError, ResolveErrorKind,
+};
+use crate::utils::remove_in;
 use core::cmp::Ordering;
+use core::fmt;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::iter::{FusedIterator, Peekable};
 use std::ops::{Deref, Index, IndexMut};
+use std::str::{CharIndices, FromStr};
 
-/// An index on a JSON object, either an integer index on an array or a string index on an object
-#[derive(Clone, Debug, PartialEq)]
+/// An index on a JSON object, either an integer index on an array or a string index on an object.
+///
+/// Indices are ordered with all array indices before all object indices, then by the index value
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Idx {
     /// An array index
     Array(usize),
@@ -46,6 +55,37 @@ impl Idx {
     }
 }
 
+impl From<usize> for Idx {
+    fn from(idx: usize) -> Self {
+        Idx::Array(idx)
+    }
+}
+
+impl From<&str> for Idx {
+    fn from(idx: &str) -> Self {
+        Idx::Object(idx.to_string())
+    }
+}
+
+impl From<String> for Idx {
+    fn from(idx: String) -> Self {
+        Idx::Object(idx)
+    }
+}
+
+impl fmt::Display for Idx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Idx::Array(i) => write!(f, "[{}]", i),
+            Idx::Object(key) => {
+                write!(f, "['")?;
+                write_escaped(f, key)?;
+                write!(f, "']")
+            }
+        }
+    }
+}
+
 impl From<Idx> for Value {
     fn from(idx: Idx) -> Self {
         match idx {
@@ -77,8 +117,11 @@ impl IndexMut<&Idx> for Value {
     }
 }
 
-/// A shortest-path set of indices on a JSON object
-#[derive(Clone, Debug, PartialEq)]
+/// A shortest-path set of indices on a JSON object.
+///
+/// Paths are ordered lexicographically by their indices, so a path is ordered directly before
+/// its children
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct IdxPath(Vec<Idx>);
 
 impl IdxPath {
@@ -92,6 +135,18 @@ impl IdxPath {
         &self.0
     }
 
+    /// Get the first index in this path, or `None` if this path is empty
+    #[must_use]
+    pub fn first(&self) -> Option<&Idx> {
+        self.0.first()
+    }
+
+    /// Get the last index in this path, or `None` if this path is empty
+    #[must_use]
+    pub fn last(&self) -> Option<&Idx> {
+        self.0.last()
+    }
+
     /// Remove the last `n` items from this path
     ///
     /// # Panics
@@ -99,13 +154,193 @@ impl IdxPath {
     /// - If `n` is greater than the length of this path
     #[must_use]
     pub fn remove(&self, n: usize) -> IdxPath {
-        assert!(
-            n <= self.len(),
-            "Cannot remove {} items from path, path is only {} items long",
-            n,
-            self.len()
-        );
-        IdxPath(self.0[..self.len() - n].to_owned())
+        self.try_remove(n).unwrap_or_else(|| {
+            panic!(
+                "Cannot remove {} items from path, path is only {} items long",
+                n,
+                self.len()
+            )
+        })
+    }
+
+    /// Remove the last `n` items from this path, or return `None` if `n` is greater than the
+    /// length of this path
+    #[must_use]
+    pub fn try_remove(&self, n: usize) -> Option<IdxPath> {
+        let len = self.len().checked_sub(n)?;
+        Some(IdxPath(self.0[..len].to_owned()))
+    }
+
+    /// Get the path to the parent of the item this path points to, or `None` if this path is
+    /// empty and so points to the root
+    #[must_use]
+    pub fn parent(&self) -> Option<IdxPath> {
+        self.try_remove(1)
+    }
+
+    /// Get a new path pointing to a child of the item this path points to
+    ///
+    /// ```
+    /// # use jsonpath_plus::idx::{Idx, IdxPath};
+    /// let path = IdxPath::from(vec![Idx::from("users")]);
+    /// assert_eq!(path.child(Idx::from(0)).to_string(), "$['users'][0]");
+    /// ```
+    #[must_use]
+    pub fn child(&self, idx: Idx) -> IdxPath {
+        let mut out = self.clone();
+        out.push(idx);
+        out
+    }
+
+    /// Whether the provided path is a prefix of this path. The empty path is a prefix of every
+    /// path, and every path is a prefix of itself
+    #[must_use]
+    pub fn starts_with(&self, prefix: &IdxPath) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    /// Whether this path points to an item within the item the provided path points to. This is
+    /// the same as `self.starts_with(ancestor)`, so every path is within itself
+    #[must_use]
+    pub fn within(&self, ancestor: &IdxPath) -> bool {
+        self.starts_with(ancestor)
+    }
+
+    /// Whether this path and the provided path point to items with the same parent. Every
+    /// non-empty path is a sibling of itself, while the empty path has no parent and so no
+    /// siblings
+    #[must_use]
+    pub fn is_sibling_of(&self, other: &IdxPath) -> bool {
+        match (self.0.split_last(), other.0.split_last()) {
+            (Some((_, left)), Some((_, right))) => left == right,
+            _ => false,
+        }
+    }
+
+    /// Remove the provided prefix from this path, returning the remainder, or `None` if this path
+    /// doesn't start with the prefix
+    #[must_use]
+    pub fn strip_prefix(&self, prefix: &IdxPath) -> Option<IdxPath> {
+        self.0
+            .strip_prefix(&*prefix.0)
+            .map(|rest| IdxPath(rest.to_vec()))
+    }
+
+    /// Get this path relative to the provided base path, or `None` if this path isn't inside the
+    /// base. Resolving the result on the item at `base` gives the same item as resolving this
+    /// path on the root
+    #[must_use]
+    pub fn relative_to(&self, base: &IdxPath) -> Option<IdxPath> {
+        self.strip_prefix(base)
+    }
+
+    /// Group paths into buckets by their first `depth` indices, keeping the paths in each bucket
+    /// in their original order. Paths shorter than `depth` are grouped under the whole path
+    #[must_use]
+    pub fn group_by_prefix(paths: &[IdxPath], depth: usize) -> HashMap<IdxPath, Vec<IdxPath>> {
+        let mut out: HashMap<IdxPath, Vec<IdxPath>> = HashMap::new();
+        for path in paths {
+            let prefix = IdxPath(path.0[..depth.min(path.len())].to_vec());
+            out.entry(prefix).or_default().push(path.clone());
+        }
+        out
+    }
+
+    /// Create a new path by appending the provided path to the end of this one
+    #[must_use]
+    pub fn join(&self, suffix: &IdxPath) -> IdxPath {
+        let mut out = self.clone();
+        out.0.extend_from_slice(&suffix.0);
+        out
+    }
+
+    /// Get the longest path which is a prefix of both this path and the provided one
+    #[must_use]
+    pub fn common_prefix(&self, other: &IdxPath) -> IdxPath {
+        let len = self
+            .0
+            .iter()
+            .zip(&other.0)
+            .take_while(|(left, right)| left == right)
+            .count();
+        IdxPath(self.0[..len].to_vec())
+    }
+
+    /// Add an index to the end of this path in-place
+    pub fn push(&mut self, idx: Idx) {
+        self.0.push(idx);
+    }
+
+    /// Render this path as an RFC 9535 normalized path string, such as `$['a'][0]`. This is the
+    /// same as the [`Display`](fmt::Display) implementation
+    #[must_use]
+    pub fn to_normalized_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Render this path as an RFC 6901 JSON Pointer, such as `/a/0`. The empty path is the empty
+    /// pointer
+    #[must_use]
+    pub fn to_pointer(&self) -> String {
+        let mut out = String::new();
+        for idx in &self.0 {
+            out.push('/');
+            match idx {
+                Idx::Array(i) => out.push_str(&i.to_string()),
+                Idx::Object(key) => out.push_str(&key.replace('~', "~0").replace('/', "~1")),
+            }
+        }
+        out
+    }
+
+    /// Remove the last index from this path in-place, returning it, or `None` if this path is
+    /// empty
+    pub fn pop(&mut self) -> Option<Idx> {
+        self.0.pop()
+    }
+
+    /// Split the first index off of this path, returning it along with the remaining path, or
+    /// `None` if this path is empty
+    #[must_use]
+    pub fn shift(mut self) -> Option<(Idx, IdxPath)> {
+        if self.0.is_empty() {
+            None
+        } else {
+            let first = self.0.remove(0);
+            Some((first, self))
+        }
+    }
+
+    /// Check that a single index can be resolved on a value, shared by all resolution methods so
+    /// they report identical errors
+    fn check_step(cur: &Value, idx: &Idx) -> Result<(), ResolveErrorKind> {
+        let (expected, exists) = match idx {
+            Idx::Array(i) => (JsonTy::Array, cur.as_array().map(|a| *i < a.len())),
+            Idx::Object(i) => (JsonTy::Object, cur.as_object().map(|o| o.contains_key(i))),
+        };
+
+        match exists {
+            Some(true) => Ok(()),
+            Some(false) => Err(ResolveErrorKind::MissingIdx(idx.clone())),
+            None => Err(ResolveErrorKind::mismatched(expected, cur)),
+        }
+    }
+
+    /// Parse an index path from a simple textual form, such as `a.b[2]['c.d']`. Keys may be
+    /// written after a dot or quoted in brackets, and array indices are written in brackets. The
+    /// path may optionally start with `$`, so the output of this type's `Display` impl can always
+    /// be parsed back. An empty string or `$` alone is the empty path.
+    ///
+    /// # Errors
+    ///
+    /// - If the string isn't a valid index path, or uses JSON Path features such as wildcards or
+    ///   filters which don't select a single index
+    pub fn parse(path: &str) -> Result<IdxPath, IdxPathParseError> {
+        IdxPathParser {
+            src: path,
+            chars: path.char_indices().peekable(),
+        }
+        .parse()
     }
 
     /// Resolve this path on a value, returning a reference to the result or an error indicating
@@ -115,28 +350,20 @@ impl IdxPath {
     ///
     /// - If the path cannot be resolved
     pub fn resolve_on<'a>(&self, value: &'a Value) -> Result<&'a Value, ResolveError> {
-        let mut cur = value;
+        self.resolve_iter(value)
+            .try_fold(value, |_, step| step.map(|(_, val)| val))
+    }
 
-        for idx in &self.0 {
-            match idx {
-                Idx::Array(i) => {
-                    cur = cur
-                        .as_array()
-                        .ok_or_else(|| ResolveError::mismatched(JsonTy::Array, cur))?
-                        .get(*i)
-                        .ok_or_else(|| ResolveError::MissingIdx(idx.clone()))?;
-                }
-                Idx::Object(i) => {
-                    cur = cur
-                        .as_object()
-                        .ok_or_else(|| ResolveError::mismatched(JsonTy::Object, cur))?
-                        .get(i)
-                        .ok_or_else(|| ResolveError::MissingIdx(idx.clone()))?;
-                }
-            }
+    /// Resolve this path on a value one index at a time, yielding each index along with the
+    /// value it resolved to. The root value itself is not yielded, so an empty path yields
+    /// nothing. If an index fails to resolve, the error is yielded and iteration stops
+    #[must_use]
+    pub fn resolve_iter<'a, 'p>(&'p self, value: &'a Value) -> ResolveIter<'a, 'p> {
+        ResolveIter {
+            path: self,
+            cur: Some(value),
+            depth: 0,
         }
-
-        Ok(cur)
     }
 
     /// Resolve this path on a value, returning a mutable reference to the result or an error
@@ -146,36 +373,124 @@ impl IdxPath {
     ///
     /// - If the path cannot be resolved
     pub fn resolve_on_mut<'a>(&self, value: &'a mut Value) -> Result<&'a mut Value, ResolveError> {
+        Self::resolve_slice_mut(&self.0, value)
+    }
+
+    fn resolve_slice_mut<'a>(
+        path: &[Idx],
+        value: &'a mut Value,
+    ) -> Result<&'a mut Value, ResolveError> {
         let mut cur = value;
 
-        for idx in &self.0 {
-            match idx {
-                Idx::Array(i) => {
-                    let json_ty = JsonTy::from(&*cur);
-                    cur = cur
-                        .as_array_mut()
-                        .ok_or(ResolveError::MismatchedTy {
-                            expected: JsonTy::Array,
-                            actual: json_ty,
-                        })?
-                        .get_mut(*i)
-                        .ok_or_else(|| ResolveError::MissingIdx(idx.clone()))?;
+        for (depth, idx) in path.iter().enumerate() {
+            Self::check_step(cur, idx).map_err(|kind| ResolveError::new(kind, &path[..depth]))?;
+            cur = &mut cur[idx];
+        }
+
+        Ok(cur)
+    }
+
+    /// Remove the item at this path from a value, returning the removed item. If this path is
+    /// empty, the value itself is taken and replaced with `null`
+    ///
+    /// # Errors
+    ///
+    /// - If the path cannot be resolved
+    pub fn delete_on(&self, value: &mut Value) -> Result<Value, ResolveError> {
+        let Some((last, parent_path)) = self.0.split_last() else {
+            return Ok(core::mem::take(value));
+        };
+
+        let parent = Self::resolve_slice_mut(parent_path, value)?;
+        Self::check_step(parent, last).map_err(|kind| ResolveError::new(kind, parent_path))?;
+        remove_in(parent, last).ok_or_else(|| {
+            ResolveError::new(ResolveErrorKind::MissingIdx(last.clone()), parent_path)
+        })
+    }
+
+    /// Replace the item at this path in a value, returning the previous item. If this path is
+    /// empty, the value itself is replaced
+    ///
+    /// # Errors
+    ///
+    /// - If the path cannot be resolved
+    pub fn replace_on(&self, value: &mut Value, new: Value) -> Result<Value, ResolveError> {
+        let target = self.resolve_on_mut(value)?;
+        Ok(core::mem::replace(target, new))
+    }
+
+    /// Resolve this path on a value, creating any missing items along the way, and return a
+    /// reference to the result. This uses the default [`CreateOptions`], see
+    /// [`IdxPath::create_on_with`] for details.
+    ///
+    /// # Errors
+    ///
+    /// - If an existing item along the path is the wrong type
+    /// - If an array would have to be grown past the default limit
+    pub fn create_on<'a>(&self, value: &'a mut Value) -> Result<&'a mut Value, ResolveError> {
+        self.create_on_with(value, CreateOptions::new())
+    }
+
+    /// Resolve this path on a value, creating any missing items along the way, and return a
+    /// reference to the result. Missing object members are inserted, and arrays are extended with
+    /// `null` up to the required index. `null` items along the path are replaced with an empty
+    /// container of the needed type, and a newly created final item is `null`.
+    ///
+    /// # Errors
+    ///
+    /// - If an existing item along the path is the wrong type, and overwriting is disabled
+    /// - If an array would have to be grown past the configured limit
+    pub fn create_on_with<'a>(
+        &self,
+        value: &'a mut Value,
+        options: CreateOptions,
+    ) -> Result<&'a mut Value, ResolveError> {
+        let mut cur = value;
+
+        for (depth, idx) in self.0.iter().enumerate() {
+            cur = Self::create_step(cur, idx, options)
+                .map_err(|kind| ResolveError::new(kind, &self.0[..depth]))?;
+        }
+
+        Ok(cur)
+    }
+
+    fn create_step<'a>(
+        cur: &'a mut Value,
+        idx: &Idx,
+        options: CreateOptions,
+    ) -> Result<&'a mut Value, ResolveErrorKind> {
+        match idx {
+            Idx::Array(i) => {
+                if cur.is_null() || (options.overwrite && !cur.is_array()) {
+                    *cur = Value::Array(Vec::new());
+                }
+                match cur {
+                    Value::Array(a) => {
+                        if *i >= a.len() {
+                            if *i >= options.array_limit {
+                                return Err(ResolveErrorKind::ExceedsLimit {
+                                    idx: *i,
+                                    limit: options.array_limit,
+                                });
+                            }
+                            a.resize(*i + 1, Value::Null);
+                        }
+                        Ok(&mut a[*i])
+                    }
+                    other => Err(ResolveErrorKind::mismatched(JsonTy::Array, other)),
+                }
+            }
+            Idx::Object(key) => {
+                if cur.is_null() || (options.overwrite && !cur.is_object()) {
+                    *cur = Value::Object(serde_json::Map::new());
                 }
-                Idx::Object(i) => {
-                    let json_ty = JsonTy::from(&*cur);
-                    cur = cur
-                        .as_object_mut()
-                        .ok_or(ResolveError::MismatchedTy {
-                            expected: JsonTy::Array,
-                            actual: json_ty,
-                        })?
-                        .get_mut(i)
-                        .ok_or_else(|| ResolveError::MissingIdx(idx.clone()))?;
+                match cur {
+                    Value::Object(m) => Ok(m.entry(key.clone()).or_insert(Value::Null)),
+                    other => Err(ResolveErrorKind::mismatched(JsonTy::Object, other)),
                 }
             }
         }
-
-        Ok(cur)
     }
 
     pub(crate) fn sort_specific_last(left: &IdxPath, right: &IdxPath) -> Ordering {
@@ -196,6 +511,280 @@ impl IdxPath {
     }
 }
 
+/// Options controlling how [`IdxPath::create_on_with`] creates missing items
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CreateOptions {
+    array_limit: usize,
+    overwrite: bool,
+}
+
+impl CreateOptions {
+    /// Create the default options. Arrays may be grown to a length of at most 4096, and existing
+    /// items of the wrong type are errors
+    #[must_use]
+    pub fn new() -> CreateOptions {
+        CreateOptions {
+            array_limit: 4096,
+            overwrite: false,
+        }
+    }
+
+    /// Set the maximum length arrays may be grown to when creating an index. Arrays already longer
+    /// than this can still be indexed
+    #[must_use]
+    pub fn array_limit(mut self, limit: usize) -> CreateOptions {
+        self.array_limit = limit;
+        self
+    }
+
+    /// Set whether existing items of the wrong type along the path are replaced with an empty
+    /// container of the needed type, instead of being an error
+    #[must_use]
+    pub fn overwrite(mut self, overwrite: bool) -> CreateOptions {
+        self.overwrite = overwrite;
+        self
+    }
+}
+
+impl Default for CreateOptions {
+    fn default() -> Self {
+        CreateOptions::new()
+    }
+}
+
+/// Iterator over the values along an [`IdxPath`], created by [`IdxPath::resolve_iter`]
+pub struct ResolveIter<'a, 'p> {
+    path: &'p IdxPath,
+    cur: Option<&'a Value>,
+    depth: usize,
+}
+
+impl<'a, 'p> Iterator for ResolveIter<'a, 'p> {
+    type Item = Result<(&'p Idx, &'a Value), ResolveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.cur?;
+        let idx = self.path.0.get(self.depth)?;
+
+        match IdxPath::check_step(cur, idx) {
+            Ok(()) => {
+                let next = &cur[idx];
+                self.cur = Some(next);
+                self.depth += 1;
+                Some(Ok((idx, next)))
+            }
+            Err(kind) => {
+                self.cur = None;
+                Some(Err(ResolveError::new(kind, &self.path.0[..self.depth])))
+            }
+        }
+    }
+}
+
+impl FusedIterator for ResolveIter<'_, '_> {}
+
+impl fmt::Display for IdxPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "$")?;
+        for idx in &self.0 {
+            write!(f, "{}", idx)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a key escaped as in a normalized path string literal
+fn write_escaped(f: &mut fmt::Formatter<'_>, key: &str) -> fmt::Result {
+    for c in key.chars() {
+        match c {
+            '\'' => write!(f, "\\'")?,
+            '\\' => write!(f, "\\\\")?,
+            '\u{8}' => write!(f, "\\b")?,
+            '\u{c}' => write!(f, "\\f")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for IdxPath {
+    type Err = IdxPathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IdxPath::parse(s)
+    }
+}
+
+/// Hand-written parser for the simple textual form of an index path
+struct IdxPathParser<'s> {
+    src: &'s str,
+    chars: Peekable<CharIndices<'s>>,
+}
+
+impl IdxPathParser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.src.len(), |&(pos, _)| pos)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.chars.next_if(|&(_, next)| next == c).is_some()
+    }
+
+    fn error(&mut self, kind: IdxPathParseErrorKind) -> IdxPathParseError {
+        IdxPathParseError::new(kind, self.position())
+    }
+
+    fn unexpected(&mut self) -> IdxPathParseError {
+        let kind = match self.peek() {
+            Some(c) => IdxPathParseErrorKind::UnexpectedChar(c),
+            None => IdxPathParseErrorKind::UnexpectedEnd,
+        };
+        self.error(kind)
+    }
+
+    fn parse(mut self) -> Result<IdxPath, IdxPathParseError> {
+        let mut path = Vec::new();
+
+        // Without a leading `$`, the path may start directly with a key
+        if !self.eat('$') && !matches!(self.peek(), None | Some('[')) {
+            path.push(self.name()?);
+        }
+
+        while let Some(c) = self.peek() {
+            match c {
+                '.' => {
+                    self.chars.next();
+                    if self.peek() == Some('.') {
+                        return Err(
+                            self.error(IdxPathParseErrorKind::Unsupported("recursive descents"))
+                        );
+                    }
+                    path.push(self.name()?);
+                }
+                '[' => {
+                    self.chars.next();
+                    path.push(self.bracket()?);
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+
+        Ok(IdxPath(path))
+    }
+
+    fn name(&mut self) -> Result<Idx, IdxPathParseError> {
+        if self.peek() == Some('*') {
+            return Err(self.error(IdxPathParseErrorKind::Unsupported("wildcards")));
+        }
+
+        let start = self.position();
+        while self
+            .chars
+            .next_if(|&(_, c)| !matches!(c, '.' | '[' | ']' | '*' | '\'' | '"'))
+            .is_some()
+        {}
+        let end = self.position();
+
+        if start == end {
+            return Err(self.unexpected());
+        }
+        Ok(Idx::Object(self.src[start..end].to_string()))
+    }
+
+    fn bracket(&mut self) -> Result<Idx, IdxPathParseError> {
+        let idx = match self.peek() {
+            Some('0'..='9') => Idx::Array(self.int()?),
+            Some('\'' | '"') => Idx::Object(self.string()?),
+            Some('*') => return Err(self.error(IdxPathParseErrorKind::Unsupported("wildcards"))),
+            Some('?') => return Err(self.error(IdxPathParseErrorKind::Unsupported("filters"))),
+            Some('-') => {
+                return Err(self.error(IdxPathParseErrorKind::Unsupported("negative indices")))
+            }
+            Some(':') => return Err(self.error(IdxPathParseErrorKind::Unsupported("slices"))),
+            _ => return Err(self.unexpected()),
+        };
+
+        match self.peek() {
+            Some(']') => {
+                self.chars.next();
+                Ok(idx)
+            }
+            Some(':') => Err(self.error(IdxPathParseErrorKind::Unsupported("slices"))),
+            Some(',') => Err(self.error(IdxPathParseErrorKind::Unsupported("unions"))),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn int(&mut self) -> Result<usize, IdxPathParseError> {
+        let start = self.position();
+        while self.chars.next_if(|&(_, c)| c.is_ascii_digit()).is_some() {}
+        let end = self.position();
+
+        self.src[start..end]
+            .parse()
+            .map_err(|_| IdxPathParseError::new(IdxPathParseErrorKind::InvalidIndex, start))
+    }
+
+    fn string(&mut self) -> Result<String, IdxPathParseError> {
+        let quote = self.chars.next().map(|(_, c)| c);
+        let mut out = String::new();
+
+        loop {
+            let pos = self.position();
+            let c = match self.chars.next() {
+                Some((_, c)) => c,
+                None => return Err(self.error(IdxPathParseErrorKind::UnexpectedEnd)),
+            };
+
+            if Some(c) == quote {
+                return Ok(out);
+            } else if c != '\\' {
+                out.push(c);
+                continue;
+            }
+
+            let escaped = match self.chars.next().map(|(_, c)| c) {
+                Some('b') => '\u{8}',
+                Some('f') => '\u{c}',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some(c @ ('\\' | '/' | '\'' | '"')) => c,
+                Some('u') => self.unicode_escape(pos)?,
+                _ => {
+                    return Err(IdxPathParseError::new(
+                        IdxPathParseErrorKind::InvalidEscape,
+                        pos,
+                    ))
+                }
+            };
+            out.push(escaped);
+        }
+    }
+
+    fn unicode_escape(&mut self, pos: usize) -> Result<char, IdxPathParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|(_, c)| c.to_digit(16))
+                .ok_or_else(|| IdxPathParseError::new(IdxPathParseErrorKind::InvalidEscape, pos))?;
+            code = code * 16 + digit;
+        }
+        char::from_u32(code)
+            .ok_or_else(|| IdxPathParseError::new(IdxPathParseErrorKind::InvalidEscape, pos))
+    }
+}
+
 impl Deref for IdxPath {
     type Target = [Idx];
 
@@ -209,3 +798,127 @@ impl From<Vec<Idx>> for IdxPath {
         IdxPath(path)
     }
 }
+
+#[cfg(feature = "serde")]
+mod __serde {
+    use super::*;
+    use serde::de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    // Human-readable formats get an untagged number or string. Compact formats may not be
+    // self-describing, so they get an enum with the variant tagged instead.
+
+    const VARIANTS: &[&str] = &["Array", "Object"];
+
+    /// The tag of a compact [`Idx`], which formats may provide by name or by index
+    enum Variant {
+        Array,
+        Object,
+    }
+
+    struct VariantVisitor;
+
+    impl Visitor<'_> for VariantVisitor {
+        type Value = Variant;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "an index variant")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Variant, E> {
+            match v {
+                0 => Ok(Variant::Array),
+                1 => Ok(Variant::Object),
+                _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+            }
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Variant, E> {
+            match v {
+                "Array" => Ok(Variant::Array),
+                "Object" => Ok(Variant::Object),
+                other => Err(E::unknown_variant(other, VARIANTS)),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Variant {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_identifier(VariantVisitor)
+        }
+    }
+
+    impl Serialize for Idx {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match (self, serializer.is_human_readable()) {
+                (Idx::Array(i), true) => serializer.serialize_u64(*i as u64),
+                (Idx::Object(key), true) => serializer.serialize_str(key),
+                (Idx::Array(i), false) => {
+                    serializer.serialize_newtype_variant("Idx", 0, "Array", &(*i as u64))
+                }
+                (Idx::Object(key), false) => {
+                    serializer.serialize_newtype_variant("Idx", 1, "Object", key)
+                }
+            }
+        }
+    }
+
+    struct IdxVisitor;
+
+    impl<'de> Visitor<'de> for IdxVisitor {
+        type Value = Idx;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "an array index or object key")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Idx, E> {
+            usize::try_from(v)
+                .map(Idx::Array)
+                .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Idx, E> {
+            usize::try_from(v)
+                .map(Idx::Array)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Idx, E> {
+            Ok(Idx::Object(v.to_string()))
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Idx, E> {
+            Ok(Idx::Object(v))
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Idx, A::Error> {
+            match data.variant()? {
+                (Variant::Array, access) => self.visit_u64(access.newtype_variant()?),
+                (Variant::Object, access) => Ok(Idx::Object(access.newtype_variant()?)),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Idx {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_any(IdxVisitor)
+            } else {
+                deserializer.deserialize_enum("Idx", VARIANTS, IdxVisitor)
+            }
+        }
+    }
+
+    impl Serialize for IdxPath {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for IdxPath {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::<Idx>::deserialize(deserializer).map(IdxPath)
+        }
+    }
+}
@@ -0,0 +1,802 @@
+//! Generic abstraction over JSON-like values, allowing paths to be evaluated on value types other
+//! than [`serde_json::Value`].
+//!
+//! A value type implements [`Json`], naming the types it uses for arrays and objects, which in turn
+//! implement [`JsonArray`] and [`JsonObject`]. Implementations are provided for
+//! [`serde_json::Value`], for `json::JsonValue` with the `json` feature, and for `simd_json`'s
+//! `OwnedValue` and `BorrowedValue` with the `simd-json` feature, for `serde_yaml::Value` with
+//! the `yaml` feature, for `toml::Value` with the `toml` feature, and for `ciborium::Value` with
+//! the `cbor` feature. The array and object traits are implemented for [`Vec`] and
+//! [`HashMap`] so custom value types can reuse them.
+//!
+//! # YAML
+//!
+//! YAML values don't map exactly onto JSON, so the `serde_yaml` implementation treats them as
+//! follows:
+//!
+//! - Mapping entries with non-string keys are skipped when iterating a mapping, and can't be
+//!   selected by name. They still count towards the mapping's length
+//! - Tagged values behave as the value they tag when being indexed or compared, with the tag
+//!   being ignored. Matching a tagged value returns it with its tag, and replacing it replaces
+//!   the tag too
+//! - Numbers are integers if they fit in an `i64` or `u64`, and floats otherwise
+//! - Removing a mapping entry preserves the order of the remaining entries
+//!
+//! # TOML
+//!
+//! The `toml` implementation treats TOML values as follows:
+//!
+//! - Datetimes are treated as strings in their TOML format, so they can be compared to string
+//!   literals in filters. Mutating a datetime through [`Json::as_mut`] converts it to a string
+//! - TOML has no null, so an empty table is used instead. Deleting the root leaves an empty
+//!   table, and `null` literals in filters compare equal to empty tables
+//!
+//! # CBOR
+//!
+//! The `cbor` implementation treats CBOR values as follows:
+//!
+//! - Map entries with non-string keys are skipped when iterating a map, and can't be selected by
+//!   name, as with YAML
+//! - Byte strings are opaque values. They can be matched, replaced and deleted, but have no
+//!   children and aren't equal to anything in filters
+//! - Tagged values behave as the value they tag, and are matched and replaced along with the tag
+//! - Integers outside the range of an `i64` or `u64` are treated as floats
+
+use crate::error::JsonTy;
+use crate::idx::Idx;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FusedIterator;
+use std::borrow::Cow;
+use std::collections::{hash_map, HashMap};
+
+#[cfg(feature = "cbor")]
+mod cbor_impl;
+#[cfg(feature = "json")]
+mod json_impl;
+mod serde_json_impl;
+#[cfg(feature = "simd-json")]
+mod simd_json_impl;
+#[cfg(feature = "toml")]
+mod toml_impl;
+#[cfg(feature = "yaml")]
+mod yaml_impl;
+
+/// A number stored in a JSON value
+#[derive(Copy, Clone, Debug)]
+pub enum JsonNumber {
+    /// A signed integer
+    Int(i64),
+    /// An unsigned integer too large to fit in an `i64`
+    UInt(u64),
+    /// A floating-point number
+    Float(f64),
+}
+
+impl JsonNumber {
+    /// Get this number as an `i64`, if it's an integer which fits
+    #[must_use]
+    pub fn as_i64(self) -> Option<i64> {
+        match self {
+            JsonNumber::Int(i) => Some(i),
+            JsonNumber::UInt(u) => i64::try_from(u).ok(),
+            JsonNumber::Float(_) => None,
+        }
+    }
+
+    /// Get this number as a `u64`, if it's an integer which fits
+    #[must_use]
+    pub fn as_u64(self) -> Option<u64> {
+        match self {
+            JsonNumber::Int(i) => u64::try_from(i).ok(),
+            JsonNumber::UInt(u) => Some(u),
+            JsonNumber::Float(_) => None,
+        }
+    }
+
+    /// Get this number as an `f64`, possibly losing precision
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_f64(self) -> f64 {
+        match self {
+            JsonNumber::Int(i) => i as f64,
+            JsonNumber::UInt(u) => u as f64,
+            JsonNumber::Float(f) => f,
+        }
+    }
+
+    /// Whether this number is an integer
+    #[must_use]
+    pub fn is_integer(self) -> bool {
+        !matches!(self, JsonNumber::Float(_))
+    }
+}
+
+/// Numbers compare by numeric value, so integers and floats of the same value are equal
+impl PartialEq for JsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_i64(), other.as_i64()) {
+            (Some(left), Some(right)) => left == right,
+            _ => match (self.as_u64(), other.as_u64()) {
+                (Some(left), Some(right)) => left == right,
+                _ => self.as_f64() == other.as_f64(),
+            },
+        }
+    }
+}
+
+/// Numbers order by numeric value, comparing integers exactly and falling back to floating point
+/// otherwise
+impl PartialOrd for JsonNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (JsonNumber::Float(_), _) | (_, JsonNumber::Float(_)) => {
+                self.as_f64().partial_cmp(&other.as_f64())
+            }
+            // A negative integer is less than any integer too large for an `i64`
+            (JsonNumber::Int(left), JsonNumber::UInt(right)) => match u64::try_from(*left) {
+                Ok(left) => Some(left.cmp(right)),
+                Err(_) => Some(Ordering::Less),
+            },
+            (JsonNumber::UInt(left), JsonNumber::Int(right)) => match u64::try_from(*right) {
+                Ok(right) => Some(left.cmp(&right)),
+                Err(_) => Some(Ordering::Greater),
+            },
+            (JsonNumber::Int(left), JsonNumber::Int(right)) => Some(left.cmp(right)),
+            (JsonNumber::UInt(left), JsonNumber::UInt(right)) => Some(left.cmp(right)),
+        }
+    }
+}
+
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonNumber::Int(i) => write!(f, "{i}"),
+            JsonNumber::UInt(u) => write!(f, "{u}"),
+            JsonNumber::Float(fl) => write!(f, "{fl}"),
+        }
+    }
+}
+
+/// A reference to the contents of a JSON value
+pub enum JsonRef<'a, T: Json> {
+    /// A null value
+    Null,
+    /// A boolean value
+    Bool(bool),
+    /// A number value
+    Number(JsonNumber),
+    /// A string value. Backends which store some values as strings only when they're needed, such
+    /// as TOML datetimes, may provide an owned string
+    String(Cow<'a, str>),
+    /// An array value
+    Array(&'a T::Array),
+    /// An object value
+    Object(&'a T::Object),
+    /// A value with no JSON equivalent, such as a CBOR byte string. It has no children, isn't
+    /// equal to any value, and its type is reported as [`JsonTy::String`]
+    Opaque,
+}
+
+impl<T: Json> Clone for JsonRef<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            JsonRef::Null => JsonRef::Null,
+            JsonRef::Bool(b) => JsonRef::Bool(*b),
+            JsonRef::Number(n) => JsonRef::Number(*n),
+            JsonRef::String(s) => JsonRef::String(s.clone()),
+            JsonRef::Array(arr) => JsonRef::Array(*arr),
+            JsonRef::Object(obj) => JsonRef::Object(*obj),
+            JsonRef::Opaque => JsonRef::Opaque,
+        }
+    }
+}
+
+impl<T: Json> fmt::Debug for JsonRef<'_, T>
+where
+    T::Array: fmt::Debug,
+    T::Object: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonRef::Null => f.write_str("Null"),
+            JsonRef::Bool(b) => f.debug_tuple("Bool").field(b).finish(),
+            JsonRef::Number(n) => f.debug_tuple("Number").field(n).finish(),
+            JsonRef::String(s) => f.debug_tuple("String").field(s).finish(),
+            JsonRef::Array(arr) => f.debug_tuple("Array").field(arr).finish(),
+            JsonRef::Object(obj) => f.debug_tuple("Object").field(obj).finish(),
+            JsonRef::Opaque => f.write_str("Opaque"),
+        }
+    }
+}
+
+impl<'a, T: Json> JsonRef<'a, T> {
+    /// Whether this is a null value
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonRef::Null)
+    }
+
+    /// Whether this is a boolean value
+    #[must_use]
+    pub fn is_bool(&self) -> bool {
+        matches!(self, JsonRef::Bool(_))
+    }
+
+    /// Whether this is a number value
+    #[must_use]
+    pub fn is_number(&self) -> bool {
+        matches!(self, JsonRef::Number(_))
+    }
+
+    /// Whether this is a string value
+    #[must_use]
+    pub fn is_string(&self) -> bool {
+        matches!(self, JsonRef::String(_))
+    }
+
+    /// Whether this is an array value
+    #[must_use]
+    pub fn is_array(&self) -> bool {
+        matches!(self, JsonRef::Array(_))
+    }
+
+    /// Whether this is an object value
+    #[must_use]
+    pub fn is_object(&self) -> bool {
+        matches!(self, JsonRef::Object(_))
+    }
+
+    /// Get the boolean this value holds, if it's a boolean
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonRef::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get the number this value holds, if it's a number
+    #[must_use]
+    pub fn as_number(&self) -> Option<JsonNumber> {
+        match self {
+            JsonRef::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get the string this value holds, if it's a string
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the array this value holds, if it's an array
+    #[must_use]
+    pub fn as_array(&self) -> Option<&'a T::Array> {
+        match self {
+            JsonRef::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Get the object this value holds, if it's an object
+    #[must_use]
+    pub fn as_object(&self) -> Option<&'a T::Object> {
+        match self {
+            JsonRef::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+}
+
+/// A mutable reference to the contents of a JSON value. Numbers can't be modified in-place, and
+/// are instead provided by value
+pub enum JsonMut<'a, T: Json> {
+    /// A null value
+    Null,
+    /// A boolean value
+    Bool(&'a mut bool),
+    /// A number value
+    Number(JsonNumber),
+    /// A string value
+    String(&'a mut String),
+    /// An array value
+    Array(&'a mut T::Array),
+    /// An object value
+    Object(&'a mut T::Object),
+    /// A value with no JSON equivalent, such as a CBOR byte string
+    Opaque,
+}
+
+impl<T: Json> fmt::Debug for JsonMut<'_, T>
+where
+    T::Array: fmt::Debug,
+    T::Object: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonMut::Null => f.write_str("Null"),
+            JsonMut::Bool(b) => f.debug_tuple("Bool").field(b).finish(),
+            JsonMut::Number(n) => f.debug_tuple("Number").field(n).finish(),
+            JsonMut::String(s) => f.debug_tuple("String").field(s).finish(),
+            JsonMut::Array(arr) => f.debug_tuple("Array").field(arr).finish(),
+            JsonMut::Object(obj) => f.debug_tuple("Object").field(obj).finish(),
+            JsonMut::Opaque => f.write_str("Opaque"),
+        }
+    }
+}
+
+impl<'a, T: Json> JsonMut<'a, T> {
+    /// Whether this is a null value
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonMut::Null)
+    }
+
+    /// Whether this is a boolean value
+    #[must_use]
+    pub fn is_bool(&self) -> bool {
+        matches!(self, JsonMut::Bool(_))
+    }
+
+    /// Whether this is a number value
+    #[must_use]
+    pub fn is_number(&self) -> bool {
+        matches!(self, JsonMut::Number(_))
+    }
+
+    /// Whether this is a string value
+    #[must_use]
+    pub fn is_string(&self) -> bool {
+        matches!(self, JsonMut::String(_))
+    }
+
+    /// Whether this is an array value
+    #[must_use]
+    pub fn is_array(&self) -> bool {
+        matches!(self, JsonMut::Array(_))
+    }
+
+    /// Whether this is an object value
+    #[must_use]
+    pub fn is_object(&self) -> bool {
+        matches!(self, JsonMut::Object(_))
+    }
+
+    /// Get a mutable reference to the boolean this value holds, if it's a boolean
+    #[must_use]
+    pub fn into_bool(self) -> Option<&'a mut bool> {
+        match self {
+            JsonMut::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Get the number this value holds, if it's a number
+    #[must_use]
+    pub fn as_number(&self) -> Option<JsonNumber> {
+        match self {
+            JsonMut::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the string this value holds, if it's a string
+    #[must_use]
+    pub fn into_string(self) -> Option<&'a mut String> {
+        match self {
+            JsonMut::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the array this value holds, if it's an array
+    #[must_use]
+    pub fn into_array(self) -> Option<&'a mut T::Array> {
+        match self {
+            JsonMut::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the object this value holds, if it's an object
+    #[must_use]
+    pub fn into_object(self) -> Option<&'a mut T::Object> {
+        match self {
+            JsonMut::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+}
+
+/// A JSON-like value type which paths can be evaluated on
+pub trait Json: Clone + Sized {
+    /// The type used to store arrays of this value
+    type Array: JsonArray<Self>;
+    /// The type used to store objects of this value
+    type Object: JsonObject<Self>;
+
+    /// Get a reference to the contents of this value
+    fn as_ref(&self) -> JsonRef<'_, Self>;
+
+    /// Get a mutable reference to the contents of this value
+    fn as_mut(&mut self) -> JsonMut<'_, Self>;
+
+    /// Create a null value
+    fn null() -> Self;
+
+    /// Create a boolean value
+    fn from_bool(val: bool) -> Self;
+
+    /// Create a number value from an integer
+    fn from_i64(val: i64) -> Self;
+
+    /// Create a number value from a float
+    fn from_f64(val: f64) -> Self;
+
+    /// Create a number value from an unsigned integer. The default implementation falls back to a
+    /// float for values too large for an `i64`, so backends which can store a `u64` exactly should
+    /// override it
+    #[allow(clippy::cast_precision_loss)]
+    fn from_u64(val: u64) -> Self {
+        i64::try_from(val).map_or_else(|_| Self::from_f64(val as f64), Self::from_i64)
+    }
+
+    /// Create a string value
+    fn from_string(val: String) -> Self;
+
+    /// Create a value from an index, an array index becoming a number and an object index a
+    /// string
+    #[allow(clippy::cast_precision_loss)]
+    fn from_idx(idx: Idx) -> Self {
+        match idx {
+            Idx::Array(i) => {
+                u64::try_from(i).map_or_else(|_| Self::from_f64(i as f64), Self::from_u64)
+            }
+            Idx::Object(key) => Self::from_string(key),
+        }
+    }
+
+    /// Get the type of this value
+    fn ty(&self) -> JsonTy {
+        match self.as_ref() {
+            JsonRef::Null => JsonTy::Null,
+            JsonRef::Bool(_) => JsonTy::Bool,
+            JsonRef::Number(_) => JsonTy::Number,
+            // Opaque values are most often byte strings, the closest JSON type to which is a string
+            JsonRef::String(_) | JsonRef::Opaque => JsonTy::String,
+            JsonRef::Array(_) => JsonTy::Array,
+            JsonRef::Object(_) => JsonTy::Object,
+        }
+    }
+
+    /// Whether this value is null
+    fn is_null(&self) -> bool {
+        self.as_ref().is_null()
+    }
+
+    /// Whether this value is a boolean
+    fn is_bool(&self) -> bool {
+        self.as_ref().is_bool()
+    }
+
+    /// Whether this value is a number
+    fn is_number(&self) -> bool {
+        self.as_ref().is_number()
+    }
+
+    /// Whether this value is a string
+    fn is_string(&self) -> bool {
+        self.as_ref().is_string()
+    }
+
+    /// Whether this value is an array
+    fn is_array(&self) -> bool {
+        self.as_ref().is_array()
+    }
+
+    /// Whether this value is an object
+    fn is_object(&self) -> bool {
+        self.as_ref().is_object()
+    }
+
+    /// Get the boolean this value holds, if it's a boolean
+    fn as_bool(&self) -> Option<bool> {
+        self.as_ref().as_bool()
+    }
+
+    /// Get the number this value holds, if it's a number
+    fn as_number(&self) -> Option<JsonNumber> {
+        self.as_ref().as_number()
+    }
+
+    /// Get the array this value holds, if it's an array
+    fn as_array(&self) -> Option<&Self::Array> {
+        self.as_ref().as_array()
+    }
+
+    /// Get the object this value holds, if it's an object
+    fn as_object(&self) -> Option<&Self::Object> {
+        self.as_ref().as_object()
+    }
+
+    /// Get a mutable reference to the array this value holds, if it's an array
+    fn as_array_mut(&mut self) -> Option<&mut Self::Array> {
+        self.as_mut().into_array()
+    }
+
+    /// Get a mutable reference to the object this value holds, if it's an object
+    fn as_object_mut(&mut self) -> Option<&mut Self::Object> {
+        self.as_mut().into_object()
+    }
+
+    /// Iterate over the children of this value - the items of an array or the values of an
+    /// object. Other values have no children
+    fn iter(&self) -> JsonIter<'_, Self> {
+        match self.as_ref() {
+            JsonRef::Array(arr) => JsonIter::Array(arr.iter()),
+            JsonRef::Object(obj) => JsonIter::Object(obj.iter()),
+            _ => JsonIter::Other,
+        }
+    }
+
+    /// Compare two values for equality, as used by `==` in filters. By default arrays
+    /// and objects compare by their contents, and numbers by their numeric value. Backends with
+    /// their own notion of equality may override this - `serde_json` values use [`PartialEq`], so
+    /// `1` and `1.0` aren't equal
+    fn json_eq(&self, other: &Self) -> bool {
+        match (self.as_ref(), other.as_ref()) {
+            (JsonRef::Null, JsonRef::Null) => true,
+            (JsonRef::Bool(left), JsonRef::Bool(right)) => left == right,
+            (JsonRef::Number(left), JsonRef::Number(right)) => left == right,
+            (JsonRef::String(left), JsonRef::String(right)) => left == right,
+            (JsonRef::Array(left), JsonRef::Array(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right.iter())
+                        .all(|(left, right)| left.json_eq(right))
+            }
+            (JsonRef::Object(left), JsonRef::Object(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .all(|(key, left)| right.get(key).is_some_and(|right| left.json_eq(right)))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// An array of JSON values
+pub trait JsonArray<T: Json> {
+    /// Iterator over the items of this array
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Get the number of items in this array
+    fn len(&self) -> usize;
+
+    /// Whether this array has no items
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the item at an index, or `None` if it's out of bounds
+    fn get(&self, idx: usize) -> Option<&T>;
+
+    /// Get a mutable reference to the item at an index, or `None` if it's out of bounds
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T>;
+
+    /// Remove the item at an index, shifting all later items down, and return it. Returns `None`
+    /// if the index is out of bounds
+    fn remove(&mut self, idx: usize) -> Option<T>;
+
+    /// Add an item to the end of this array
+    fn push(&mut self, value: T);
+
+    /// Insert an item at an index, shifting all later items up
+    ///
+    /// # Panics
+    ///
+    /// - If the index is greater than the length of the array
+    fn insert(&mut self, idx: usize, value: T);
+
+    /// Iterate over the items in this array
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+/// An object of JSON values, mapping string keys to values
+pub trait JsonObject<T: Json> {
+    /// Iterator over the entries of this object
+    type Iter<'a>: Iterator<Item = (&'a str, &'a T)>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Iterator over the entries of this object, with mutable access to the values
+    type IterMut<'a>: Iterator<Item = (&'a str, &'a mut T)>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Get the number of entries in this object
+    fn len(&self) -> usize;
+
+    /// Whether this object has no entries
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the value for a key, or `None` if it isn't present
+    fn get(&self, key: &str) -> Option<&T>;
+
+    /// Get a mutable reference to the value for a key, or `None` if it isn't present
+    fn get_mut(&mut self, key: &str) -> Option<&mut T>;
+
+    /// Remove the entry for a key, returning its value, or `None` if it isn't present
+    fn remove(&mut self, key: &str) -> Option<T>;
+
+    /// Insert an entry, replacing the value of any existing entry with the same key
+    fn insert(&mut self, key: String, value: T);
+
+    /// Iterate over the entries in this object
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Iterate over the entries in this object, with mutable access to the values. Keys can't be
+    /// modified
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+
+    /// Iterate mutably over the values in this object
+    fn values_mut(&mut self) -> ValuesMut<'_, T, Self>
+    where
+        Self: Sized,
+    {
+        ValuesMut(self.iter_mut())
+    }
+}
+
+/// Iterator over mutable references to the values of an object, created by
+/// [`JsonObject::values_mut`]
+pub struct ValuesMut<'a, T: Json + 'a, O: JsonObject<T> + 'a>(O::IterMut<'a>);
+
+impl<'a, T: Json + 'a, O: JsonObject<T> + 'a> Iterator for ValuesMut<'a, T, O> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterator over the children of a JSON value, created by [`Json::iter`]
+pub enum JsonIter<'a, T: Json + 'a> {
+    /// Iterating the items of an array
+    Array(<T::Array as JsonArray<T>>::Iter<'a>),
+    /// Iterating the values of an object
+    Object(<T::Object as JsonObject<T>>::Iter<'a>),
+    /// A value with no children
+    Other,
+}
+
+impl<'a, T: Json + 'a> Iterator for JsonIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            JsonIter::Array(iter) => iter.next(),
+            JsonIter::Object(iter) => iter.next().map(|(_, val)| val),
+            JsonIter::Other => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            JsonIter::Array(iter) => iter.size_hint(),
+            JsonIter::Object(iter) => iter.size_hint(),
+            JsonIter::Other => (0, Some(0)),
+        }
+    }
+}
+
+impl<'a, T: Json + 'a> FusedIterator for JsonIter<'a, T>
+where
+    <T::Array as JsonArray<T>>::Iter<'a>: FusedIterator,
+    <T::Object as JsonObject<T>>::Iter<'a>: FusedIterator,
+{
+}
+
+impl<T: Json> JsonArray<T> for Vec<T> {
+    type Iter<'a>
+        = core::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn get(&self, idx: usize) -> Option<&T> {
+        <[T]>::get(self, idx)
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        <[T]>::get_mut(self, idx)
+    }
+
+    fn remove(&mut self, idx: usize) -> Option<T> {
+        (idx < self.len()).then(|| Vec::remove(self, idx))
+    }
+
+    fn push(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+
+    fn insert(&mut self, idx: usize, value: T) {
+        Vec::insert(self, idx, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        <[T]>::iter(self)
+    }
+}
+
+type StrEntry<'a, T> = fn((&'a String, &'a T)) -> (&'a str, &'a T);
+
+fn str_entry<'a, T>((key, val): (&'a String, &'a T)) -> (&'a str, &'a T) {
+    (key.as_str(), val)
+}
+
+type StrEntryMut<'a, T> = fn((&'a String, &'a mut T)) -> (&'a str, &'a mut T);
+
+fn str_entry_mut<'a, T>((key, val): (&'a String, &'a mut T)) -> (&'a str, &'a mut T) {
+    (key.as_str(), val)
+}
+
+impl<T: Json> JsonObject<T> for HashMap<String, T> {
+    type Iter<'a>
+        = core::iter::Map<hash_map::Iter<'a, String, T>, StrEntry<'a, T>>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = core::iter::Map<hash_map::IterMut<'a, String, T>, StrEntryMut<'a, T>>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&T> {
+        HashMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut T> {
+        HashMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<T> {
+        HashMap::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: T) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashMap::iter(self).map(str_entry as StrEntry<'_, T>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        HashMap::iter_mut(self).map(str_entry_mut as StrEntryMut<'_, T>)
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+use ciborium::value::{Integer, Value};
+
+#[allow(clippy::cast_precision_loss)]
+fn to_number(int: Integer) -> JsonNumber {
+    let int = i128::from(int);
+    if let Ok(i) = i64::try_from(int) {
+        JsonNumber::Int(i)
+    } else if let Ok(u) = u64::try_from(int) {
+        JsonNumber::UInt(u)
+    } else {
+        JsonNumber::Float(int as f64)
+    }
+}
+
+type CborEntry<'a> = fn(&'a (Value, Value)) -> Option<(&'a str, &'a Value)>;
+
+/// Map entries with non-string keys can't be indexed by a path, so they're skipped
+fn cbor_entry((key, val): &(Value, Value)) -> Option<(&str, &Value)> {
+    Some((key.as_text()?, val))
+}
+
+type CborEntryMut<'a> = fn(&'a mut (Value, Value)) -> Option<(&'a str, &'a mut Value)>;
+
+fn cbor_entry_mut((key, val): &mut (Value, Value)) -> Option<(&str, &mut Value)> {
+    Some((key.as_text()?, val))
+}
+
+impl Json for Value {
+    type Array = Vec<Value>;
+    type Object = Vec<(Value, Value)>;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            Value::Null => JsonRef::Null,
+            Value::Bool(b) => JsonRef::Bool(*b),
+            Value::Integer(i) => JsonRef::Number(to_number(*i)),
+            Value::Float(f) => JsonRef::Number(JsonNumber::Float(*f)),
+            Value::Text(s) => JsonRef::String(Cow::Borrowed(s)),
+            Value::Array(a) => JsonRef::Array(a),
+            Value::Map(m) => JsonRef::Object(m),
+            Value::Tag(_, val) => Json::as_ref(&**val),
+            _ => JsonRef::Opaque,
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        match self {
+            Value::Null => JsonMut::Null,
+            Value::Bool(b) => JsonMut::Bool(b),
+            Value::Integer(i) => JsonMut::Number(to_number(*i)),
+            Value::Float(f) => JsonMut::Number(JsonNumber::Float(*f)),
+            Value::Text(s) => JsonMut::String(s),
+            Value::Array(a) => JsonMut::Array(a),
+            Value::Map(m) => JsonMut::Object(m),
+            Value::Tag(_, val) => Json::as_mut(&mut **val),
+            _ => JsonMut::Opaque,
+        }
+    }
+
+    fn null() -> Self {
+        Value::Null
+    }
+
+    fn from_bool(val: bool) -> Self {
+        Value::Bool(val)
+    }
+
+    fn from_i64(val: i64) -> Self {
+        Value::Integer(val.into())
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Value::Float(val)
+    }
+
+    fn from_u64(val: u64) -> Self {
+        Value::Integer(val.into())
+    }
+
+    fn from_string(val: String) -> Self {
+        Value::Text(val)
+    }
+}
+
+impl JsonObject<Value> for Vec<(Value, Value)> {
+    type Iter<'a> = core::iter::FilterMap<core::slice::Iter<'a, (Value, Value)>, CborEntry<'a>>;
+    type IterMut<'a> =
+        core::iter::FilterMap<core::slice::IterMut<'a, (Value, Value)>, CborEntryMut<'a>>;
+
+    fn len(&self) -> usize {
+        <[(Value, Value)]>::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        <[(Value, Value)]>::iter(self)
+            .find(|(k, _)| k.as_text() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        <[(Value, Value)]>::iter_mut(self)
+            .find(|(k, _)| k.as_text() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        let idx = <[(Value, Value)]>::iter(self).position(|(k, _)| k.as_text() == Some(key))?;
+        Some(Vec::remove(self, idx).1)
+    }
+
+    fn insert(&mut self, key: String, value: Value) {
+        match JsonObject::get_mut(self, &key) {
+            Some(old) => *old = value,
+            None => Vec::push(self, (Value::Text(key), value)),
+        }
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        <[(Value, Value)]>::iter(self).filter_map(cbor_entry as CborEntry<'_>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        <[(Value, Value)]>::iter_mut(self).filter_map(cbor_entry_mut as CborEntryMut<'_>)
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use ::json::number::Number;
+use ::json::object::{self, Object};
+use ::json::JsonValue;
+
+fn to_number(num: Number) -> JsonNumber {
+    let (positive, mantissa, exponent) = num.as_parts();
+    // Numbers are stored as a decimal mantissa and exponent, so only those without a fractional
+    // part are integers
+    let int = u32::try_from(exponent)
+        .ok()
+        .and_then(|exp| 10u64.checked_pow(exp))
+        .and_then(|pow| mantissa.checked_mul(pow));
+
+    match int {
+        _ if num.is_nan() => JsonNumber::Float(f64::NAN),
+        Some(u) if positive => i64::try_from(u).map_or(JsonNumber::UInt(u), JsonNumber::Int),
+        Some(u) => i64::try_from(u)
+            .ok()
+            .map_or_else(|| JsonNumber::Float(num.into()), |i| JsonNumber::Int(-i)),
+        None => JsonNumber::Float(num.into()),
+    }
+}
+
+impl Json for JsonValue {
+    type Array = Vec<JsonValue>;
+    type Object = Object;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            JsonValue::Null => JsonRef::Null,
+            JsonValue::Boolean(b) => JsonRef::Bool(*b),
+            JsonValue::Number(n) => JsonRef::Number(to_number(*n)),
+            JsonValue::Short(s) => JsonRef::String(Cow::Borrowed(s.as_str())),
+            JsonValue::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            JsonValue::Array(a) => JsonRef::Array(a),
+            JsonValue::Object(o) => JsonRef::Object(o),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        // Short strings are stored inline and can't be borrowed as a `String`, so convert them to
+        // an owned string in-place first
+        if let JsonValue::Short(s) = self {
+            *self = JsonValue::String(s.as_str().to_string());
+        }
+
+        match self {
+            JsonValue::Null => JsonMut::Null,
+            JsonValue::Boolean(b) => JsonMut::Bool(b),
+            JsonValue::Number(n) => JsonMut::Number(to_number(*n)),
+            JsonValue::Short(_) => unreachable!("short strings are converted above"),
+            JsonValue::String(s) => JsonMut::String(s),
+            JsonValue::Array(a) => JsonMut::Array(a),
+            JsonValue::Object(o) => JsonMut::Object(o),
+        }
+    }
+
+    fn null() -> Self {
+        JsonValue::Null
+    }
+
+    fn from_bool(val: bool) -> Self {
+        JsonValue::Boolean(val)
+    }
+
+    fn from_i64(val: i64) -> Self {
+        JsonValue::from(val)
+    }
+
+    fn from_f64(val: f64) -> Self {
+        JsonValue::from(val)
+    }
+
+    fn from_u64(val: u64) -> Self {
+        JsonValue::from(val)
+    }
+
+    fn from_string(val: String) -> Self {
+        JsonValue::String(val)
+    }
+}
+
+impl JsonObject<JsonValue> for Object {
+    type Iter<'a> = object::Iter<'a>;
+    type IterMut<'a> = object::IterMut<'a>;
+
+    fn len(&self) -> usize {
+        Object::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&JsonValue> {
+        Object::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        Object::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<JsonValue> {
+        Object::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: JsonValue) {
+        Object::insert(self, &key, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Object::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        Object::iter_mut(self)
+    }
+}
@@ -0,0 +1,105 @@
+use super::*;
+use serde_json::{Map, Number, Value};
+
+fn to_number(num: &Number) -> JsonNumber {
+    if let Some(i) = num.as_i64() {
+        JsonNumber::Int(i)
+    } else if let Some(u) = num.as_u64() {
+        JsonNumber::UInt(u)
+    } else {
+        JsonNumber::Float(num.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+impl Json for Value {
+    type Array = Vec<Value>;
+    type Object = Map<String, Value>;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            Value::Null => JsonRef::Null,
+            Value::Bool(b) => JsonRef::Bool(*b),
+            Value::Number(n) => JsonRef::Number(to_number(n)),
+            Value::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            Value::Array(a) => JsonRef::Array(a),
+            Value::Object(o) => JsonRef::Object(o),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        match self {
+            Value::Null => JsonMut::Null,
+            Value::Bool(b) => JsonMut::Bool(b),
+            Value::Number(n) => JsonMut::Number(to_number(n)),
+            Value::String(s) => JsonMut::String(s),
+            Value::Array(a) => JsonMut::Array(a),
+            Value::Object(o) => JsonMut::Object(o),
+        }
+    }
+
+    fn null() -> Self {
+        Value::Null
+    }
+
+    fn from_bool(val: bool) -> Self {
+        Value::Bool(val)
+    }
+
+    fn from_i64(val: i64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_u64(val: u64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_string(val: String) -> Self {
+        Value::String(val)
+    }
+
+    fn ty(&self) -> JsonTy {
+        JsonTy::from(self)
+    }
+
+    fn json_eq(&self, other: &Self) -> bool {
+        // Keep serde_json's own equality, where an integer never equals a float
+        self == other
+    }
+}
+
+impl JsonObject<Value> for Map<String, Value> {
+    type Iter<'a> = core::iter::Map<serde_json::map::Iter<'a>, StrEntry<'a, Value>>;
+    type IterMut<'a> = core::iter::Map<serde_json::map::IterMut<'a>, StrEntryMut<'a, Value>>;
+
+    fn len(&self) -> usize {
+        Map::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        Map::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        Map::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        Map::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: Value) {
+        Map::insert(self, key, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Map::iter(self).map(str_entry as StrEntry<'_, Value>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        Map::iter_mut(self).map(str_entry_mut as StrEntryMut<'_, Value>)
+    }
+}
@@ -0,0 +1,210 @@
+use super::*;
+use simd_json::{borrowed, owned, StaticNode};
+use std::borrow::Cow;
+
+fn to_number(node: StaticNode) -> Option<JsonNumber> {
+    match node {
+        StaticNode::I64(i) => Some(JsonNumber::Int(i)),
+        StaticNode::U64(u) => Some(JsonNumber::UInt(u)),
+        StaticNode::F64(f) => Some(JsonNumber::Float(f)),
+        StaticNode::Bool(_) | StaticNode::Null => None,
+    }
+}
+
+type CowEntry<'a, 'v, T> = fn((&'a Cow<'v, str>, &'a T)) -> (&'a str, &'a T);
+
+fn cow_entry<'a, 'v, T>((key, val): (&'a Cow<'v, str>, &'a T)) -> (&'a str, &'a T) {
+    (key, val)
+}
+
+type CowEntryMut<'a, 'v, T> = fn((&'a Cow<'v, str>, &'a mut T)) -> (&'a str, &'a mut T);
+
+fn cow_entry_mut<'a, 'v, T>((key, val): (&'a Cow<'v, str>, &'a mut T)) -> (&'a str, &'a mut T) {
+    (key, val)
+}
+
+impl Json for owned::Value {
+    type Array = Vec<owned::Value>;
+    type Object = owned::Object;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            owned::Value::Static(StaticNode::Null) => JsonRef::Null,
+            owned::Value::Static(StaticNode::Bool(b)) => JsonRef::Bool(*b),
+            owned::Value::Static(n) => JsonRef::Number(to_number(*n).unwrap()),
+            owned::Value::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            owned::Value::Array(a) => JsonRef::Array(a),
+            owned::Value::Object(o) => JsonRef::Object(&**o),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        match self {
+            owned::Value::Static(StaticNode::Null) => JsonMut::Null,
+            owned::Value::Static(StaticNode::Bool(b)) => JsonMut::Bool(b),
+            owned::Value::Static(n) => JsonMut::Number(to_number(*n).unwrap()),
+            owned::Value::String(s) => JsonMut::String(s),
+            owned::Value::Array(a) => JsonMut::Array(a),
+            owned::Value::Object(o) => JsonMut::Object(&mut **o),
+        }
+    }
+
+    fn null() -> Self {
+        owned::Value::Static(StaticNode::Null)
+    }
+
+    fn from_bool(val: bool) -> Self {
+        owned::Value::Static(StaticNode::Bool(val))
+    }
+
+    fn from_i64(val: i64) -> Self {
+        owned::Value::Static(StaticNode::I64(val))
+    }
+
+    fn from_f64(val: f64) -> Self {
+        owned::Value::Static(StaticNode::F64(val))
+    }
+
+    fn from_u64(val: u64) -> Self {
+        owned::Value::Static(StaticNode::U64(val))
+    }
+
+    fn from_string(val: String) -> Self {
+        owned::Value::String(val)
+    }
+}
+
+impl JsonObject<owned::Value> for owned::Object {
+    type Iter<'a> =
+        core::iter::Map<halfbrown::Iter<'a, String, owned::Value>, StrEntry<'a, owned::Value>>;
+    type IterMut<'a> = core::iter::Map<
+        halfbrown::IterMut<'a, String, owned::Value>,
+        StrEntryMut<'a, owned::Value>,
+    >;
+
+    fn len(&self) -> usize {
+        halfbrown::HashMap::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&owned::Value> {
+        halfbrown::HashMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut owned::Value> {
+        halfbrown::HashMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<owned::Value> {
+        halfbrown::HashMap::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: owned::Value) {
+        halfbrown::HashMap::insert(self, key, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        halfbrown::HashMap::iter(self).map(str_entry as StrEntry<'_, owned::Value>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        halfbrown::HashMap::iter_mut(self).map(str_entry_mut as StrEntryMut<'_, owned::Value>)
+    }
+}
+
+impl<'v> Json for borrowed::Value<'v> {
+    type Array = Vec<borrowed::Value<'v>>;
+    type Object = borrowed::Object<'v>;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            borrowed::Value::Static(StaticNode::Null) => JsonRef::Null,
+            borrowed::Value::Static(StaticNode::Bool(b)) => JsonRef::Bool(*b),
+            borrowed::Value::Static(n) => JsonRef::Number(to_number(*n).unwrap()),
+            borrowed::Value::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            borrowed::Value::Array(a) => JsonRef::Array(a),
+            borrowed::Value::Object(o) => JsonRef::Object(&**o),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        match self {
+            borrowed::Value::Static(StaticNode::Null) => JsonMut::Null,
+            borrowed::Value::Static(StaticNode::Bool(b)) => JsonMut::Bool(b),
+            borrowed::Value::Static(n) => JsonMut::Number(to_number(*n).unwrap()),
+            // Borrowed strings are copied into an owned string before being mutated
+            borrowed::Value::String(s) => JsonMut::String(s.to_mut()),
+            borrowed::Value::Array(a) => JsonMut::Array(a),
+            borrowed::Value::Object(o) => JsonMut::Object(&mut **o),
+        }
+    }
+
+    fn null() -> Self {
+        borrowed::Value::Static(StaticNode::Null)
+    }
+
+    fn from_bool(val: bool) -> Self {
+        borrowed::Value::Static(StaticNode::Bool(val))
+    }
+
+    fn from_i64(val: i64) -> Self {
+        borrowed::Value::Static(StaticNode::I64(val))
+    }
+
+    fn from_f64(val: f64) -> Self {
+        borrowed::Value::Static(StaticNode::F64(val))
+    }
+
+    fn from_u64(val: u64) -> Self {
+        borrowed::Value::Static(StaticNode::U64(val))
+    }
+
+    fn from_string(val: String) -> Self {
+        borrowed::Value::String(Cow::Owned(val))
+    }
+}
+
+impl<'v> JsonObject<borrowed::Value<'v>> for borrowed::Object<'v> {
+    type Iter<'a>
+        = core::iter::Map<
+        halfbrown::Iter<'a, Cow<'v, str>, borrowed::Value<'v>>,
+        CowEntry<'a, 'v, borrowed::Value<'v>>,
+    >
+    where
+        'v: 'a;
+    type IterMut<'a>
+        = core::iter::Map<
+        halfbrown::IterMut<'a, Cow<'v, str>, borrowed::Value<'v>>,
+        CowEntryMut<'a, 'v, borrowed::Value<'v>>,
+    >
+    where
+        'v: 'a;
+
+    fn len(&self) -> usize {
+        halfbrown::HashMap::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&borrowed::Value<'v>> {
+        halfbrown::HashMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut borrowed::Value<'v>> {
+        halfbrown::HashMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<borrowed::Value<'v>> {
+        halfbrown::HashMap::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: borrowed::Value<'v>) {
+        halfbrown::HashMap::insert(self, Cow::Owned(key), value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        halfbrown::HashMap::iter(self).map(cow_entry as CowEntry<'_, 'v, borrowed::Value<'v>>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        halfbrown::HashMap::iter_mut(self)
+            .map(cow_entry_mut as CowEntryMut<'_, 'v, borrowed::Value<'v>>)
+    }
+}
@@ -0,0 +1,90 @@
+use super::*;
+use toml::map::{self, Map};
+use toml::{Table, Value};
+
+impl Json for Value {
+    type Array = Vec<Value>;
+    type Object = Table;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            Value::Boolean(b) => JsonRef::Bool(*b),
+            Value::Integer(i) => JsonRef::Number(JsonNumber::Int(*i)),
+            Value::Float(f) => JsonRef::Number(JsonNumber::Float(*f)),
+            Value::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            Value::Datetime(dt) => JsonRef::String(Cow::Owned(dt.to_string())),
+            Value::Array(a) => JsonRef::Array(a),
+            Value::Table(t) => JsonRef::Object(t),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        // Datetimes are treated as strings, so convert them to one in-place first
+        if let Value::Datetime(dt) = self {
+            *self = Value::String(dt.to_string());
+        }
+
+        match self {
+            Value::Boolean(b) => JsonMut::Bool(b),
+            Value::Integer(i) => JsonMut::Number(JsonNumber::Int(*i)),
+            Value::Float(f) => JsonMut::Number(JsonNumber::Float(*f)),
+            Value::Datetime(_) => unreachable!("datetimes are converted above"),
+            Value::String(s) => JsonMut::String(s),
+            Value::Array(a) => JsonMut::Array(a),
+            Value::Table(t) => JsonMut::Object(t),
+        }
+    }
+
+    fn null() -> Self {
+        Value::Table(Table::new())
+    }
+
+    fn from_bool(val: bool) -> Self {
+        Value::Boolean(val)
+    }
+
+    fn from_i64(val: i64) -> Self {
+        Value::Integer(val)
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Value::Float(val)
+    }
+
+    fn from_string(val: String) -> Self {
+        Value::String(val)
+    }
+}
+
+impl JsonObject<Value> for Table {
+    type Iter<'a> = core::iter::Map<map::Iter<'a, String, Value>, StrEntry<'a, Value>>;
+    type IterMut<'a> = core::iter::Map<map::IterMut<'a, String, Value>, StrEntryMut<'a, Value>>;
+
+    fn len(&self) -> usize {
+        Map::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        Map::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        Map::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        Map::remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: Value) {
+        Map::insert(self, key, value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Map::iter(self).map(str_entry as StrEntry<'_, Value>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        Map::iter_mut(self).map(str_entry_mut as StrEntryMut<'_, Value>)
+    }
+}
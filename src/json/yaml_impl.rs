@@ -0,0 +1,112 @@
+use super::*;
+use serde_yaml::mapping::{self, Mapping};
+use serde_yaml::{Number, Value};
+
+fn to_number(num: &Number) -> JsonNumber {
+    if let Some(i) = num.as_i64() {
+        JsonNumber::Int(i)
+    } else if let Some(u) = num.as_u64() {
+        JsonNumber::UInt(u)
+    } else {
+        JsonNumber::Float(num.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+type YamlEntry<'a> = fn((&'a Value, &'a Value)) -> Option<(&'a str, &'a Value)>;
+
+/// Mapping entries with non-string keys can't be indexed by a path, so they're skipped
+fn yaml_entry<'a>((key, val): (&'a Value, &'a Value)) -> Option<(&'a str, &'a Value)> {
+    Some((key.as_str()?, val))
+}
+
+type YamlEntryMut<'a> = fn((&'a Value, &'a mut Value)) -> Option<(&'a str, &'a mut Value)>;
+
+fn yaml_entry_mut<'a>((key, val): (&'a Value, &'a mut Value)) -> Option<(&'a str, &'a mut Value)> {
+    Some((key.as_str()?, val))
+}
+
+impl Json for Value {
+    type Array = Vec<Value>;
+    type Object = Mapping;
+
+    fn as_ref(&self) -> JsonRef<'_, Self> {
+        match self {
+            Value::Null => JsonRef::Null,
+            Value::Bool(b) => JsonRef::Bool(*b),
+            Value::Number(n) => JsonRef::Number(to_number(n)),
+            Value::String(s) => JsonRef::String(Cow::Borrowed(s)),
+            Value::Sequence(a) => JsonRef::Array(a),
+            Value::Mapping(m) => JsonRef::Object(m),
+            Value::Tagged(tagged) => tagged.value.as_ref(),
+        }
+    }
+
+    fn as_mut(&mut self) -> JsonMut<'_, Self> {
+        match self {
+            Value::Null => JsonMut::Null,
+            Value::Bool(b) => JsonMut::Bool(b),
+            Value::Number(n) => JsonMut::Number(to_number(n)),
+            Value::String(s) => JsonMut::String(s),
+            Value::Sequence(a) => JsonMut::Array(a),
+            Value::Mapping(m) => JsonMut::Object(m),
+            Value::Tagged(tagged) => tagged.value.as_mut(),
+        }
+    }
+
+    fn null() -> Self {
+        Value::Null
+    }
+
+    fn from_bool(val: bool) -> Self {
+        Value::Bool(val)
+    }
+
+    fn from_i64(val: i64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_f64(val: f64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_u64(val: u64) -> Self {
+        Value::from(val)
+    }
+
+    fn from_string(val: String) -> Self {
+        Value::String(val)
+    }
+}
+
+impl JsonObject<Value> for Mapping {
+    type Iter<'a> = core::iter::FilterMap<mapping::Iter<'a>, YamlEntry<'a>>;
+    type IterMut<'a> = core::iter::FilterMap<mapping::IterMut<'a>, YamlEntryMut<'a>>;
+
+    fn len(&self) -> usize {
+        Mapping::len(self)
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        Mapping::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        Mapping::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Value> {
+        Mapping::shift_remove(self, key)
+    }
+
+    fn insert(&mut self, key: String, value: Value) {
+        Mapping::insert(self, Value::String(key), value);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Mapping::iter(self).filter_map(yaml_entry as YamlEntry<'_>)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        Mapping::iter_mut(self).filter_map(yaml_entry_mut as YamlEntryMut<'_>)
+    }
+}
@@ -0,0 +1,143 @@
+//! Paths which are parsed on first use, rather than when created
+
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::error::ParseError;
+use crate::idx::IdxPath;
+use crate::json::Json;
+use crate::JsonPath;
+
+/// A JSON path which is only parsed the first time it's used, after which the result is cached.
+/// Created with [`JsonPath::compile_lazy`]
+///
+/// As parsing happens on first use, every method which uses the path can fail with the error
+/// from parsing it.
+pub struct LazyJsonPath {
+    pattern: String,
+    compiled: OnceLock<Result<JsonPath, ParseError>>,
+}
+
+impl LazyJsonPath {
+    /// Create a new lazy path from a pattern, without parsing it
+    #[must_use]
+    pub fn new(pattern: &str) -> LazyJsonPath {
+        LazyJsonPath {
+            pattern: pattern.to_string(),
+            compiled: OnceLock::new(),
+        }
+    }
+
+    /// Get the pattern this path will be parsed from
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether this path has been parsed yet, successfully or not
+    #[must_use]
+    pub fn is_compiled(&self) -> bool {
+        self.compiled.get().is_some()
+    }
+
+    /// Get the compiled path, parsing it if this is the first use
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn compiled(&self) -> Result<&JsonPath, ParseError> {
+        self.compiled
+            .get_or_init(|| JsonPath::compile(&self.pattern))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Find this pattern in the provided JSON value. See [`JsonPath::find`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn find<'a>(&self, value: &'a Value) -> Result<Vec<&'a Value>, ParseError> {
+        Ok(self.compiled()?.find(value))
+    }
+
+    /// Find this pattern in the provided value of any type implementing [`Json`]. See
+    /// [`JsonPath::find_in`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn find_in<'a, T: Json>(&self, value: &'a T) -> Result<Vec<&'a T>, ParseError> {
+        Ok(self.compiled()?.find_in(value))
+    }
+
+    /// Find this pattern in the provided JSON value, and return the paths to all found values.
+    /// See [`JsonPath::find_paths`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn find_paths(&self, value: &Value) -> Result<Vec<IdxPath>, ParseError> {
+        Ok(self.compiled()?.find_paths(value))
+    }
+
+    /// Find this pattern in the provided value of any type implementing [`Json`], and return the
+    /// paths to all found values. See [`JsonPath::find_paths_in`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn find_paths_in<T: Json>(&self, value: &T) -> Result<Vec<IdxPath>, ParseError> {
+        Ok(self.compiled()?.find_paths_in(value))
+    }
+
+    /// Delete all items matched by this pattern on the provided JSON value, and return the
+    /// resulting object. See [`JsonPath::delete`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn delete(&self, value: &Value) -> Result<Value, ParseError> {
+        Ok(self.compiled()?.delete(value))
+    }
+
+    /// Delete all items matched by this pattern on the provided JSON value, operating in-place.
+    /// See [`JsonPath::delete_on`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path, in which case the value is unchanged
+    pub fn delete_on(&self, value: &mut Value) -> Result<(), ParseError> {
+        self.compiled()?.delete_on(value);
+        Ok(())
+    }
+
+    /// Replace items matched by this pattern on the provided JSON value, and return the resulting
+    /// object. See [`JsonPath::replace`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path
+    pub fn replace(
+        &self,
+        value: &Value,
+        f: impl FnMut(&Value) -> Value,
+    ) -> Result<Value, ParseError> {
+        Ok(self.compiled()?.replace(value, f))
+    }
+
+    /// Replace items matched by this pattern on the provided JSON value, operating in-place. See
+    /// [`JsonPath::replace_on`]
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern fails to parse as a valid JSON path, in which case the value is unchanged
+    pub fn replace_on(
+        &self,
+        value: &mut Value,
+        f: impl FnMut(&Value) -> Value,
+    ) -> Result<(), ParseError> {
+        self.compiled()?.replace_on(value, f);
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 //! Implementation of the `JSONPath` spec, Proposal A with extensions.
 
 #![forbid(unsafe_code)]
+// `pointer_structural_match` is a hard error on newer compilers, which report the lint as removed
+#![allow(renamed_and_removed_lints)]
 #![warn(
     missing_docs,
     elided_lifetimes_in_paths,
@@ -21,22 +23,49 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+use core::ops::{ControlFlow, Index};
 use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Read;
+use std::str::FromStr;
 
 use ast::Span;
-use error::{ParseError, ParseOrJsonError};
-use eval::EvalCtx;
+use error::EvalError;
+#[cfg(feature = "serde")]
+use error::FindTypedError;
+use error::{JsonTy, ParseError, ParseErrors};
+use eval::{flatten_recur, EvalCtx};
 use idx::{Idx, IdxPath};
-use utils::{delete_paths, replace_paths, try_replace_paths};
+use json::Json;
+use lazy::LazyJsonPath;
+use nfa::PathNfa;
+use pipeline::Pipeline;
+use utils::{
+    delete_paths, delete_paths_collect, replace_paths, replace_paths_collect,
+    replace_paths_with_path, resolve_paths_mut, try_replace_paths,
+};
+use warning::CompileWarning;
 
 pub mod ast;
+#[cfg(feature = "json5")]
+mod comments;
 pub mod error;
 mod eval;
 pub mod idx;
+pub mod json;
+pub mod lazy;
+pub mod nfa;
+pub mod pipeline;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod stream;
 mod utils;
+pub mod warning;
 
 #[doc(inline)]
 pub use ast::Path as JsonPath;
+pub use eval::EvalOptions;
+pub use utils::ValueKey;
 
 /// Find a pattern in the provided JSON value. Recompiles the pattern every call, if the same
 /// pattern is used a lot should instead try using [`JsonPath::compile`].
@@ -48,6 +77,26 @@ pub fn find<'a>(pattern: &str, value: &'a Value) -> Result<Vec<&'a Value>, Parse
     Ok(JsonPath::compile(pattern)?.find(value))
 }
 
+/// Check whether a pattern matches anything in the provided JSON value. Recompiles the pattern
+/// every call, if the same pattern is used a lot should instead try using [`JsonPath::compile`].
+///
+/// # Errors
+///
+/// - If the provided pattern fails to parse as a valid JSON path
+pub fn path_exists(pattern: &str, value: &Value) -> Result<bool, ParseError> {
+    Ok(JsonPath::compile(pattern)?.find_first(value).is_some())
+}
+
+/// Count the matches of a pattern in the provided JSON value. Recompiles the pattern every call,
+/// if the same pattern is used a lot should instead try using [`JsonPath::compile`].
+///
+/// # Errors
+///
+/// - If the provided pattern fails to parse as a valid JSON path
+pub fn count_matches(pattern: &str, value: &Value) -> Result<usize, ParseError> {
+    Ok(JsonPath::compile(pattern)?.find(value).len())
+}
+
 /// Find a pattern in the provided JSON string. Recompiles the pattern every call, if the same
 /// pattern is used a lot should instead try using [`JsonPath::compile`].
 ///
@@ -55,13 +104,28 @@ pub fn find<'a>(pattern: &str, value: &'a Value) -> Result<Vec<&'a Value>, Parse
 ///
 /// - If the provided pattern fails to parse as a valid JSON path
 /// - If the provided value fails to deserialize
-pub fn find_str(pattern: &str, value: &str) -> Result<Vec<Value>, ParseOrJsonError> {
+pub fn find_str(pattern: &str, value: &str) -> Result<Vec<Value>, error::Error> {
     Ok(JsonPath::compile(pattern)?.find_str(value)?)
 }
 
+/// Iterate over every node in the provided JSON value along with its path, in depth-first
+/// document order. The root itself is included first, with an empty path.
+pub fn iter_with_paths(root: &Value) -> impl Iterator<Item = (IdxPath, &Value)> {
+    let mut ctx = EvalCtx::new(root);
+    ctx.prepopulate_parents();
+    let mut all = Vec::new();
+    flatten_recur(&mut all, root);
+    ctx.set_matched(all);
+    ctx.into_paths_and_values().into_iter()
+}
+
 impl JsonPath {
     /// Compile a JSON path, which can be used to match items multiple times.
     ///
+    /// With the `json5` feature, `//` line comments and `/* */` block comments outside of string
+    /// literals are ignored, along with line breaks and whitespace next to comments, so paths can
+    /// be written across multiple lines. See [`JsonPath::strip_comments`]
+    ///
     /// # Errors
     ///
     /// - If the provided pattern fails to parse as a valid JSON path
@@ -69,14 +133,13 @@ impl JsonPath {
         use chumsky::{Parser, Stream};
 
         let len = pattern.chars().count();
+        #[cfg(feature = "json5")]
+        let chars = comments::strip_indexed(pattern).into_iter();
+        #[cfg(not(feature = "json5"))]
+        let chars = pattern.chars().enumerate();
         let stream = Stream::from_iter(
             Span::from(len..len),
-            Box::new(
-                pattern
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| (c, Span::from(i..i + 1))),
-            ),
+            Box::new(chars.map(|(i, c)| (c, Span::from(i..i + 1)))),
         );
 
         Self::parser()
@@ -84,9 +147,111 @@ impl JsonPath {
             .map_err(|e| ParseError::new(pattern, e))
     }
 
+    /// Read a JSON path from the provided reader and compile it. Whitespace around the pattern,
+    /// such as the trailing newline of a file, is ignored
+    ///
+    /// ```
+    /// # use jsonpath_plus::JsonPath;
+    /// let path = JsonPath::compile_from_reader("$.a[0]\n".as_bytes()).unwrap();
+    /// assert_eq!(path.to_string(), "$.a[0]");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - If reading fails, or the read data isn't valid UTF-8
+    /// - If the read pattern fails to parse as a valid JSON path
+    pub fn compile_from_reader(mut reader: impl Read) -> Result<JsonPath, error::Error> {
+        let mut pattern = String::new();
+        reader.read_to_string(&mut pattern)?;
+        Ok(Self::compile(pattern.trim())?)
+    }
+
+    /// Remove the comments from a pattern the same way [`JsonPath::compile`] does, returning the
+    /// pattern which is actually parsed
+    ///
+    /// ```
+    /// # use jsonpath_plus::JsonPath;
+    /// let pattern = "$ // all users\n  .users[*] /* each user */ .name";
+    /// assert_eq!(JsonPath::strip_comments(pattern), "$.users[*].name");
+    /// ```
+    #[cfg(feature = "json5")]
+    #[must_use]
+    pub fn strip_comments(pattern: &str) -> String {
+        comments::strip_indexed(pattern)
+            .into_iter()
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// Compile multiple JSON paths at once, reporting every pattern which fails to parse rather
+    /// than stopping at the first
+    ///
+    /// # Errors
+    ///
+    /// - If any of the provided patterns fail to parse as valid JSON paths
+    pub fn compile_all(patterns: &[&str]) -> Result<Vec<JsonPath>, ParseErrors> {
+        let mut paths = Vec::with_capacity(patterns.len());
+        let mut errs = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            match Self::compile(pattern) {
+                Ok(path) => {
+                    paths.push(path);
+                    errs.push(None);
+                }
+                Err(err) => errs.push(Some(err)),
+            }
+        }
+
+        if paths.len() == patterns.len() {
+            Ok(paths)
+        } else {
+            Err(ParseErrors::new(errs))
+        }
+    }
+
+    /// Compile a JSON path, additionally returning warnings for any parts of it which are valid
+    /// but likely mistakes, such as slices which can never select anything
+    ///
+    /// # Errors
+    ///
+    /// - If the provided pattern fails to parse as a valid JSON path
+    pub fn compile_with_warnings(
+        pattern: &str,
+    ) -> Result<(JsonPath, Vec<CompileWarning>), ParseError> {
+        let path = Self::compile(pattern)?;
+        let mut warnings = Vec::new();
+        path.lint(&mut warnings);
+        Ok((path, warnings))
+    }
+
+    /// Create a path which isn't parsed until it's first used. Useful when many paths are created
+    /// up-front but only some are ever used
+    #[must_use]
+    pub fn compile_lazy(pattern: &str) -> LazyJsonPath {
+        LazyJsonPath::new(pattern)
+    }
+
+    /// Compile this path to a non-deterministic finite automaton, which can be merged with other
+    /// automata to evaluate many paths together with [`PathNfa::find_all`]
+    #[must_use]
+    pub fn to_nfa(&self) -> PathNfa {
+        PathNfa::new(self)
+    }
+
+    /// Create a new [`Pipeline`], for applying multiple operations to a value in sequence
+    pub fn pipeline<'a>() -> Pipeline<'a> {
+        Pipeline::new()
+    }
+
     /// Find this pattern in the provided JSON value
     #[must_use = "this does not modify the path or provided value"]
     pub fn find<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        self.find_in(value)
+    }
+
+    /// Find this pattern in the provided value of any type implementing [`Json`]
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_in<'a, T: Json>(&self, value: &'a T) -> Vec<&'a T> {
         let mut ctx = EvalCtx::new(value);
         if self.has_parent() {
             ctx.prepopulate_parents();
@@ -95,58 +260,473 @@ impl JsonPath {
         ctx.into_matched()
     }
 
+    /// Find this pattern in the provided value of any type implementing [`Json`], evaluating it
+    /// with the provided options
+    ///
+    /// # Errors
+    ///
+    /// - If strict filter errors are enabled and a filter fails to evaluate on any item
+    /// - If the path ends in a tilde at the top level, which isn't yet supported
+    pub fn try_find_with<'a, T: Json>(
+        &self,
+        value: &'a T,
+        options: EvalOptions,
+    ) -> Result<Vec<&'a T>, EvalError> {
+        let mut ctx = EvalCtx::new(value);
+        ctx.set_options(options);
+        if self.has_parent() {
+            ctx.prepopulate_parents();
+        }
+        self.eval(&mut ctx);
+        match ctx.take_error() {
+            Some(err) => Err(err),
+            None => Ok(ctx.into_matched()),
+        }
+    }
+
+    /// Find this pattern in the provided JSON value, and deserialize every match into the
+    /// requested type
+    ///
+    /// # Errors
+    ///
+    /// - If any match fails to deserialize into the requested type
+    #[cfg(feature = "serde")]
+    pub fn find_typed<T: serde::de::DeserializeOwned>(
+        &self,
+        value: &Value,
+    ) -> Result<Vec<T>, serde_json::Error> {
+        self.find(value).into_iter().map(T::deserialize).collect()
+    }
+
+    /// Find this pattern in the provided JSON value, and deserialize the single match into the
+    /// requested type
+    ///
+    /// # Errors
+    ///
+    /// - If the pattern matches no items, or more than one item
+    /// - If the match fails to deserialize into the requested type
+    #[cfg(feature = "serde")]
+    pub fn find_one_typed<T: serde::de::DeserializeOwned>(
+        &self,
+        value: &Value,
+    ) -> Result<T, FindTypedError> {
+        match &*self.find(value) {
+            [] => Err(FindTypedError::NoMatch),
+            [val] => Ok(T::deserialize(*val)?),
+            many => Err(FindTypedError::ManyMatches(many.len())),
+        }
+    }
+
+    /// Find this pattern in the provided JSON value, keeping only matches which are containers -
+    /// arrays or objects
+    ///
+    /// ```
+    /// # use jsonpath_plus::JsonPath;
+    /// # use serde_json::json;
+    /// let value = json!({"a": [1, {"b": 2}], "c": "d"});
+    /// let path = JsonPath::compile("$..").unwrap();
+    /// assert_eq!(path.find_containers(&value), [&value, &value["a"], &value["a"][1]]);
+    /// ```
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_containers<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        self.find(value)
+            .into_iter()
+            .filter(|v| v.is_array() || v.is_object())
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, keeping only matches which are leaves -
+    /// nulls, booleans, numbers or strings
+    ///
+    /// ```
+    /// # use jsonpath_plus::JsonPath;
+    /// # use serde_json::json;
+    /// let value = json!({"a": [1, {"b": null}], "c": "d"});
+    /// let path = JsonPath::compile("$.*").unwrap();
+    /// assert_eq!(path.find_leaves(&value), [&value["c"]]);
+    /// ```
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_leaves<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        self.find(value)
+            .into_iter()
+            .filter(|v| !v.is_array() && !v.is_object())
+            .collect()
+    }
+
+    /// Call the provided function on each match of this pattern in the provided JSON value, in
+    /// the same order as [`JsonPath::find`]. Each match is passed to the function as soon as it's
+    /// found, rather than collecting every match first. Paths containing unions are evaluated in
+    /// full up to their last union, as its matches are grouped by component
+    pub fn for_each<'a>(&self, value: &'a Value, mut f: impl FnMut(&'a Value)) {
+        let mut ctx = EvalCtx::new(value);
+        if self.has_parent() {
+            ctx.prepopulate_parents();
+        }
+        let _ = self.eval_each(&mut ctx, |_, val| {
+            f(val);
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    /// Call the provided function on each match of this pattern in the provided JSON value along
+    /// with its path, in the same order as [`JsonPath::find`]. Like [`JsonPath::for_each`], each
+    /// match is passed to the function as soon as it's found
+    pub fn for_each_with_path<'a>(&self, value: &'a Value, mut f: impl FnMut(&'a Value, IdxPath)) {
+        let mut ctx = EvalCtx::new(value);
+        ctx.prepopulate_parents();
+        let _ = self.eval_each(&mut ctx, |ctx, val| {
+            f(val, ctx.path_of(val));
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    /// Find the first match of this pattern in the provided JSON value, or `None` if nothing
+    /// matched. This is the first item [`JsonPath::find`] would return, but evaluation stops
+    /// once it's found, rather than finding every match
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_first<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        let mut ctx = EvalCtx::new(value);
+        if self.has_parent() {
+            ctx.prepopulate_parents();
+        }
+        self.eval_first(&mut ctx)
+    }
+
+    /// Get the first match of this pattern in the provided JSON value, or `None` if nothing
+    /// matched. An alias of [`JsonPath::find_first`]
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.find_first(value)
+    }
+
+    /// Find the first match of this pattern in the provided JSON value, or return the provided
+    /// default if nothing matched. Like [`JsonPath::find_first`], evaluation stops at the first
+    /// match
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_or_default<'a>(&self, value: &'a Value, default: &'a Value) -> &'a Value {
+        self.find_or_else(value, || default)
+    }
+
+    /// Find the first match of this pattern in the provided JSON value, or return the result of
+    /// the provided function if nothing matched. Like [`JsonPath::find_first`], evaluation stops
+    /// at the first match
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_or_else<'a>(&self, value: &'a Value, f: impl FnOnce() -> &'a Value) -> &'a Value {
+        self.find_first(value).unwrap_or_else(f)
+    }
+
+    /// Find this pattern in the provided JSON value, and return copies of the matched objects
+    /// containing only the provided keys. Matches which aren't objects are returned unchanged
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn select_keys(&self, value: &Value, keys: &[&str]) -> Vec<Value> {
+        self.find(value)
+            .into_iter()
+            .map(|val| match val {
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .filter(|(key, _)| keys.contains(&key.as_str()))
+                        .map(|(key, val)| (key.clone(), val.clone()))
+                        .collect(),
+                ),
+                other => other.clone(),
+            })
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, removing any duplicate results. Values are
+    /// compared by deep equality, so distinct items with the same content are only returned once,
+    /// in the order they were first matched
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_unique<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut seen = HashSet::new();
+        self.find(value)
+            .into_iter()
+            .filter(|&val| seen.insert(ValueKey::from(val)))
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, removing any duplicate results and returning
+    /// owned copies of the remaining values. See [`JsonPath::find_unique`]
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_unique_owned(&self, value: &Value) -> Vec<Value> {
+        self.find_unique(value).into_iter().cloned().collect()
+    }
+
     /// Find this pattern in the provided JSON value, and return the shortest paths to all found
     /// values as a chain of indices
     #[must_use = "this does not modify the path or provided value"]
     pub fn find_paths(&self, value: &Value) -> Vec<IdxPath> {
+        self.find_paths_in(value)
+    }
+
+    /// Find this pattern in the provided value of any type implementing [`Json`], and return the
+    /// shortest paths to all found values as a chain of indices
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_paths_in<T: Json>(&self, value: &T) -> Vec<IdxPath> {
         let mut ctx = EvalCtx::new(value);
         ctx.prepopulate_parents();
         self.eval(&mut ctx);
         ctx.paths_matched()
     }
 
+    /// Find this pattern in the provided JSON value, and return mutable references to all found
+    /// values. Mutable references can't overlap, so a match nested inside another match, or the
+    /// same item matched more than once, is only returned once through the outermost match
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_mut<'a>(&self, value: &'a mut Value) -> Vec<&'a mut Value> {
+        let paths = self.find_paths(value);
+        resolve_paths_mut(paths, value)
+    }
+
+    /// Find this pattern in the provided JSON value, and return the paths to all found values as
+    /// RFC 9535 normalized paths, such as `$['a'][0]`, in the same order as [`JsonPath::find`]
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_normalized_paths(&self, value: &Value) -> Vec<String> {
+        self.find_paths(value)
+            .iter()
+            .map(IdxPath::to_normalized_string)
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, and return the paths to all found values as
+    /// RFC 6901 JSON Pointers, in the same order as [`JsonPath::find_paths`]
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_pointers(&self, value: &Value) -> Vec<String> {
+        self.find_paths(value)
+            .iter()
+            .map(IdxPath::to_pointer)
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, returning each matched value along with its
+    /// index in the containing array or object. The root value has no container, so it is skipped
+    /// if matched
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn find_indices<'a>(&self, value: &'a Value) -> Vec<(Idx, &'a Value)> {
+        self.find_paths_and_values(value)
+            .into_iter()
+            .filter_map(|(path, val)| path.last().cloned().map(|idx| (idx, val)))
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, calling the provided function on each match
+    /// along with its path and a mutable reference to the provided context, and return the
+    /// results in match order
+    pub fn find_with_context<C, R>(
+        &self,
+        value: &Value,
+        ctx: &mut C,
+        mut f: impl FnMut(&Value, &IdxPath, &mut C) -> R,
+    ) -> Vec<R> {
+        self.find_paths_and_values(value)
+            .into_iter()
+            .map(|(path, val)| f(val, &path, ctx))
+            .collect()
+    }
+
+    fn find_paths_and_values<'a>(&self, value: &'a Value) -> Vec<(IdxPath, &'a Value)> {
+        let mut ctx = EvalCtx::new(value);
+        ctx.prepopulate_parents();
+        self.eval(&mut ctx);
+        ctx.into_paths_and_values()
+    }
+
+    /// Find this pattern in the provided JSON value, and check that every matched value has the
+    /// expected type. Returns the paths and types of all matches with a different type, so an
+    /// empty result means every match was valid
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn validate_types(&self, value: &Value, expected: JsonTy) -> Vec<(IdxPath, JsonTy)> {
+        self.validate_types_report(value, expected)
+            .into_iter()
+            .map(|(path, ty, _)| (path, ty))
+            .collect()
+    }
+
+    /// Find this pattern in the provided JSON value, and check that every matched value has the
+    /// expected type. Returns the paths, types, and values of all matches with a different type
+    #[must_use = "this does not modify the path or provided value"]
+    pub fn validate_types_report<'a>(
+        &self,
+        value: &'a Value,
+        expected: JsonTy,
+    ) -> Vec<(IdxPath, JsonTy, &'a Value)> {
+        self.find_paths_and_values(value)
+            .into_iter()
+            .filter_map(|(path, val)| {
+                let ty = JsonTy::from(val);
+                (ty != expected).then_some((path, ty, val))
+            })
+            .collect()
+    }
+
     /// Delete all items matched by this pattern on the provided JSON value, and return the
-    /// resulting object
+    /// resulting object. If the root value itself is matched, the result is `null`. See
+    /// [`JsonPath::would_select_root`] to check for this ahead of time
     #[must_use = "this returns the new value, without modifying the original. To work in-place, \
                   use `delete_on`"]
     pub fn delete(&self, value: &Value) -> Value {
+        self.delete_in(value)
+    }
+
+    /// Delete all items matched by this pattern on the provided JSON value, and return the
+    /// resulting object along with the number of items deleted. Items nested inside another
+    /// deleted item aren't counted separately. If the root value itself is matched, the result is
+    /// `null` and one item was deleted
+    #[must_use = "this returns the new value, without modifying the original. To work in-place, \
+                  use `delete_on_count`"]
+    pub fn delete_count(&self, value: &Value) -> (Value, usize) {
         let paths = self.find_paths(value);
+        // Deleting the root discards everything, so there's no need to copy the value
+        if paths.iter().any(|p| p.is_empty()) {
+            return (Value::Null, 1);
+        }
+        let mut out = value.clone();
+        let count = delete_paths(paths, &mut out);
+        (out, count)
+    }
+
+    /// Delete all items matched by this pattern on the provided value of any type implementing
+    /// [`Json`], and return the resulting value. If the root value itself is matched, the result
+    /// is null
+    #[must_use = "this returns the new value, without modifying the original"]
+    pub fn delete_in<T: Json>(&self, value: &T) -> T {
+        let paths = self.find_paths_in(value);
+        // Deleting the root discards everything, so there's no need to copy the value
+        if paths.iter().any(|p| p.is_empty()) {
+            return T::null();
+        }
         let mut out = value.clone();
         delete_paths(paths, &mut out);
         out
     }
 
-    /// Delete all items matched by this pattern on the provided JSON value, operating in-place
+    /// Delete all items matched by this pattern on the provided JSON value, operating in-place. If
+    /// the root value itself is matched, it is replaced with `null`. Unlike [`JsonPath::delete`],
+    /// this never copies the provided value
     pub fn delete_on(&self, value: &mut Value) {
         let paths = self.find_paths(value);
         delete_paths(paths, value);
     }
 
+    /// Delete all items matched by this pattern on the provided JSON value, operating in-place,
+    /// and return the number of items deleted. Items nested inside another deleted item aren't
+    /// counted separately, so a result of `0` means the pattern matched nothing
+    pub fn delete_on_count(&self, value: &mut Value) -> usize {
+        let paths = self.find_paths(value);
+        delete_paths(paths, value)
+    }
+
+    /// Delete all items matched by this pattern on the provided JSON value, operating in-place,
+    /// and return the path and original value of every deleted item. Items nested inside another
+    /// deleted item aren't reported separately, as they're part of their parent's value
+    pub fn delete_on_collect(&self, value: &mut Value) -> Vec<(IdxPath, Value)> {
+        let paths = self.find_paths(value);
+        delete_paths_collect(paths, value)
+    }
+
     /// Replace items matched by this pattern on the provided JSON value, filling them with the
-    /// value returned by the provided function, then return the resulting object
+    /// value returned by the provided function, then return the resulting object. If the root
+    /// value itself is matched, the whole document is replaced. See
+    /// [`JsonPath::would_select_root`] to check for this ahead of time
     #[must_use = "this returns the new value, without modifying the original. To work in-place, \
                   use `replace_on`"]
     pub fn replace(&self, value: &Value, f: impl FnMut(&Value) -> Value) -> Value {
-        let paths = self.find_paths(value);
+        self.replace_in(value, f)
+    }
+
+    /// Replace items matched by this pattern on the provided value of any type implementing
+    /// [`Json`], filling them with the value returned by the provided function, then return the
+    /// resulting value
+    #[must_use = "this returns the new value, without modifying the original"]
+    pub fn replace_in<T: Json>(&self, value: &T, mut f: impl FnMut(&T) -> T) -> T {
+        let paths = self.find_paths_in(value);
+        // Only the root was matched, so the result is built directly from the original
+        if let [path] = &*paths {
+            if path.is_empty() {
+                return f(value);
+            }
+        }
         let mut out = value.clone();
         replace_paths(paths, &mut out, f);
         out
     }
 
     /// Replace items matched by this pattern on the provided JSON value, filling them the value
-    /// returned by the provided function, operating in-place
+    /// returned by the provided function, operating in-place. Unlike [`JsonPath::replace`], this
+    /// never copies the provided value
     pub fn replace_on(&self, value: &mut Value, f: impl FnMut(&Value) -> Value) {
         let paths = self.find_paths(value);
         replace_paths(paths, value, f);
     }
 
+    /// Replace items matched by this pattern on the provided JSON value, operating in-place, and
+    /// return the number of items replaced
+    pub fn replace_on_count(&self, value: &mut Value, f: impl FnMut(&Value) -> Value) -> usize {
+        let paths = self.find_paths(value);
+        replace_paths(paths, value, f)
+    }
+
+    /// Replace items matched by this pattern on the provided JSON value, operating in-place, and
+    /// return the path and original value of every replaced item in the order they were
+    /// replaced. Writing the original values back in reverse order undoes the replacement
+    pub fn replace_on_collect(
+        &self,
+        value: &mut Value,
+        f: impl FnMut(&Value) -> Value,
+    ) -> Vec<(IdxPath, Value)> {
+        let paths = self.find_paths(value);
+        replace_paths_collect(paths, value, f)
+    }
+
+    /// Replace items matched by this pattern on the provided JSON value, filling them with the
+    /// value returned by the provided function, then return the resulting object. The function
+    /// receives the path of each matched item along with the item itself
+    #[must_use = "this returns the new value, without modifying the original. To work in-place, \
+                  use `replace_on_with_path`"]
+    pub fn replace_with_path(
+        &self,
+        value: &Value,
+        mut f: impl FnMut(&Value, &IdxPath) -> Value,
+    ) -> Value {
+        let paths = self.find_paths(value);
+        // Only the root was matched, so the result is built directly from the original
+        if let [path] = &*paths {
+            if path.is_empty() {
+                return f(value, path);
+            }
+        }
+        let mut out = value.clone();
+        replace_paths_with_path(paths, &mut out, f);
+        out
+    }
+
+    /// Replace items matched by this pattern on the provided JSON value, filling them with the
+    /// value returned by the provided function, operating in-place. The function receives the
+    /// path of each matched item along with the item itself. If the function panics, items
+    /// already replaced keep their new values and the rest are left untouched, as with
+    /// [`JsonPath::replace_on`]
+    pub fn replace_on_with_path(
+        &self,
+        value: &mut Value,
+        f: impl FnMut(&Value, &IdxPath) -> Value,
+    ) {
+        let paths = self.find_paths(value);
+        replace_paths_with_path(paths, value, f);
+    }
+
     /// Replace or delete items matched by this pattern on the provided JSON value. Replaces if the
     /// provided method returns `Some`, deletes if the provided method returns `None`. This method
-    /// then returns the resulting object
+    /// then returns the resulting object. Deleting the root value results in `null`
     #[must_use = "this returns the new value, without modifying the original. To work in-place, \
                   use `try_replace_on`"]
-    pub fn try_replace(&self, value: &Value, f: impl FnMut(&Value) -> Option<Value>) -> Value {
+    pub fn try_replace(&self, value: &Value, mut f: impl FnMut(&Value) -> Option<Value>) -> Value {
         let paths = self.find_paths(value);
+        // Only the root was matched, so the result is built directly from the original
+        if let [path] = &*paths {
+            if path.is_empty() {
+                return f(value).unwrap_or(Value::Null);
+            }
+        }
         let mut out = value.clone();
         try_replace_paths(paths, &mut out, f);
         out
@@ -154,12 +734,25 @@ impl JsonPath {
 
     /// Replace or delete items matched by this pattern on the provided JSON value. Replaces if the
     /// provided method returns `Some`, deletes if the provided method returns `None`. This method
-    /// operates in-place on the provided value
+    /// operates in-place on the provided value, without copying it. Deleting the root value
+    /// replaces it with `null`
     pub fn try_replace_on(&self, value: &mut Value, f: impl FnMut(&Value) -> Option<Value>) {
         let paths = self.find_paths(value);
         try_replace_paths(paths, value, f);
     }
 
+    /// Replace or delete items matched by this pattern on the provided JSON value, operating
+    /// in-place as [`JsonPath::try_replace_on`], and return the number of items replaced and the
+    /// number deleted. Items nested inside a deleted item aren't visited, so aren't counted
+    pub fn try_replace_on_count(
+        &self,
+        value: &mut Value,
+        f: impl FnMut(&Value) -> Option<Value>,
+    ) -> (usize, usize) {
+        let paths = self.find_paths(value);
+        try_replace_paths(paths, value, f)
+    }
+
     /// Find this pattern in the provided JSON string
     ///
     /// # Errors
@@ -170,6 +763,28 @@ impl JsonPath {
         Ok(self.find(&val).into_iter().cloned().collect())
     }
 
+    /// Find this pattern in the provided JSON string, and return the paths to all found values
+    /// as RFC 6901 JSON Pointers
+    ///
+    /// # Errors
+    ///
+    /// - If the provided value fails to deserialize
+    pub fn find_str_pointers(&self, str: &str) -> Result<Vec<String>, serde_json::Error> {
+        let val = serde_json::from_str(str)?;
+        Ok(self.find_pointers(&val))
+    }
+
+    /// Find the first match of this pattern in the provided JSON string, or return the provided
+    /// default if nothing matched
+    ///
+    /// # Errors
+    ///
+    /// - If the provided value fails to deserialize
+    pub fn find_str_or(&self, str: &str, default: Value) -> Result<Value, serde_json::Error> {
+        let val = serde_json::from_str(str)?;
+        Ok(self.find_first(&val).cloned().unwrap_or(default))
+    }
+
     /// Delete items matching this pattern in the provided JSON string
     ///
     /// # Errors
@@ -209,5 +824,47 @@ impl JsonPath {
     }
 }
 
+impl FromStr for JsonPath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JsonPath::compile(s)
+    }
+}
+
+impl TryFrom<&str> for JsonPath {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        JsonPath::compile(value)
+    }
+}
+
+impl TryFrom<String> for JsonPath {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        JsonPath::compile(&value)
+    }
+}
+
+impl From<JsonPath> for String {
+    fn from(path: JsonPath) -> Self {
+        path.to_string()
+    }
+}
+
+/// Index a JSON value by a path, returning the first match. Like indexing a [`Value`] by a key
+/// that isn't present, this returns `null` rather than panicking if nothing matched. Use
+/// [`JsonPath::find_first`] to tell a missing match apart from a matched `null`
+impl Index<&JsonPath> for Value {
+    type Output = Value;
+
+    fn index(&self, path: &JsonPath) -> &Value {
+        static NULL: Value = Value::Null;
+        path.find_first(self).unwrap_or(&NULL)
+    }
+}
+
 #[cfg(test)]
 mod tests;
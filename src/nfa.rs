@@ -0,0 +1,159 @@
+//! Paths compiled to non-deterministic finite automata, for evaluating many paths on a value
+//! together
+
+use crate::ast::Segment;
+use crate::eval::EvalCtx;
+use crate::json::Json;
+use crate::JsonPath;
+
+/// A single state in a [`PathNfa`], holding the items matched by one prefix shared by some of its
+/// paths
+#[derive(Clone)]
+pub struct NfaState {
+    transitions: Vec<(Segment, usize)>,
+    accepts: Vec<usize>,
+}
+
+impl NfaState {
+    fn new() -> NfaState {
+        NfaState {
+            transitions: Vec::new(),
+            accepts: Vec::new(),
+        }
+    }
+
+    /// The segments applied to items matched in this state, each with the index of the state
+    /// the resulting items move to. No two transitions out of a state have the same segment
+    #[must_use]
+    pub fn transitions(&self) -> &[(Segment, usize)] {
+        &self.transitions
+    }
+
+    /// The indices of the paths which accept in this state, the items of this state being their
+    /// matches
+    #[must_use]
+    pub fn accepts(&self) -> &[usize] {
+        &self.accepts
+    }
+}
+
+/// A set of paths compiled to a non-deterministic finite automaton. Each state holds the items
+/// matched by a prefix of one or more of the paths, moving to following states by applying a
+/// segment as a transition. Paths starting with the same segments share the states for that
+/// prefix, so it's only evaluated once. Created from a single path with [`JsonPath::to_nfa`], and
+/// combined with [`PathNfa::merge`]
+#[derive(Clone)]
+pub struct PathNfa {
+    states: Vec<NfaState>,
+    paths: usize,
+    needs_parents: bool,
+}
+
+impl PathNfa {
+    pub(crate) fn new(path: &JsonPath) -> PathNfa {
+        let mut states = vec![NfaState::new()];
+        for segment in path.segments() {
+            let next = states.len();
+            states[next - 1].transitions.push((segment.clone(), next));
+            states.push(NfaState::new());
+        }
+        // A tilde at the top level isn't supported, so like `JsonPath::find` the path matches
+        // nothing and never accepts
+        if path.tilde_token().is_none() {
+            states.last_mut().unwrap().accepts.push(0);
+        }
+
+        PathNfa {
+            states,
+            paths: 1,
+            needs_parents: path.has_parent(),
+        }
+    }
+
+    /// Combine many automata into one, sharing the states of any common prefix between their
+    /// paths. The paths of the result are those of each provided automaton, in order
+    #[must_use]
+    pub fn merge(nfas: &[PathNfa]) -> PathNfa {
+        let mut out = PathNfa {
+            states: vec![NfaState::new()],
+            paths: 0,
+            needs_parents: nfas.iter().any(|nfa| nfa.needs_parents),
+        };
+
+        for nfa in nfas {
+            // Pairs of a state in the automaton being merged, and its equivalent in the output
+            let mut stack = vec![(0, 0)];
+            while let Some((from, to)) = stack.pop() {
+                let state = &nfa.states[from];
+                out.states[to]
+                    .accepts
+                    .extend(state.accepts.iter().map(|idx| idx + out.paths));
+
+                for (segment, next) in &state.transitions {
+                    let existing = out.states[to]
+                        .transitions
+                        .iter()
+                        .find(|(other, _)| other == segment)
+                        .map(|&(_, idx)| idx);
+                    let target = existing.unwrap_or_else(|| {
+                        let idx = out.states.len();
+                        out.states.push(NfaState::new());
+                        out.states[to].transitions.push((segment.clone(), idx));
+                        idx
+                    });
+                    stack.push((*next, target));
+                }
+            }
+            out.paths += nfa.paths;
+        }
+
+        out
+    }
+
+    /// The states of this automaton. The first is the start state, which holds only the root
+    #[must_use]
+    pub fn states(&self) -> &[NfaState] {
+        &self.states
+    }
+
+    /// The number of paths this automaton evaluates
+    #[must_use]
+    pub fn path_count(&self) -> usize {
+        self.paths
+    }
+
+    /// Evaluate this automaton on a value, returning the matches of each of its paths in order.
+    /// Matches are the same as evaluating each path individually, but every state is only
+    /// evaluated once, as is work such as tracking the parents of items
+    #[must_use]
+    pub fn find<'a, T: Json>(&self, value: &'a T) -> Vec<Vec<&'a T>> {
+        let mut ctx = EvalCtx::new(value);
+        if self.needs_parents {
+            ctx.prepopulate_parents();
+        }
+
+        let mut found = vec![Vec::new(); self.paths];
+        let mut stack = vec![(0, vec![value])];
+        while let Some((state, matched)) = stack.pop() {
+            let state = &self.states[state];
+            for &idx in &state.accepts {
+                found[idx].clone_from(&matched);
+            }
+            for (segment, next) in &state.transitions {
+                ctx.set_matched(matched.clone());
+                segment.eval(&mut ctx);
+                stack.push((*next, ctx.get_matched().to_vec()));
+            }
+        }
+
+        found
+    }
+
+    /// Evaluate many automata on a value together, returning the matches of each of their paths
+    /// in order. The automata are merged first, so prefixes shared between them are only
+    /// evaluated once
+    #[must_use]
+    pub fn find_all<'a, T: Json>(paths: &[PathNfa], value: &'a T) -> Vec<Vec<&'a T>> {
+        PathNfa::merge(paths).find(value)
+    }
+}
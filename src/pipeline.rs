@@ -0,0 +1,107 @@
+//! Builder for applying multiple path operations to a value in sequence
+
+use serde_json::Value;
+
+use crate::JsonPath;
+
+/// A function which replaces a matched value
+pub type ReplaceFn<'a> = Box<dyn FnMut(&Value) -> Value + 'a>;
+
+/// A function which replaces or deletes a matched value
+pub type TryReplaceFn<'a> = Box<dyn FnMut(&Value) -> Option<Value> + 'a>;
+
+/// A single operation in a [`Pipeline`]
+#[non_exhaustive]
+pub enum PipelineOp<'a> {
+    /// Replace the value with an array of all items matched by the path
+    Find(JsonPath),
+    /// Replace all items matched by the path with the result of the function
+    Replace(JsonPath, ReplaceFn<'a>),
+    /// Replace or delete all items matched by the path, depending on the result of the function
+    TryReplace(JsonPath, TryReplaceFn<'a>),
+    /// Delete all items matched by the path
+    Delete(JsonPath),
+}
+
+impl PipelineOp<'_> {
+    fn apply(&mut self, value: &mut Value) {
+        match self {
+            PipelineOp::Find(path) => {
+                *value = Value::Array(path.find(value).into_iter().cloned().collect());
+            }
+            PipelineOp::Replace(path, f) => path.replace_on(value, f),
+            PipelineOp::TryReplace(path, f) => path.try_replace_on(value, f),
+            PipelineOp::Delete(path) => path.delete_on(value),
+        }
+    }
+}
+
+/// A sequence of operations to apply to a value, each operating on the result of the last.
+/// Created with [`JsonPath::pipeline`]
+#[must_use = "A pipeline does nothing on its own, call `execute` or `execute_on` to apply it to a \
+              value"]
+#[derive(Default)]
+pub struct Pipeline<'a> {
+    ops: Vec<PipelineOp<'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Create a new, empty pipeline
+    pub fn new() -> Pipeline<'a> {
+        Pipeline { ops: Vec::new() }
+    }
+
+    /// Add an operation to the end of this pipeline
+    pub fn op(mut self, op: PipelineOp<'a>) -> Pipeline<'a> {
+        self.ops.push(op);
+        self
+    }
+
+    /// Replace the value with an array of all items matched by the path
+    pub fn find(self, path: JsonPath) -> Pipeline<'a> {
+        self.op(PipelineOp::Find(path))
+    }
+
+    /// Replace all items matched by the path with the result of the provided function
+    pub fn replace(self, path: JsonPath, f: impl FnMut(&Value) -> Value + 'a) -> Pipeline<'a> {
+        self.op(PipelineOp::Replace(path, Box::new(f)))
+    }
+
+    /// Replace all items matched by the path if the provided function returns `Some`, or delete
+    /// them if it returns `None`
+    pub fn try_replace(
+        self,
+        path: JsonPath,
+        f: impl FnMut(&Value) -> Option<Value> + 'a,
+    ) -> Pipeline<'a> {
+        self.op(PipelineOp::TryReplace(path, Box::new(f)))
+    }
+
+    /// Delete all items matched by the path
+    pub fn delete(self, path: JsonPath) -> Pipeline<'a> {
+        self.op(PipelineOp::Delete(path))
+    }
+
+    /// Get the operations in this pipeline
+    #[must_use]
+    pub fn ops(&self) -> &[PipelineOp<'a>] {
+        &self.ops
+    }
+
+    /// Apply every operation in this pipeline in order to a copy of the provided value, then return
+    /// the result
+    #[must_use = "this returns the new value, without modifying the original. To work in-place, \
+                  use `execute_on`"]
+    pub fn execute(self, value: &Value) -> Value {
+        let mut out = value.clone();
+        self.execute_on(&mut out);
+        out
+    }
+
+    /// Apply every operation in this pipeline in order to the provided value, operating in-place
+    pub fn execute_on(self, value: &mut Value) {
+        for mut op in self.ops {
+            op.apply(value);
+        }
+    }
+}
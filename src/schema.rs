@@ -0,0 +1,450 @@
+//! Best-effort static checking of paths against a [JSON Schema](https://json-schema.org)
+//!
+//! A [`PathValidator`] walks the segments of a path alongside the schema, and reports names and
+//! indices which the schema says can never exist. Only the structural keywords `type`,
+//! `properties`, `patternProperties`, `additionalProperties`, `items`, `prefixItems`, `allOf`,
+//! `anyOf`, `oneOf` and local `$ref`s are understood. Once a segment reaches a part of the schema
+//! which doesn't constrain its children, or a selector the validator can't follow such as a parent
+//! or sub-path, the rest of the path is assumed valid. A path with no warnings may still match
+//! nothing, and a path with warnings may still match in documents which don't follow the schema.
+//!
+//! ```
+//! # use jsonpath_plus::JsonPath;
+//! use jsonpath_plus::schema::{PathValidator, ValidationWarningKind};
+//! use serde_json::json;
+//!
+//! let validator = PathValidator::new(json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "users": {
+//!             "type": "array",
+//!             "items": {
+//!                 "type": "object",
+//!                 "properties": { "name": { "type": "string" } }
+//!             }
+//!         }
+//!     }
+//! }));
+//!
+//! let path = JsonPath::compile("$.users[*].email").unwrap();
+//! let warnings = validator.validate(&path);
+//! assert_eq!(warnings.len(), 1);
+//! assert_eq!(warnings[0].kind(), ValidationWarningKind::UnknownProperty);
+//! assert_eq!(warnings[0].segment(), 2);
+//! ```
+
+use core::fmt;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::ast::{BracketLit, BracketSelector, RawSelector, Segment, UnionComponent};
+#[cfg(feature = "spanned")]
+use crate::ast::{Span, Spanned};
+use crate::JsonPath;
+
+/// The category of a [`ValidationWarning`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationWarningKind {
+    /// A name which isn't a property of any object the schema allows at that point
+    UnknownProperty,
+    /// A name used where the schema only allows values which aren't objects
+    NotAnObject,
+    /// An index used where the schema only allows values which aren't arrays, or past the end of
+    /// a fixed-length array
+    NotAnArray,
+}
+
+/// A warning about a segment of a path which the schema says can never match
+#[derive(Clone, Debug)]
+pub struct ValidationWarning {
+    kind: ValidationWarningKind,
+    message: String,
+    segment: usize,
+    #[cfg(feature = "spanned")]
+    span: Span,
+}
+
+impl ValidationWarning {
+    /// Get the category of this warning
+    #[must_use]
+    pub fn kind(&self) -> ValidationWarningKind {
+        self.kind
+    }
+
+    /// Get the human-readable message for this warning
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the index of the segment this warning refers to, in [`JsonPath::segments`]
+    #[must_use]
+    pub fn segment(&self) -> usize {
+        self.segment
+    }
+
+    /// Get the source span of the segment this warning refers to
+    #[cfg(feature = "spanned")]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The kind and message of a warning, before it's attached to a segment
+type Problem = (ValidationWarningKind, String);
+
+/// The result of looking up a child in a single schema
+enum Lookup<'a> {
+    /// The schema for the child
+    Found(&'a Value),
+    /// The schema doesn't constrain the child
+    Open,
+    /// The schema says the child can't exist
+    Missing(ValidationWarningKind),
+}
+
+/// Whether a schema allows values of a JSON type, or `None` if it doesn't restrict types
+fn allows_type(schema: &Value, ty: &str) -> Option<bool> {
+    match schema.get("type")? {
+        Value::String(s) => Some(s == ty),
+        Value::Array(types) => Some(types.iter().any(|t| t == ty)),
+        _ => None,
+    }
+}
+
+/// A validator which checks paths against a JSON Schema. See the [module docs](self) for what is
+/// checked
+#[derive(Clone, Debug)]
+pub struct PathValidator {
+    schema: Value,
+}
+
+impl PathValidator {
+    /// Create a new validator for the provided schema
+    #[must_use]
+    pub fn new(schema: Value) -> PathValidator {
+        PathValidator { schema }
+    }
+
+    /// Get the schema this validator checks against
+    #[must_use]
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Check a path against the schema, returning a warning for each selector which can never
+    /// match. Checking stops after a segment where nothing can match, as later segments have
+    /// nothing to match against
+    #[must_use]
+    pub fn validate(&self, path: &JsonPath) -> Vec<ValidationWarning> {
+        let mut out = Vec::new();
+        let mut current = self.expand(&self.schema);
+
+        for (idx, seg) in path.segments().iter().enumerate() {
+            let mut problems = Vec::new();
+            let next = self.step(seg, &current, &mut problems);
+            out.extend(
+                problems
+                    .into_iter()
+                    .map(|(kind, message)| ValidationWarning {
+                        kind,
+                        message,
+                        segment: idx,
+                        #[cfg(feature = "spanned")]
+                        span: seg.span(),
+                    }),
+            );
+            match next {
+                Some(next) if !next.is_empty() => current = next,
+                _ => break,
+            }
+        }
+
+        out
+    }
+
+    /// Apply a segment to the possible schemas of the current items, returning the possible
+    /// schemas of the selected items, or `None` if they're unconstrained
+    fn step<'a>(
+        &'a self,
+        seg: &Segment,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        match seg {
+            Segment::Dot(_, selector) => self.raw_selector(selector, current, problems),
+            Segment::Bracket(_, selector) => self.bracket_selector(selector, current, problems),
+            Segment::Recursive(_, selector) => {
+                let descendants = current
+                    .iter()
+                    .flat_map(|schema| self.descendants(schema))
+                    .collect::<Vec<_>>();
+                match selector {
+                    Some(RawSelector::Name(name)) => {
+                        let before = problems.len();
+                        let next = self.name(name.as_str(), &descendants, problems);
+                        for (_, message) in &mut problems[before..] {
+                            *message =
+                                format!("No descendant can have the property '{}'", name.as_str());
+                        }
+                        next
+                    }
+                    Some(selector) => self.raw_selector(selector, &descendants, problems),
+                    None => Some(descendants),
+                }
+            }
+        }
+    }
+
+    fn raw_selector<'a>(
+        &'a self,
+        selector: &RawSelector,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        match selector {
+            RawSelector::Name(name) => self.name(name.as_str(), current, problems),
+            RawSelector::Wildcard(_) => self.children(current),
+            RawSelector::Parent(_) => None,
+        }
+    }
+
+    fn bracket_selector<'a>(
+        &'a self,
+        selector: &BracketSelector,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        match selector {
+            BracketSelector::Literal(lit) => self.literal(lit, current, problems),
+            BracketSelector::StepRange(_) | BracketSelector::Range(_) => {
+                self.index(None, current, problems)
+            }
+            BracketSelector::Wildcard(_) | BracketSelector::Filter(_) => self.children(current),
+            BracketSelector::Parent(_) | BracketSelector::Path(_) => None,
+            BracketSelector::Union(components) => {
+                let mut out = Vec::new();
+                for component in components {
+                    let next = match component {
+                        UnionComponent::Literal(lit) => self.literal(lit, current, problems),
+                        UnionComponent::StepRange(_) | UnionComponent::Range(_) => {
+                            self.index(None, current, problems)
+                        }
+                        UnionComponent::Filter(_) => self.children(current),
+                        UnionComponent::Parent(_) | UnionComponent::Path(_) => None,
+                    };
+                    out.extend(next?);
+                }
+                Some(out)
+            }
+        }
+    }
+
+    fn literal<'a>(
+        &'a self,
+        lit: &BracketLit,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        match (lit.as_str(), lit.as_int()) {
+            (Some(name), _) => self.name(name, current, problems),
+            (_, idx) => self.index(idx, current, problems),
+        }
+    }
+
+    /// Collect the results of looking up a child in each possible schema. If any schema doesn't
+    /// constrain the child the result is unconstrained, and if none allow it the first reason
+    /// it's missing is reported and nothing is selected
+    fn collect<'a>(
+        &'a self,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+        lookup: impl Fn(&'a Value) -> Lookup<'a>,
+        message: impl FnOnce(ValidationWarningKind) -> String,
+    ) -> Option<Vec<&'a Value>> {
+        let mut found = Vec::new();
+        let mut missing = None;
+        for schema in current {
+            match lookup(schema) {
+                Lookup::Found(child) => found.extend(self.expand(child)),
+                Lookup::Open => return None,
+                Lookup::Missing(kind) => {
+                    missing.get_or_insert(kind);
+                }
+            }
+        }
+        if let Some(kind) = missing.filter(|_| found.is_empty()) {
+            problems.push((kind, message(kind)));
+        }
+        Some(found)
+    }
+
+    fn name<'a>(
+        &'a self,
+        name: &str,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        let lookup = |schema: &'a Value| {
+            if allows_type(schema, "object") == Some(false) {
+                return Lookup::Missing(ValidationWarningKind::NotAnObject);
+            }
+            if let Some(child) = schema.get("properties").and_then(|props| props.get(name)) {
+                return Lookup::Found(child);
+            }
+            if schema.get("patternProperties").is_some() {
+                return Lookup::Open;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => Lookup::Missing(ValidationWarningKind::UnknownProperty),
+                Some(Value::Bool(true)) => Lookup::Open,
+                Some(child) => Lookup::Found(child),
+                None if schema.get("properties").is_some() => {
+                    Lookup::Missing(ValidationWarningKind::UnknownProperty)
+                }
+                None => Lookup::Open,
+            }
+        };
+        let message = |kind| match kind {
+            ValidationWarningKind::NotAnObject => {
+                format!(
+                    "Property '{}' selected from a value which isn't an object",
+                    name
+                )
+            }
+            _ => format!("Property '{}' isn't allowed by the schema", name),
+        };
+        self.collect(current, problems, lookup, message)
+    }
+
+    fn index<'a>(
+        &'a self,
+        idx: Option<i64>,
+        current: &[&'a Value],
+        problems: &mut Vec<Problem>,
+    ) -> Option<Vec<&'a Value>> {
+        let fixed = idx.and_then(|idx| usize::try_from(idx).ok());
+        let lookup = |schema: &'a Value| {
+            if allows_type(schema, "array") == Some(false) {
+                return Lookup::Missing(ValidationWarningKind::NotAnArray);
+            }
+            // Tuple schemas describe each index separately
+            let tuple = schema
+                .get("prefixItems")
+                .or_else(|| schema.get("items").filter(|items| items.is_array()));
+            if let (Some(Value::Array(tuple)), Some(idx)) = (tuple, fixed) {
+                if let Some(child) = tuple.get(idx) {
+                    return Lookup::Found(child);
+                }
+            }
+            match schema.get("items") {
+                Some(Value::Bool(false)) => Lookup::Missing(ValidationWarningKind::NotAnArray),
+                Some(child @ Value::Object(_)) => Lookup::Found(child),
+                _ => Lookup::Open,
+            }
+        };
+        let message = |_| match idx {
+            Some(idx) => format!("Index {} selected from a value which can't have it", idx),
+            None => "Slice selected from a value which isn't an array".to_string(),
+        };
+        self.collect(current, problems, lookup, message)
+    }
+
+    /// The possible schemas of every child of items matching the provided schemas, or `None` if
+    /// they're unconstrained
+    fn children<'a>(&'a self, current: &[&'a Value]) -> Option<Vec<&'a Value>> {
+        let mut out = Vec::new();
+        for schema in current {
+            let is_scalar = allows_type(schema, "object") == Some(false)
+                && allows_type(schema, "array") == Some(false);
+            if is_scalar {
+                continue;
+            }
+            let direct = self.direct_children(schema);
+            if direct.is_empty() {
+                return None;
+            }
+            out.extend(direct.into_iter().flat_map(|child| self.expand(child)));
+        }
+        Some(out)
+    }
+
+    /// The schemas directly nested in a schema which describe its children
+    fn direct_children<'a>(&self, schema: &'a Value) -> Vec<&'a Value> {
+        let mut out = Vec::new();
+        for key in ["properties", "patternProperties"] {
+            if let Some(Value::Object(props)) = schema.get(key) {
+                out.extend(props.values());
+            }
+        }
+        for key in ["additionalProperties", "items"] {
+            match schema.get(key) {
+                Some(child @ Value::Object(_)) => out.push(child),
+                Some(Value::Array(items)) => out.extend(items),
+                _ => (),
+            }
+        }
+        if let Some(Value::Array(items)) = schema.get("prefixItems") {
+            out.extend(items);
+        }
+        out
+    }
+
+    /// The provided schema and every schema nested below it which describes a descendant
+    fn descendants<'a>(&'a self, schema: &'a Value) -> Vec<&'a Value> {
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        let mut stack = vec![schema];
+        while let Some(schema) = stack.pop() {
+            if !seen.insert(schema as *const Value) {
+                continue;
+            }
+            out.push(schema);
+            for child in self.direct_children(schema) {
+                stack.extend(self.expand(child));
+            }
+        }
+        out
+    }
+
+    /// Resolve a schema into the schemas it's made of, following local `$ref`s and combining
+    /// keywords
+    fn expand<'a>(&'a self, schema: &'a Value) -> Vec<&'a Value> {
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        let mut stack = vec![schema];
+        while let Some(schema) = stack.pop() {
+            if !seen.insert(schema as *const Value) {
+                continue;
+            }
+            let target = schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.schema.pointer(pointer));
+            if let Some(target) = target {
+                stack.push(target);
+                continue;
+            }
+            let mut combined = false;
+            for key in ["allOf", "anyOf", "oneOf"] {
+                if let Some(Value::Array(options)) = schema.get(key) {
+                    stack.extend(options);
+                    combined = true;
+                }
+            }
+            if !combined || schema.get("properties").is_some() || schema.get("items").is_some() {
+                out.push(schema);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,386 @@
+//! Extraction of matches while deserializing, without building the full value.
+//!
+//! A [`PathExtractor`] implements [`DeserializeSeed`], so it can be driven by any serde
+//! [`Deserializer`], such as one created by [`serde_json::Deserializer::from_reader`]. Only the
+//! values matching the path are built, with everything else being skipped as it's read.
+//!
+//! Values are read once from start to end, so only paths which can be decided without looking
+//! ahead or back are supported:
+//!
+//! - Dot names, such as `.foo`, and bracket string literals, such as `['foo']`
+//! - Non-negative bracket index literals, such as `[0]`
+//! - Wildcards, such as `.*` or `[*]`
+//! - Slices with non-negative bounds and a positive step, such as `[1:]` or `[::2]`
+//! - Recursive descent, such as `..foo` or `..`
+//! - Unions of any of the above bracket selectors
+//!
+//! Filters, sub-paths, parent selectors, negative indices, and tildes result in an error.
+//! Matches are returned in document order, with each matched value only returned once, which
+//! may differ from the order [`JsonPath::find`] returns them in for unions and recursive
+//! descent.
+//!
+//! ```
+//! # use jsonpath_plus::JsonPath;
+//! # use jsonpath_plus::stream::PathExtractor;
+//! # use serde::de::DeserializeSeed;
+//! let path = JsonPath::compile("$.items[*].id").unwrap();
+//! let extractor = PathExtractor::new(&path).unwrap();
+//!
+//! let input = r#"{"items": [{"id": 1, "data": [1, 2, 3]}, {"id": 2}]}"#;
+//! let mut de = serde_json::Deserializer::from_str(input);
+//! let ids = extractor.deserialize(&mut de).unwrap();
+//! assert_eq!(ids, [1, 2]);
+//! ```
+//!
+//! [`JsonPath::find`]: crate::JsonPath::find
+
+use crate::ast::{
+    BracketLit, BracketSelector, Range, RawSelector, Segment, StepRange, UnionComponent,
+};
+use crate::error::{EvalError, EvalErrorKind};
+use crate::JsonPath;
+use core::fmt;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Clone, Copy)]
+enum Key<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+enum Selector {
+    Name(String),
+    Index(usize),
+    Wildcard,
+    Slice {
+        start: usize,
+        end: Option<usize>,
+        step: usize,
+    },
+    Union(Vec<Selector>),
+}
+
+impl Selector {
+    fn matches(&self, key: Key<'_>) -> bool {
+        match (self, key) {
+            (Selector::Name(name), Key::Name(key)) => name == key,
+            (Selector::Index(idx), Key::Index(key)) => *idx == key,
+            (Selector::Wildcard, _) => true,
+            (Selector::Slice { start, end, step }, Key::Index(key)) => {
+                key >= *start && end.is_none_or(|end| key < end) && (key - start) % step == 0
+            }
+            (Selector::Union(selectors), key) => selectors.iter().any(|sel| sel.matches(key)),
+            _ => false,
+        }
+    }
+}
+
+fn unsupported(feature: &'static str) -> EvalError {
+    EvalErrorKind::Unsupported(feature).into()
+}
+
+fn non_negative(val: Option<i64>) -> Result<Option<usize>, EvalError> {
+    val.map(|val| usize::try_from(val).map_err(|_| unsupported("negative indices")))
+        .transpose()
+}
+
+fn slice(start: Option<i64>, end: Option<i64>, step: i64) -> Result<Selector, EvalError> {
+    Ok(Selector::Slice {
+        start: non_negative(start)?.unwrap_or(0),
+        end: non_negative(end)?,
+        step: usize::try_from(step).map_err(|_| unsupported("negative steps"))?,
+    })
+}
+
+fn from_step_range(range: &StepRange) -> Result<Selector, EvalError> {
+    slice(
+        range.start(),
+        range.end(),
+        range.step().map_or(1, |step| step.get()),
+    )
+}
+
+fn from_range(range: &Range) -> Result<Selector, EvalError> {
+    slice(range.start(), range.end(), 1)
+}
+
+fn from_literal(lit: &BracketLit) -> Result<Selector, EvalError> {
+    match lit {
+        BracketLit::Int(idx) => Ok(Selector::Index(
+            usize::try_from(idx.as_int()).map_err(|_| unsupported("negative indices"))?,
+        )),
+        BracketLit::String(name) => Ok(Selector::Name(name.as_str().to_string())),
+    }
+}
+
+fn from_raw(sel: &RawSelector) -> Result<Selector, EvalError> {
+    match sel {
+        RawSelector::Wildcard(_) => Ok(Selector::Wildcard),
+        RawSelector::Name(name) => Ok(Selector::Name(name.as_str().to_string())),
+        RawSelector::Parent(_) => Err(unsupported("parent selectors")),
+    }
+}
+
+fn from_component(component: &UnionComponent) -> Result<Selector, EvalError> {
+    match component {
+        UnionComponent::StepRange(range) => from_step_range(range),
+        UnionComponent::Range(range) => from_range(range),
+        UnionComponent::Literal(lit) => from_literal(lit),
+        UnionComponent::Parent(_) => Err(unsupported("parent selectors")),
+        UnionComponent::Path(_) => Err(unsupported("sub-path selectors")),
+        UnionComponent::Filter(_) => Err(unsupported("filter selectors")),
+    }
+}
+
+fn from_bracket(sel: &BracketSelector) -> Result<Selector, EvalError> {
+    match sel {
+        BracketSelector::Union(components) => components
+            .iter()
+            .map(from_component)
+            .collect::<Result<_, _>>()
+            .map(Selector::Union),
+        BracketSelector::StepRange(range) => from_step_range(range),
+        BracketSelector::Range(range) => from_range(range),
+        BracketSelector::Wildcard(_) => Ok(Selector::Wildcard),
+        BracketSelector::Literal(lit) => from_literal(lit),
+        BracketSelector::Parent(_) => Err(unsupported("parent selectors")),
+        BracketSelector::Path(_) => Err(unsupported("sub-path selectors")),
+        BracketSelector::Filter(_) => Err(unsupported("filter selectors")),
+    }
+}
+
+enum Step {
+    Child(Selector),
+    Recursive(Option<Selector>),
+}
+
+/// Extracts the values matching a path while deserializing, see the [module docs](self)
+pub struct PathExtractor {
+    steps: Vec<Step>,
+}
+
+impl PathExtractor {
+    /// Create an extractor for the provided path
+    ///
+    /// # Errors
+    ///
+    /// - If the path uses a feature which can't be evaluated while streaming, such as a filter
+    pub fn new(path: &JsonPath) -> Result<PathExtractor, EvalError> {
+        if path.is_id() {
+            return Err(unsupported("tildes"));
+        }
+        let steps = path
+            .segments()
+            .iter()
+            .map(|seg| match seg {
+                Segment::Dot(_, sel) => from_raw(sel).map(Step::Child),
+                Segment::Bracket(_, sel) => from_bracket(sel).map(Step::Child),
+                Segment::Recursive(_, sel) => {
+                    sel.as_ref().map(from_raw).transpose().map(Step::Recursive)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PathExtractor { steps })
+    }
+
+    /// A bare recursive descent also matches the item it's applied to, so add the following
+    /// step for the same item
+    fn close(&self, states: &mut Vec<usize>) {
+        let mut idx = 0;
+        while idx < states.len() {
+            let state = states[idx];
+            if matches!(self.steps.get(state), Some(Step::Recursive(None)))
+                && !states.contains(&(state + 1))
+            {
+                states.push(state + 1);
+            }
+            idx += 1;
+        }
+        states.sort_unstable();
+        states.dedup();
+    }
+
+    fn start(&self) -> Vec<usize> {
+        let mut states = vec![0];
+        self.close(&mut states);
+        states
+    }
+
+    /// The states active for a child with the provided key, given the states active for its
+    /// parent
+    fn advance(&self, states: &[usize], key: Key<'_>) -> Vec<usize> {
+        let mut out = Vec::new();
+        for &state in states {
+            match self.steps.get(state) {
+                Some(Step::Child(sel)) if sel.matches(key) => out.push(state + 1),
+                Some(Step::Recursive(sel)) => {
+                    out.push(state);
+                    if sel.as_ref().is_some_and(|sel| sel.matches(key)) {
+                        out.push(state + 1);
+                    }
+                }
+                _ => (),
+            }
+        }
+        self.close(&mut out);
+        out
+    }
+
+    fn is_match(&self, states: &[usize]) -> bool {
+        states.contains(&self.steps.len())
+    }
+
+    /// Collect matches from a value which has already been built, because it matched
+    fn walk(&self, value: &Value, states: &[usize], out: &mut Vec<Value>) {
+        if self.is_match(states) {
+            out.push(value.clone());
+        }
+        let mut visit = |key, child| {
+            let states = self.advance(states, key);
+            if !states.is_empty() {
+                self.walk(child, &states, out);
+            }
+        };
+        match value {
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .for_each(|(idx, child)| visit(Key::Index(idx), child)),
+            Value::Object(obj) => obj
+                .iter()
+                .for_each(|(key, child)| visit(Key::Name(key), child)),
+            _ => (),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for &PathExtractor {
+    type Value = Vec<Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<Value>, D::Error> {
+        let mut out = Vec::new();
+        Node {
+            extractor: self,
+            states: self.start(),
+            out: &mut out,
+        }
+        .deserialize(deserializer)?;
+        Ok(out)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for PathExtractor {
+    type Value = Vec<Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<Value>, D::Error> {
+        (&self).deserialize(deserializer)
+    }
+}
+
+/// A value being deserialized, along with the path steps active for it
+struct Node<'a> {
+    extractor: &'a PathExtractor,
+    states: Vec<usize>,
+    out: &'a mut Vec<Value>,
+}
+
+impl Node<'_> {
+    fn child(&mut self, key: Key<'_>) -> Option<Node<'_>> {
+        let states = self.extractor.advance(&self.states, key);
+        (!states.is_empty()).then_some(Node {
+            extractor: self.extractor,
+            states,
+            out: &mut *self.out,
+        })
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Node<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.extractor.is_match(&self.states) {
+            let value = Value::deserialize(deserializer)?;
+            self.extractor.walk(&value, &self.states, self.out);
+            Ok(())
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for Node<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        let mut idx = 0;
+        loop {
+            let more = match self.child(Key::Index(idx)) {
+                Some(child) => seq.next_element_seed(child)?.is_some(),
+                None => seq.next_element::<IgnoredAny>()?.is_some(),
+            };
+            if !more {
+                return Ok(());
+            }
+            idx += 1;
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match self.child(Key::Name(&key)) {
+                Some(child) => map.next_value_seed(child)?,
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,62 +1,7 @@
 use super::*;
+use crate::utils::ValueKey;
 use serde_json::{json, Value};
 use std::collections::HashSet;
-use std::fmt;
-use std::hash::{Hash, Hasher};
-
-fn hash_val<H: Hasher>(val: &Value, state: &mut H) {
-    match val {
-        Value::Null => state.write_u8(0),
-        Value::Bool(b) => {
-            state.write_u8(1);
-            state.write_u8(*b as u8);
-        }
-        Value::Number(n) => {
-            state.write_u8(2);
-            state.write(&n.as_f64().unwrap().to_ne_bytes());
-        }
-        Value::String(s) => {
-            state.write_u8(3);
-            state.write(s.as_bytes());
-        }
-        Value::Array(a) => {
-            state.write_u8(4);
-            state.write_usize(a.len());
-            for v in a {
-                hash_val(v, state);
-            }
-        }
-        Value::Object(m) => {
-            state.write_u8(5);
-            state.write_usize(m.len());
-            for (key, val) in m {
-                state.write(key.as_bytes());
-                hash_val(val, state);
-            }
-        }
-    }
-}
-
-#[derive(PartialEq, Eq)]
-pub struct ValueKey(Value);
-
-impl fmt::Debug for ValueKey {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        <Value as fmt::Debug>::fmt(&self.0, f)
-    }
-}
-
-impl Hash for ValueKey {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        hash_val(&self.0, state)
-    }
-}
-
-impl From<Value> for ValueKey {
-    fn from(val: Value) -> Self {
-        ValueKey(val)
-    }
-}
 
 #[test]
 fn test_replace() {
@@ -149,7 +94,7 @@ fn dot_notation_after_recursive_descent() {
         .into_iter()
         .cloned()
         .map(ValueKey::from)
-        .collect::<HashSet<ValueKey>>();
+        .collect::<HashSet<ValueKey<'_>>>();
 
     assert_eq!(
         result,
@@ -170,7 +115,7 @@ fn bracket_notation_after_recursive_descent() {
         .into_iter()
         .cloned()
         .map(ValueKey::from)
-        .collect::<HashSet<ValueKey>>();
+        .collect::<HashSet<ValueKey<'_>>>();
 
     assert_eq!(
         result,
@@ -201,7 +146,7 @@ fn parent_after_recursive_descent() {
         .into_iter()
         .cloned()
         .map(ValueKey::from)
-        .collect::<HashSet<ValueKey>>();
+        .collect::<HashSet<ValueKey<'_>>>();
 
     assert_eq!(
         result,
@@ -322,3 +267,2993 @@ fn dot_notation_with_empty_path() {
     let json = json!({"key": 42, "": 9001, "''": "nice"});
     let _result = find("$.", &json).unwrap();
 }
+
+#[test]
+fn filter_with_integer_addition() {
+    let json = json!([{"a": 1}, {"a": 2}, {"a": "2"}]);
+    let result = find("$[?(@.a + 1 > 2)]", &json).unwrap();
+
+    let expected = vec![&json.as_array().unwrap()[1]];
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn filter_integer_arithmetic() {
+    let json = json!([{"a": 3}, {"a": 0}, {"a": i64::MAX}]);
+
+    let result = find("$[?(@.a * 2 == 6)]", &json).unwrap();
+    assert_eq!(result, [&json[0]]);
+
+    // Overflow and division by zero never match, rather than wrapping or panicking
+    let result = find("$[?(@.a + 1 > 0)]", &json).unwrap();
+    assert_eq!(result, [&json[0], &json[1]]);
+    let result = find("$[?(7 % @.a == 1)]", &json).unwrap();
+    assert_eq!(result, [&json[0]]);
+    // Division is always floating point, so dividing by zero isn't an error
+    let result = find("$[?(7 / @.a > 0)]", &json).unwrap();
+    assert_eq!(result, [&json[0], &json[2]]);
+
+    // Integer addition stays exact past the precision of a float
+    let json = json!([{"a": 9_007_199_254_740_993_i64}]);
+    let result = find("$[?(@.a + 0 == 9007199254740993)]", &json).unwrap();
+    assert_eq!(result, [&json[0]]);
+}
+
+#[test]
+fn filter_equality_is_exact() {
+    let json = json!([
+        {"a": 1},
+        {"a": 1.0},
+        {"a": [1, {"b": 2}]},
+        {"a": [1.0, {"b": 2.0}]},
+    ]);
+
+    let result = find("$[?(@.a == 1)]", &json).unwrap();
+    assert_eq!(result, [&json[0]]);
+    let result = find("$[?(@.a == $[1].a)]", &json).unwrap();
+    assert_eq!(result, [&json[1]]);
+    let result = find("$[?(@.a == $[2].a)]", &json).unwrap();
+    assert_eq!(result, [&json[2]]);
+
+    // Ordering still compares numerically
+    let result = find("$[?(@.a >= $[1].a)]", &json).unwrap();
+    assert_eq!(result, [&json[0], &json[1]]);
+}
+
+#[test]
+fn filter_strict_arithmetic() {
+    let json = json!([{"a": 3}, {"a": 0}, {"a": i64::MAX}, {"a": "b"}]);
+    let strict = EvalOptions::new().strict_arithmetic(true);
+
+    let path = JsonPath::compile("$[?(@.a * 2 == 6)]").unwrap();
+    assert!(path.try_find_with(&json, strict).is_err());
+    assert_eq!(
+        path.try_find_with(&json, EvalOptions::new()).unwrap(),
+        [&json[0]]
+    );
+
+    let path = JsonPath::compile("$[?(7 % @.a == 1)]").unwrap();
+    let err = path.try_find_with(&json, strict).unwrap_err();
+    assert!(err.is_arithmetic());
+    assert!(matches!(
+        err.kind(),
+        crate::error::EvalErrorKind::DivideByZero
+    ));
+
+    let path = JsonPath::compile("$[?(@.a + 1 > 0)]").unwrap();
+    let err = path.try_find_with(&json, strict).unwrap_err();
+    assert!(matches!(err.kind(), crate::error::EvalErrorKind::Overflow));
+
+    // Other filter errors, like the string compared to a number, still don't match
+    let path = JsonPath::compile("$[?(@.a > 0)]").unwrap();
+    assert_eq!(
+        path.try_find_with(&json, strict).unwrap(),
+        [&json[0], &json[2]]
+    );
+    assert!(path
+        .try_find_with(&json, EvalOptions::new().strict_filter_errors(true))
+        .is_err());
+
+    // Dividing by zero is floating point, so isn't an arithmetic error even when strict
+    let path = JsonPath::compile("$[?(7 / @.a > 1)]").unwrap();
+    assert_eq!(path.try_find_with(&json, strict).unwrap(), [&json[0]]);
+}
+
+#[test]
+fn parse_error_spans() {
+    let err = match JsonPath::compile("$.a[1") {
+        Ok(_) => panic!("Path should fail to parse"),
+        Err(err) => err,
+    };
+
+    let primary = err.primary_span().unwrap();
+    assert!(err.all_spans().contains(&primary));
+    assert!(err
+        .all_spans()
+        .iter()
+        .all(|span| span.start <= primary.start));
+}
+
+#[test]
+fn find_unique_removes_equal_values() {
+    let json = json!({"a": 1, "b": 1, "c": [1, 2]});
+    let path = JsonPath::compile("$..*").unwrap();
+
+    assert_eq!(path.find(&json).len(), 5);
+    assert_eq!(
+        path.find_unique_owned(&json),
+        vec![json!(1), json!([1, 2]), json!(2)]
+    );
+
+    let json = json!([0.0, -0.0, 1, 1]);
+    let path = JsonPath::compile("$[*]").unwrap();
+    assert_eq!(path.find_unique(&json), [&json[0], &json[2]]);
+}
+
+#[test]
+fn compile_with_warnings() {
+    use crate::warning::WarningKind;
+
+    let cases = [
+        ("$.a[*]", vec![]),
+        ("$[5:2]", vec![WarningKind::EmptySlice]),
+        ("$[-1:-3:1]", vec![WarningKind::EmptySlice]),
+        ("$[5:2:-1]", vec![]),
+        ("$[-1:2]", vec![]),
+        ("$[1, 'a', 1]", vec![WarningKind::DuplicateUnion]),
+        ("$[?(1 == 2)]", vec![WarningKind::ConstantFilter]),
+        ("$[?(@.a == 2)]", vec![]),
+        ("$....a", vec![WarningKind::RedundantRecursive]),
+        ("$[$.a[3:3]]", vec![WarningKind::EmptySlice]),
+    ];
+
+    for (path, expected) in cases {
+        let (_, warnings) = JsonPath::compile_with_warnings(path).unwrap();
+        let kinds = warnings.iter().map(|w| w.kind()).collect::<Vec<_>>();
+        assert_eq!(kinds, expected, "Unexpected warnings for path {}", path);
+    }
+}
+
+#[test]
+fn parse_error_unclosed_bracket() {
+    use crate::ast::FailReason;
+
+    let err = match JsonPath::compile("$.a[1") {
+        Ok(_) => panic!("Path should fail to parse"),
+        Err(err) => err,
+    };
+
+    let unclosed = err.errors().iter().find_map(|err| match err.reason() {
+        FailReason::Unclosed {
+            unclosed_span,
+            delimiter,
+            ..
+        } => Some((*unclosed_span, *delimiter)),
+        _ => None,
+    });
+
+    assert_eq!(unclosed, Some((Span::from(3..4), '[')));
+    assert!(err.to_string().contains("Unclosed delimiter '['"));
+}
+
+#[test]
+fn span_ranges() {
+    let span = Span::from(3..6);
+    assert_eq!(span.as_range(), 3..6);
+    assert_eq!(span.len(), 3);
+    assert!(!span.is_empty());
+    assert!(span.contains(3));
+    assert!(span.contains(5));
+    assert!(!span.contains(6));
+    assert!(!span.contains(2));
+
+    let empty = Span::from(4..4);
+    assert!(empty.is_empty());
+    assert!(!empty.contains(4));
+}
+
+#[test]
+fn root_match_mutation() {
+    let json = json!({"a": [1, 2]});
+    let path = JsonPath::compile("$").unwrap();
+
+    assert_eq!(path.delete(&json), Value::Null);
+    assert_eq!(path.replace(&json, |_| json!(true)), json!(true));
+    assert_eq!(path.try_replace(&json, |_| Some(json!(1))), json!(1));
+    assert_eq!(path.try_replace(&json, |_| None), Value::Null);
+
+    let mut json = json;
+    path.delete_on(&mut json);
+    assert_eq!(json, Value::Null);
+}
+
+#[test]
+fn root_match_mutation_with_children() {
+    let json = json!({"a": [1, 2]});
+    let path = JsonPath::compile("$..").unwrap();
+
+    let result = path.replace(&json, |v| match v {
+        Value::Number(n) => json!(n.as_i64().unwrap() * 10),
+        other => other.clone(),
+    });
+    assert_eq!(result, json!({"a": [10, 20]}));
+
+    assert_eq!(path.delete(&json), Value::Null);
+}
+
+#[test]
+fn idx_path_pop_and_shift() {
+    let mut path = IdxPath::from(vec![
+        Idx::Object("a".to_string()),
+        Idx::Array(1),
+        Idx::Object("b".to_string()),
+    ]);
+
+    assert_eq!(path.pop(), Some(Idx::Object("b".to_string())));
+    assert_eq!(path.len(), 2);
+
+    let (first, rest) = path.shift().unwrap();
+    assert_eq!(first, Idx::Object("a".to_string()));
+    assert_eq!(rest.raw_path(), &[Idx::Array(1)]);
+
+    let (_, rest) = rest.shift().unwrap();
+    assert!(rest.shift().is_none());
+}
+
+#[test]
+fn resolve_error_depth() {
+    let json = json!({"a": {"b": [1, "two", 3]}});
+    let path = IdxPath::from(vec![
+        Idx::Object("a".to_string()),
+        Idx::Object("b".to_string()),
+        Idx::Array(1),
+        Idx::Array(0),
+    ]);
+
+    let err = path.resolve_on(&json).unwrap_err();
+    assert_eq!(err.depth(), 3);
+    assert_eq!(err.resolved().raw_path(), &path[..3]);
+    assert_eq!(
+        err.to_string(),
+        "Failed to resolve path at depth 3 (`$['a']['b'][1]`): expected array, found string"
+    );
+}
+
+#[test]
+fn compile_all_reports_every_error() {
+    let paths = JsonPath::compile_all(&["$.a", "$[*]"]).unwrap();
+    assert_eq!(paths.len(), 2);
+
+    let err = match JsonPath::compile_all(&["$.a[", "$.b", "$."]) {
+        Ok(_) => panic!("Paths should fail to parse"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.errors().len(), 3);
+    assert_eq!(err.failed().map(|(idx, _)| idx).collect::<Vec<_>>(), [0, 2]);
+    assert!(err
+        .to_string()
+        .starts_with("Error Parsing 2 of 3 JSON Paths:"));
+}
+
+#[test]
+fn resolve_error_mismatched_types() {
+    use crate::error::{JsonTy, ResolveError};
+
+    fn assert_mismatch(path: &IdxPath, json: &Value, exp: JsonTy, act: JsonTy) {
+        let errs = [
+            path.resolve_on(json).unwrap_err(),
+            path.resolve_on_mut(&mut json.clone()).unwrap_err(),
+        ];
+        for err in errs {
+            match err {
+                ResolveError::MismatchedTy {
+                    expected, actual, ..
+                } => {
+                    assert_eq!((expected, actual), (exp, act));
+                }
+                err => panic!("Unexpected error {:?}", err),
+            }
+        }
+    }
+
+    let array_on_object = IdxPath::from(vec![Idx::Array(0)]);
+    assert_mismatch(
+        &array_on_object,
+        &json!({"a": 1}),
+        JsonTy::Array,
+        JsonTy::Object,
+    );
+
+    let object_on_array = IdxPath::from(vec![Idx::Object("a".to_string())]);
+    assert_mismatch(&object_on_array, &json!([1]), JsonTy::Object, JsonTy::Array);
+
+    let object_on_number = IdxPath::from(vec![Idx::Object("a".to_string())]);
+    assert_mismatch(&object_on_number, &json!(1), JsonTy::Object, JsonTy::Number);
+}
+
+#[test]
+fn filter_and_map_segments() {
+    use crate::ast::{BracketSelector, Segment};
+
+    let json = json!({"a": [{"b": 1, "c": true}, {"b": 2, "c": false}]});
+    let path = JsonPath::compile("$.a[?(@.c == true)].b").unwrap();
+
+    let skeleton =
+        path.filter_segments(|seg| !matches!(seg, Segment::Bracket(_, BracketSelector::Filter(_))));
+    assert_eq!(skeleton.segments().len(), 2);
+
+    let wildcard = JsonPath::compile("$[*]").unwrap();
+    let mapped = path.map_segments(|seg| match seg {
+        Segment::Bracket(_, BracketSelector::Filter(_)) => Some(wildcard.segments()[0].clone()),
+        other => Some(other.clone()),
+    });
+
+    assert_eq!(path.find(&json), [&json!(1)]);
+    assert_eq!(mapped.find(&json), [&json!(1), &json!(2)]);
+}
+
+#[test]
+fn find_str_errors() {
+    use crate::error::Error;
+
+    assert!(matches!(find_str("$.", "{}"), Err(Error::Parse(_))));
+    assert!(matches!(find_str("$.a", "{"), Err(Error::Json(_))));
+    assert_eq!(find_str("$.a", r#"{"a": 1}"#).unwrap(), [json!(1)]);
+}
+
+#[test]
+fn path_conversions() {
+    let json = json!({"a": 1});
+
+    let path = JsonPath::try_from("$.a").unwrap();
+    assert_eq!(path.find(&json), [&json!(1)]);
+
+    let path = JsonPath::try_from("$.a".to_string()).unwrap();
+    assert_eq!(path.find(&json), [&json!(1)]);
+
+    let path = "$.a".parse::<JsonPath>().unwrap();
+    assert_eq!(path.find(&json), [&json!(1)]);
+
+    assert!(JsonPath::try_from("$.").is_err());
+    assert!(JsonPath::try_from("a".to_string()).is_err());
+
+    let path = JsonPath::compile("$.a[0, 'b'][?(@.c == 1)]").unwrap();
+    let string = String::from(path.clone());
+    assert_eq!(string, "$.a[0, 'b'][?(@.c == 1)]");
+    assert_eq!(JsonPath::try_from(string).unwrap(), path);
+}
+
+#[test]
+fn idx_path_display() {
+    assert_eq!(IdxPath::from(vec![]).to_string(), "$");
+
+    let path = IdxPath::from(vec![
+        Idx::Object("store".to_string()),
+        Idx::Object("book".to_string()),
+        Idx::Array(0),
+        Idx::Object("title".to_string()),
+    ]);
+    assert_eq!(path.to_string(), "$['store']['book'][0]['title']");
+
+    let path = IdxPath::from(vec![
+        Idx::Object("it's".to_string()),
+        Idx::Object("back\\slash".to_string()),
+        Idx::Object("ඞ".to_string()),
+        Idx::Object("line\nbreak\u{1}".to_string()),
+    ]);
+    assert_eq!(
+        path.to_normalized_string(),
+        r"$['it\'s']['back\\slash']['ඞ']['line\nbreak\u0001']"
+    );
+}
+
+#[test]
+fn find_with_defaults() {
+    let json = json!({"a": [1, 2]});
+    let default = json!("default");
+
+    let path = JsonPath::compile("$.a[*]").unwrap();
+    assert_eq!(path.find_or_default(&json, &default), &json!(1));
+    assert_eq!(
+        path.find_or_else(&json, || panic!("a match was found")),
+        &json!(1)
+    );
+    assert_eq!(
+        path.find_str_or(r#"{"a": [3, 4]}"#, json!(null)).unwrap(),
+        json!(3)
+    );
+
+    let path = JsonPath::compile("$.b").unwrap();
+    assert_eq!(path.find_or_default(&json, &default), &default);
+    assert_eq!(path.find_or_else(&json, || &default), &default);
+    assert_eq!(
+        path.find_str_or(r#"{"a": 1}"#, json!(null)).unwrap(),
+        Value::Null
+    );
+}
+
+#[test]
+fn pipeline_applies_in_order() {
+    let json = json!({"users": [{"name": "a", "age": 1}, {"name": "b", "age": 2}], "meta": {}});
+
+    let mut replaced = 0;
+    let result = JsonPath::pipeline()
+        .delete(JsonPath::compile("$.meta").unwrap())
+        .replace(JsonPath::compile("$.users[*].age").unwrap(), |v| {
+            replaced += 1;
+            json!(v.as_i64().unwrap() + 1)
+        })
+        .find(JsonPath::compile("$.users[*].age").unwrap())
+        .execute(&json);
+
+    assert_eq!(result, json!([2, 3]));
+    assert_eq!(replaced, 2);
+}
+
+#[test]
+fn idx_path_construction() {
+    let mut path = IdxPath::from(vec![]);
+    assert_eq!(path.parent(), None);
+    assert_eq!(path.first(), None);
+
+    path.push(Idx::Object("a".to_string()));
+    let child = path.child(Idx::Array(2));
+
+    assert_eq!(child.first(), Some(&Idx::Object("a".to_string())));
+    assert_eq!(child.last(), Some(&Idx::Array(2)));
+    assert_eq!(child.parent(), Some(path.clone()));
+    assert_eq!(child.try_remove(2), Some(IdxPath::from(vec![])));
+    assert_eq!(child.try_remove(3), None);
+}
+
+#[test]
+fn idx_path_prefixes() {
+    let empty = IdxPath::from(vec![]);
+    let a = IdxPath::from(vec![Idx::Object("a".to_string())]);
+    let a_0 = a.child(Idx::Array(0));
+    let a_1_b = a.child(Idx::Array(1)).child(Idx::Object("b".to_string()));
+
+    assert!(a_0.starts_with(&empty));
+    assert!(a_0.starts_with(&a));
+    assert!(a_0.starts_with(&a_0));
+    assert!(!a.starts_with(&a_0));
+    assert!(!a_1_b.starts_with(&a_0));
+
+    assert_eq!(
+        a_0.strip_prefix(&a),
+        Some(IdxPath::from(vec![Idx::Array(0)]))
+    );
+    assert_eq!(a_0.strip_prefix(&empty), Some(a_0.clone()));
+    assert_eq!(a.strip_prefix(&a_0), None);
+
+    assert_eq!(a.join(&IdxPath::from(vec![Idx::Array(0)])), a_0);
+    assert_eq!(a_0.common_prefix(&a_1_b), a);
+    assert_eq!(a_0.common_prefix(&empty), empty);
+
+    assert!(a_1_b.within(&a));
+    assert!(a_0.within(&a_0));
+    assert!(!a.within(&a_0));
+
+    let a_1 = a.child(Idx::Array(1));
+    assert!(a_0.is_sibling_of(&a_1));
+    assert!(a_0.is_sibling_of(&a_0));
+    assert!(!a_0.is_sibling_of(&a_1_b));
+    assert!(!a.is_sibling_of(&a_0));
+    assert!(!empty.is_sibling_of(&empty));
+}
+
+#[test]
+fn validate_match_types() {
+    use crate::error::JsonTy;
+
+    let json = json!({"users": [{"age": 1}, {"age": "2"}, {"age": 3}, {"age": null}]});
+    let path = JsonPath::compile("$.users[*].age").unwrap();
+
+    let mismatched = path.validate_types(&json, JsonTy::Number);
+    assert_eq!(
+        mismatched
+            .iter()
+            .map(|(path, ty)| (path.to_string(), *ty))
+            .collect::<Vec<_>>(),
+        [
+            ("$['users'][1]['age']".to_string(), JsonTy::String),
+            ("$['users'][3]['age']".to_string(), JsonTy::Null),
+        ]
+    );
+
+    let report = path.validate_types_report(&json, JsonTy::Number);
+    assert_eq!(report[0].2, &json!("2"));
+
+    assert!(JsonPath::compile("$.users")
+        .unwrap()
+        .validate_types(&json, JsonTy::Array)
+        .is_empty());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn idx_path_serde_round_trip() {
+    let path = IdxPath::from(vec![
+        Idx::Object("a".to_string()),
+        Idx::Array(0),
+        Idx::Object("0".to_string()),
+    ]);
+
+    let json = serde_json::to_value(&path).unwrap();
+    assert_eq!(json, json!(["a", 0, "0"]));
+    assert_eq!(serde_json::from_value::<IdxPath>(json).unwrap(), path);
+
+    let bytes = bincode::serialize(&path).unwrap();
+    assert_eq!(bincode::deserialize::<IdxPath>(&bytes).unwrap(), path);
+
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&path, &mut cbor).unwrap();
+    let round_trip: IdxPath = ciborium::de::from_reader(&*cbor).unwrap();
+    assert_eq!(round_trip, path);
+}
+
+#[test]
+fn select_keys_projects_objects() {
+    let json = json!({"users": [
+        {"name": "a", "email": "a@x", "age": 1},
+        {"name": "b", "age": 2},
+        "not an object",
+    ]});
+    let path = JsonPath::compile("$.users[*]").unwrap();
+
+    assert_eq!(
+        path.select_keys(&json, &["name", "email"]),
+        [
+            json!({"name": "a", "email": "a@x"}),
+            json!({"name": "b"}),
+            json!("not an object"),
+        ]
+    );
+}
+
+#[test]
+fn idx_traits() {
+    use std::collections::BTreeSet;
+
+    assert_eq!(Idx::from(3), Idx::Array(3));
+    assert_eq!(Idx::from("a"), Idx::Object("a".to_string()));
+    assert_eq!(Idx::from("a".to_string()), Idx::Object("a".to_string()));
+
+    assert_eq!(Idx::from(3).to_string(), "[3]");
+    assert_eq!(Idx::from("it's").to_string(), r"['it\'s']");
+
+    let sorted = BTreeSet::from([Idx::from("b"), Idx::from(10), Idx::from("a"), Idx::from(2)]);
+    assert_eq!(
+        sorted.into_iter().collect::<Vec<_>>(),
+        [Idx::from(2), Idx::from(10), Idx::from("a"), Idx::from("b")]
+    );
+
+    let paths = HashSet::from([
+        IdxPath::from(vec![Idx::from("a")]),
+        IdxPath::from(vec![Idx::from("a")]),
+        IdxPath::from(vec![Idx::from("a"), Idx::from(0)]),
+    ]);
+    assert_eq!(paths.len(), 2);
+}
+
+#[test]
+fn delete_parent_and_child() {
+    let json = json!({"a": {"b": [1, 2, {"c": 3}]}, "d": 4});
+
+    let result = JsonPath::compile("$.a..").unwrap().delete(&json);
+    assert_eq!(result, json!({"d": 4}));
+
+    let result = JsonPath::compile("$.a.b[2, 2, 0]").unwrap().delete(&json);
+    assert_eq!(result, json!({"a": {"b": [2]}, "d": 4}));
+}
+
+#[test]
+fn idx_path_resolve_iter() {
+    let json = json!({"a": {"b": [1, 2]}});
+
+    let path = IdxPath::from(vec![Idx::from("a"), Idx::from("b"), Idx::from(1)]);
+    let steps = path
+        .resolve_iter(&json)
+        .map(|step| step.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        [
+            (&Idx::from("a"), &json["a"]),
+            (&Idx::from("b"), &json["a"]["b"]),
+            (&Idx::from(1), &json["a"]["b"][1]),
+        ]
+    );
+
+    let path = IdxPath::from(vec![Idx::from("a"), Idx::from("c"), Idx::from(1)]);
+    let mut iter = path.resolve_iter(&json);
+    assert!(iter.next().unwrap().is_ok());
+    assert_eq!(iter.next().unwrap().unwrap_err().depth(), 1);
+    assert!(iter.next().is_none());
+
+    assert_eq!(IdxPath::from(vec![]).resolve_iter(&json).count(), 0);
+}
+
+#[test]
+fn mutation_collects_originals() {
+    let json = json!({"a": [1, 2, 3], "b": {"a": 4}});
+
+    let mut out = json.clone();
+    let deleted = JsonPath::compile("$..a")
+        .unwrap()
+        .delete_on_collect(&mut out);
+    assert_eq!(out, json!({"b": {}}));
+    let mut deleted = deleted
+        .into_iter()
+        .map(|(path, val)| (path.to_string(), val))
+        .collect::<Vec<_>>();
+    deleted.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        deleted,
+        [
+            ("$['a']".to_string(), json!([1, 2, 3])),
+            ("$['b']['a']".to_string(), json!(4)),
+        ]
+    );
+
+    let mut out = json.clone();
+    let replaced = JsonPath::compile("$.a[*]")
+        .unwrap()
+        .replace_on_collect(&mut out, |v| json!(v.as_i64().unwrap() * 10));
+    assert_eq!(out, json!({"a": [10, 20, 30], "b": {"a": 4}}));
+    assert_eq!(replaced.len(), 3);
+    for (path, val) in replaced.into_iter().rev() {
+        *path.resolve_on_mut(&mut out).unwrap() = val;
+    }
+    assert_eq!(out, json);
+}
+
+#[test]
+fn idx_path_create_on() {
+    use crate::error::ResolveError;
+
+    let path = IdxPath::from(vec![Idx::from("a"), Idx::from(2), Idx::from("b")]);
+
+    let mut json = Value::Null;
+    *path.create_on(&mut json).unwrap() = json!(true);
+    assert_eq!(json, json!({"a": [null, null, {"b": true}]}));
+
+    let mut json = json!({"a": [1, 2, {"c": 3}]});
+    *path.create_on(&mut json).unwrap() = json!(true);
+    assert_eq!(json, json!({"a": [1, 2, {"c": 3, "b": true}]}));
+
+    let mut json = json!({"a": "str"});
+    let err = path.create_on(&mut json).unwrap_err();
+    assert_eq!(err.depth(), 1);
+    assert!(matches!(
+        err,
+        ResolveError::MismatchedTy {
+            expected: JsonTy::Array,
+            actual: JsonTy::String,
+            ..
+        }
+    ));
+
+    let opts = idx::CreateOptions::new().overwrite(true);
+    path.create_on_with(&mut json, opts).unwrap();
+    assert_eq!(json, json!({"a": [null, null, {"b": null}]}));
+
+    let path = IdxPath::from(vec![Idx::from(1_000_000_000)]);
+    let mut json = json!([]);
+    let err = path.create_on(&mut json).unwrap_err();
+    assert!(matches!(
+        err,
+        ResolveError::ExceedsLimit {
+            idx: 1_000_000_000,
+            limit: 4096,
+            ..
+        }
+    ));
+    assert_eq!(json, json!([]));
+}
+
+#[test]
+fn find_indices_of_matches() {
+    let json = json!({"a": [1, 5, 2, 7], "b": {"c": 6}});
+
+    let path = JsonPath::compile("$.a[?(@ > 3)]").unwrap();
+    assert_eq!(
+        path.find_indices(&json),
+        [(Idx::from(1), &json!(5)), (Idx::from(3), &json!(7))]
+    );
+
+    let path = JsonPath::compile("$.b.*").unwrap();
+    assert_eq!(path.find_indices(&json), [(Idx::from("c"), &json!(6))]);
+
+    let path = JsonPath::compile("$").unwrap();
+    assert!(path.find_indices(&json).is_empty());
+}
+
+#[test]
+fn idx_path_delete_and_replace() {
+    let mut json = json!({"a": [1, 2, 3]});
+
+    let path = IdxPath::from(vec![Idx::from("a"), Idx::from(1)]);
+    assert_eq!(path.replace_on(&mut json, json!(5)).unwrap(), json!(2));
+    assert_eq!(json, json!({"a": [1, 5, 3]}));
+    assert_eq!(path.delete_on(&mut json).unwrap(), json!(5));
+    assert_eq!(json, json!({"a": [1, 3]}));
+
+    let missing = IdxPath::from(vec![Idx::from("a"), Idx::from(2)]);
+    assert_eq!(missing.delete_on(&mut json).unwrap_err().depth(), 1);
+    assert!(missing.replace_on(&mut json, json!(null)).is_err());
+    assert_eq!(json, json!({"a": [1, 3]}));
+
+    let root = IdxPath::from(vec![]);
+    assert_eq!(
+        root.replace_on(&mut json, json!(1)).unwrap(),
+        json!({"a": [1, 3]})
+    );
+    assert_eq!(root.delete_on(&mut json).unwrap(), json!(1));
+    assert_eq!(json, Value::Null);
+}
+
+#[test]
+fn idx_path_parse() {
+    use crate::error::IdxPathParseErrorKind;
+
+    let path = IdxPath::parse("a.b[2]['c.d']").unwrap();
+    assert_eq!(
+        path,
+        IdxPath::from(vec![
+            Idx::from("a"),
+            Idx::from("b"),
+            Idx::from(2),
+            Idx::from("c.d"),
+        ])
+    );
+    assert_eq!(
+        IdxPath::parse("$.a[0]").unwrap(),
+        IdxPath::parse("a[0]").unwrap()
+    );
+    assert_eq!(IdxPath::parse("[0][1]").unwrap().len(), 2);
+    assert!(IdxPath::parse("$").unwrap().is_empty());
+    assert!(IdxPath::parse("").unwrap().is_empty());
+
+    let path = IdxPath::from(vec![
+        Idx::from("it's"),
+        Idx::from(10),
+        Idx::from("tab\there\u{1}"),
+        Idx::from("\\"),
+    ]);
+    assert_eq!(path.to_string().parse::<IdxPath>().unwrap(), path);
+    assert_eq!(
+        IdxPath::parse(r#"["\"q\"A"]"#).unwrap(),
+        IdxPath::from(vec![Idx::from("\"q\"A")])
+    );
+
+    let cases = [
+        ("a.*", IdxPathParseErrorKind::Unsupported("wildcards"), 2),
+        ("a[*]", IdxPathParseErrorKind::Unsupported("wildcards"), 2),
+        (
+            "a[?(@.b)]",
+            IdxPathParseErrorKind::Unsupported("filters"),
+            2,
+        ),
+        (
+            "a..b",
+            IdxPathParseErrorKind::Unsupported("recursive descents"),
+            2,
+        ),
+        ("a[1:2]", IdxPathParseErrorKind::Unsupported("slices"), 3),
+        ("a[1,2]", IdxPathParseErrorKind::Unsupported("unions"), 3),
+        (
+            "a[-1]",
+            IdxPathParseErrorKind::Unsupported("negative indices"),
+            2,
+        ),
+        ("a[1", IdxPathParseErrorKind::UnexpectedEnd, 3),
+        ("a.", IdxPathParseErrorKind::UnexpectedEnd, 2),
+        ("a['b", IdxPathParseErrorKind::UnexpectedEnd, 4),
+        ("a[b]", IdxPathParseErrorKind::UnexpectedChar('b'), 2),
+        ("a['\\q']", IdxPathParseErrorKind::InvalidEscape, 3),
+        (
+            "[99999999999999999999999]",
+            IdxPathParseErrorKind::InvalidIndex,
+            1,
+        ),
+    ];
+    for (input, kind, position) in cases {
+        let err = IdxPath::parse(input).unwrap_err();
+        assert_eq!(err.kind(), &kind, "{input}");
+        assert_eq!(err.position(), position, "{input}");
+    }
+}
+
+#[test]
+fn find_mut_matches() {
+    let mut json = json!({"a": [1, 2, 3], "b": {"c": 4}});
+
+    let path = JsonPath::compile("$.a[2, 0, 2]").unwrap();
+    let found = path.find_mut(&mut json);
+    assert_eq!(found, [&mut json!(3), &mut json!(1)]);
+    for val in found {
+        *val = json!(val.as_i64().unwrap() * 10);
+    }
+    assert_eq!(json, json!({"a": [10, 2, 30], "b": {"c": 4}}));
+
+    // Nested matches are returned through their outermost match
+    let path = JsonPath::compile("$..*").unwrap();
+    let found = path.find_mut(&mut json);
+    assert_eq!(found.len(), 2);
+}
+
+#[test]
+fn idx_path_grouping() {
+    let json = json!({"pods": [
+        {"containers": [{"image": "a"}, {"image": "b"}]},
+        {"containers": [{"image": "c"}]},
+    ]});
+
+    let paths = JsonPath::compile("$..containers[*].image")
+        .unwrap()
+        .find_paths(&json);
+    let groups = IdxPath::group_by_prefix(&paths, 2);
+    assert_eq!(groups.len(), 2);
+
+    let pod = IdxPath::parse("pods[0]").unwrap();
+    let images = groups[&pod]
+        .iter()
+        .map(|path| {
+            let rel = path.relative_to(&pod).unwrap();
+            rel.resolve_on(pod.resolve_on(&json).unwrap()).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(images, [&json!("a"), &json!("b")]);
+
+    assert_eq!(pod.relative_to(&paths[0]), None);
+    let groups = IdxPath::group_by_prefix(std::slice::from_ref(&pod), 5);
+    assert_eq!(groups[&pod], [pod]);
+}
+
+#[test]
+fn range_explicit_indices() {
+    use crate::ast::{BracketSelector, Segment};
+
+    // Expected indices for arrays of length 0, 1 and 5
+    let cases: [(&str, [&[usize]; 3]); 16] = [
+        ("1:3", [&[], &[], &[1, 2]]),
+        ("5:1", [&[], &[], &[]]),
+        ("::2", [&[], &[0], &[0, 2, 4]]),
+        ("1::-1", [&[], &[0], &[1, 0]]),
+        ("3:0:-1", [&[], &[], &[3, 2, 1]]),
+        ("3::-1", [&[], &[0], &[3, 2, 1, 0]]),
+        (":2:-1", [&[], &[], &[4, 3]]),
+        ("-1:0:-1", [&[], &[], &[4, 3, 2, 1]]),
+        ("::-2", [&[], &[0], &[4, 2, 0]]),
+        ("4:1:-2", [&[], &[], &[4, 2]]),
+        ("-2:-5:-1", [&[], &[], &[3, 2, 1]]),
+        ("10:-10:-3", [&[], &[0], &[4, 1]]),
+        ("-10::-1", [&[], &[], &[]]),
+        ("1:3:-1", [&[], &[], &[]]),
+        ("0:0:-1", [&[], &[], &[]]),
+        ("0::-1", [&[], &[0], &[0]]),
+    ];
+
+    for (slice, expected) in cases {
+        let path = JsonPath::compile(&format!("$[{slice}]")).unwrap();
+        for (len, expected) in [0, 1, 5].into_iter().zip(expected) {
+            let indices = match &path.segments()[0] {
+                Segment::Bracket(_, BracketSelector::StepRange(sr)) => sr.to_explicit_indices(len),
+                Segment::Bracket(_, BracketSelector::Range(r)) => r.to_explicit_indices(len),
+                _ => panic!("Expected a range selector for {slice}"),
+            };
+            assert_eq!(indices, expected, "{slice} with length {len}");
+
+            let json = Value::from((0..len).collect::<Vec<_>>());
+            let found = path
+                .find(&json)
+                .into_iter()
+                .map(|v| v.as_u64().unwrap() as usize)
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected, "{slice} with length {len}");
+        }
+    }
+}
+
+#[test]
+fn find_json_pointers() {
+    let json = json!({"a/b": [1, {"c~d": 2}], "e": 3});
+
+    let path = JsonPath::compile("$['a/b'][1]['c~d']").unwrap();
+    let pointers = path.find_pointers(&json);
+    assert_eq!(pointers, ["/a~1b/1/c~0d"]);
+    assert_eq!(json.pointer(&pointers[0]), Some(&json!(2)));
+
+    let path = JsonPath::compile("$..*").unwrap();
+    let pointers = path.find_pointers(&json);
+    let expected = path
+        .find_paths(&json)
+        .iter()
+        .map(IdxPath::to_pointer)
+        .collect::<Vec<_>>();
+    assert_eq!(pointers, expected);
+    for (pointer, val) in pointers.iter().zip(path.find(&json)) {
+        assert_eq!(json.pointer(pointer), Some(val));
+    }
+
+    let path = JsonPath::compile("$").unwrap();
+    assert_eq!(path.find_str_pointers("[1]").unwrap(), [""]);
+    assert!(path.find_str_pointers("[1").is_err());
+}
+
+#[test]
+fn segment_determinism() {
+    let cases = [
+        ("$.a", true, false),
+        ("$['a']", true, false),
+        ("$[0]", true, false),
+        ("$.^", true, false),
+        ("$.*", false, false),
+        ("$[*]", false, false),
+        ("$..a", false, false),
+        ("$[?(@.a)]", false, false),
+        ("$[1:3]", false, false),
+        ("$[3:1]", true, true),
+        ("$[-1:-3:2]", true, true),
+        ("$[9223372036854775807]", true, true),
+        ("$[1, 2]", false, false),
+        ("$[5:1, 4:2]", true, true),
+    ];
+
+    for (path, deterministic, empty) in cases {
+        let path_ast = JsonPath::compile(path).unwrap();
+        let segment = &path_ast.segments()[0];
+        assert_eq!(segment.is_deterministic(), deterministic, "{path}");
+        assert_eq!(segment.always_returns_empty(), empty, "{path}");
+    }
+
+    assert!(JsonPath::compile("$.a[0]['b']")
+        .unwrap()
+        .could_match_at_most_one());
+    assert!(!JsonPath::compile("$.a[*].b")
+        .unwrap()
+        .could_match_at_most_one());
+}
+
+#[test]
+fn find_normalized_paths_rfc_examples() {
+    // Examples from RFC 9535, section 2.7
+    let json = json!({
+        "a": {"b": [0, 1, 2]},
+        "\u{b}": 1,
+        "arr": [0, 1, 2, 3, 4],
+    });
+    let cases = [
+        ("$.a", "$['a']"),
+        ("$.arr[1]", "$['arr'][1]"),
+        ("$.arr[-3]", "$['arr'][2]"),
+        ("$.a.b[1:2]", "$['a']['b'][1]"),
+        ("$['\u{b}']", "$['\\u000b']"),
+    ];
+    for (path, expected) in cases {
+        let path = JsonPath::compile(path).unwrap();
+        assert_eq!(path.find_normalized_paths(&json), [expected]);
+    }
+
+    let json = json!({"it's": ["\\", "\n"], "a\tb": {"c": 1}});
+    let path = JsonPath::compile("$..*").unwrap();
+    let found = path.find(&json);
+    let paths = path.find_normalized_paths(&json);
+    assert_eq!(found.len(), paths.len());
+    for (val, path) in found.into_iter().zip(&paths) {
+        let idx_path = IdxPath::parse(path).unwrap();
+        assert_eq!(idx_path.resolve_on(&json).unwrap(), val);
+    }
+    assert!(paths.contains(&"$['it\\'s'][1]".to_string()));
+    assert!(paths.contains(&"$['a\\tb']['c']".to_string()));
+}
+
+#[test]
+fn sub_path_to_absolute() {
+    use crate::ast::{BracketSelector, FilterExpr, Segment};
+
+    let json = json!({"a": [{"b": {"c": 1}}, {"b": {"c": 2}}], "d": 3});
+    let path = JsonPath::compile("$.a[?(@.b.c > $.d)]").unwrap();
+    assert!(path.is_absolute());
+
+    let sub_paths = match &path.segments()[1] {
+        Segment::Bracket(_, BracketSelector::Filter(filter)) => match filter.expression() {
+            FilterExpr::Binary(lhs, _, rhs) => match (&**lhs, &**rhs) {
+                (FilterExpr::Path(lhs), FilterExpr::Path(rhs)) => (lhs, rhs),
+                _ => panic!("Expected two sub-paths"),
+            },
+            _ => panic!("Expected a binary expression"),
+        },
+        _ => panic!("Expected a filter"),
+    };
+
+    let base = JsonPath::compile("$.a[*]").unwrap();
+    let (relative, root) = sub_paths;
+    assert!(!relative.is_absolute());
+    assert!(root.is_absolute());
+
+    let relative = relative.to_absolute(&base);
+    assert_eq!(relative.find(&json), [&json!(1), &json!(2)]);
+    let root = root.to_absolute(&base);
+    assert_eq!(root.find(&json), [&json!(3)]);
+}
+
+#[test]
+fn find_in_custom_json() {
+    use crate::json::{JsonMut, JsonNumber, JsonRef};
+    use std::collections::HashMap;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Custom {
+        Null,
+        Bool(bool),
+        Int(i64),
+        Float(f64),
+        Str(String),
+        List(Vec<Custom>),
+        Map(HashMap<String, Custom>),
+    }
+
+    impl Json for Custom {
+        type Array = Vec<Custom>;
+        type Object = HashMap<String, Custom>;
+
+        fn as_ref(&self) -> JsonRef<'_, Self> {
+            match self {
+                Custom::Null => JsonRef::Null,
+                Custom::Bool(b) => JsonRef::Bool(*b),
+                Custom::Int(i) => JsonRef::Number(JsonNumber::Int(*i)),
+                Custom::Float(f) => JsonRef::Number(JsonNumber::Float(*f)),
+                Custom::Str(s) => JsonRef::String(s.into()),
+                Custom::List(l) => JsonRef::Array(l),
+                Custom::Map(m) => JsonRef::Object(m),
+            }
+        }
+
+        fn as_mut(&mut self) -> JsonMut<'_, Self> {
+            match self {
+                Custom::Null => JsonMut::Null,
+                Custom::Bool(b) => JsonMut::Bool(b),
+                Custom::Int(i) => JsonMut::Number(JsonNumber::Int(*i)),
+                Custom::Float(f) => JsonMut::Number(JsonNumber::Float(*f)),
+                Custom::Str(s) => JsonMut::String(s),
+                Custom::List(l) => JsonMut::Array(l),
+                Custom::Map(m) => JsonMut::Object(m),
+            }
+        }
+
+        fn null() -> Self {
+            Custom::Null
+        }
+
+        fn from_bool(val: bool) -> Self {
+            Custom::Bool(val)
+        }
+
+        fn from_i64(val: i64) -> Self {
+            Custom::Int(val)
+        }
+
+        fn from_f64(val: f64) -> Self {
+            Custom::Float(val)
+        }
+
+        fn from_string(val: String) -> Self {
+            Custom::Str(val)
+        }
+    }
+
+    let value = Custom::Map(HashMap::from([(
+        "items".to_string(),
+        Custom::List(vec![
+            Custom::Map(HashMap::from([
+                ("price".to_string(), Custom::Float(2.0)),
+                ("name".to_string(), Custom::Str("a".to_string())),
+            ])),
+            Custom::Map(HashMap::from([
+                ("price".to_string(), Custom::Int(5)),
+                ("name".to_string(), Custom::Str("b".to_string())),
+            ])),
+        ]),
+    )]));
+
+    let path = JsonPath::compile("$.items[?(@.price == 2)].name").unwrap();
+    assert_eq!(path.find_in(&value), vec![&Custom::Str("a".to_string())]);
+    assert_eq!(
+        path.find_paths_in(&value),
+        vec![IdxPath::new(vec![
+            Idx::Object("items".to_string()),
+            Idx::Array(0),
+            Idx::Object("name".to_string()),
+        ])]
+    );
+
+    let path = JsonPath::compile("$..price").unwrap();
+    assert_eq!(path.find_in(&value).len(), 2);
+}
+
+#[test]
+fn iter_with_paths_round_trip() {
+    let json = json!({"a": [1, {"b": null}], "c": "d"});
+
+    let nodes = iter_with_paths(&json).collect::<Vec<_>>();
+    assert_eq!(nodes.len(), 6);
+    assert_eq!(nodes[0], (IdxPath::new(vec![]), &json));
+
+    let mut rebuilt = Value::Null;
+    for (path, val) in iter_with_paths(&json) {
+        if val.is_array() || val.is_object() {
+            continue;
+        }
+        *path.create_on(&mut rebuilt).unwrap() = val.clone();
+    }
+    assert_eq!(rebuilt, json);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_crate_backend() {
+    use ::json::{array, object, JsonValue};
+
+    let value = object! {"list": ["red", "green", "blue"]};
+    let path = JsonPath::compile("$.list[*]").unwrap();
+    assert_eq!(
+        path.replace_in(&value, |_| JsonValue::from("black")),
+        object! {"list": ["black", "black", "black"]}
+    );
+    assert_eq!(path.delete_in(&value), object! {"list": []});
+
+    let value = object! {"a": {"b": 1, "c": [2, {"b": 3}]}};
+    let path = JsonPath::compile("$..b").unwrap();
+    assert_eq!(
+        path.find_in(&value),
+        vec![&JsonValue::from(1), &JsonValue::from(3)]
+    );
+    assert_eq!(
+        path.find_paths_in(&value),
+        vec![
+            IdxPath::new(vec![Idx::Object("a".into()), Idx::Object("b".into())]),
+            IdxPath::new(vec![
+                Idx::Object("a".into()),
+                Idx::Object("c".into()),
+                Idx::Array(1),
+                Idx::Object("b".into()),
+            ]),
+        ]
+    );
+
+    let value = array![{"price": 2.5, "name": "a"}, {"price": 10, "name": "b"}];
+    let path = JsonPath::compile("$[?(@.price > 5)].name").unwrap();
+    assert_eq!(path.find_in(&value), vec![&JsonValue::from("b")]);
+    let path = JsonPath::compile("$[?(@.price == 10)].name").unwrap();
+    assert_eq!(path.find_in(&value), vec![&JsonValue::from("b")]);
+
+    // Short strings are converted to owned strings when mutated
+    let path = JsonPath::compile("$[*].name").unwrap();
+    assert_eq!(
+        path.replace_in(&value, |v| JsonValue::from(format!("{}!", v))),
+        array![{"price": 2.5, "name": "a!"}, {"price": 10, "name": "b!"}]
+    );
+    let mut short = JsonValue::from("short");
+    match short.as_mut() {
+        json::JsonMut::String(s) => s.push_str(" string"),
+        _ => panic!("expected a string"),
+    }
+    assert_eq!(short, "short string");
+}
+
+#[test]
+fn selector_accessors() {
+    use crate::ast::{BracketLit, Segment};
+
+    let path = JsonPath::compile("$[1, 'a', ?(@.b)][1:2:3][$.c]").unwrap();
+    let selectors = path
+        .segments()
+        .iter()
+        .map(|seg| match seg {
+            Segment::Bracket(_, sel) => sel,
+            _ => panic!("expected bracket segment"),
+        })
+        .collect::<Vec<_>>();
+
+    let union = selectors[0].as_union().unwrap();
+    assert_eq!(union.len(), 3);
+    assert_eq!(union[0].as_literal().and_then(BracketLit::as_int), Some(1));
+    assert_eq!(
+        union[1].as_literal().and_then(BracketLit::as_str),
+        Some("a")
+    );
+    assert!(union[2].as_filter().is_some());
+    assert!(union[2].as_path().is_none());
+
+    assert!(selectors[1].as_step_range().is_some());
+    assert!(selectors[1].as_union().is_none());
+    assert!(selectors[2].as_path().is_some());
+    assert!(selectors[2].as_literal().is_none());
+}
+
+#[test]
+fn strict_filter_errors() {
+    let json = json!([{"a": 1}, {"a": "b"}, {"c": 2}]);
+    let path = JsonPath::compile("$[?(@.a > 0)]").unwrap();
+
+    assert_eq!(
+        path.try_find_with(&json, EvalOptions::new()).unwrap(),
+        vec![&json!({"a": 1})]
+    );
+
+    let err = path
+        .try_find_with(&json, EvalOptions::new().strict_filter_errors(true))
+        .unwrap_err();
+    assert!(err.is_type_mismatch());
+    assert!(!err.is_structural());
+
+    let json = json!([{"a": 1}, {"c": 2}]);
+    let err = path
+        .try_find_with(&json, EvalOptions::new().strict_filter_errors(true))
+        .unwrap_err();
+    assert!(err.is_structural());
+    assert!(!err.is_type_mismatch());
+
+    let path = JsonPath::compile("$[?(@.a > 0)].a").unwrap();
+    let json = json!([{"a": 1}, {"a": 2}]);
+    assert_eq!(
+        path.try_find_with(&json, EvalOptions::new().strict_filter_errors(true))
+            .unwrap(),
+        vec![&json!(1), &json!(2)]
+    );
+}
+
+#[test]
+fn top_level_tilde_unsupported() {
+    let json = json!({"a": {"b": 1}});
+    let path = JsonPath::compile("$.a~").unwrap();
+
+    let err = path.try_find_with(&json, EvalOptions::new()).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        crate::error::EvalErrorKind::Unsupported(_)
+    ));
+    assert!(path.find(&json).is_empty());
+}
+
+#[test]
+#[cfg(feature = "simd-json")]
+fn simd_json_backend() {
+    use simd_json::{json as simd, BorrowedValue, OwnedValue};
+
+    let value: OwnedValue = simd!({"list": ["red", "green", "blue"], "n": [1, 2.5, 10]});
+    let path = JsonPath::compile("$.list[*]").unwrap();
+    assert_eq!(
+        path.replace_in(&value, |_| OwnedValue::from("black")),
+        simd!({"list": ["black", "black", "black"], "n": [1, 2.5, 10]})
+    );
+    assert_eq!(
+        path.delete_in(&value),
+        simd!({"list": [], "n": [1, 2.5, 10]})
+    );
+
+    let path = JsonPath::compile("$.n[?(@ > 2)]").unwrap();
+    assert_eq!(
+        path.find_in(&value),
+        vec![&OwnedValue::from(2.5), &OwnedValue::from(10)]
+    );
+
+    let mut input = br#"{"a": {"b": 1, "c": [2, {"b": "x"}]}}"#.to_vec();
+    let value: BorrowedValue<'_> = simd_json::to_borrowed_value(&mut input).unwrap();
+    let path = JsonPath::compile("$..b").unwrap();
+    assert_eq!(
+        path.find_in(&value),
+        vec![&BorrowedValue::from(1), &BorrowedValue::from("x")]
+    );
+    assert_eq!(
+        path.find_paths_in(&value)[1],
+        IdxPath::new(vec![
+            Idx::Object("a".into()),
+            Idx::Object("c".into()),
+            Idx::Array(1),
+            Idx::Object("b".into()),
+        ])
+    );
+    let replaced = JsonPath::compile("$.a.c[1].b")
+        .unwrap()
+        .replace_in(&value, |_| BorrowedValue::from("y"));
+    assert_eq!(
+        JsonPath::compile("$.a.c[1].b").unwrap().find_in(&replaced),
+        vec![&BorrowedValue::from("y")]
+    );
+}
+
+#[test]
+fn compile_lazy() {
+    let json = json!({"a": [1, 2]});
+
+    let path = JsonPath::compile_lazy("$.a[*]");
+    assert!(!path.is_compiled());
+    assert_eq!(path.find(&json).unwrap(), vec![&json!(1), &json!(2)]);
+    assert!(path.is_compiled());
+    assert_eq!(path.delete(&json).unwrap(), json!({"a": []}));
+
+    let path = JsonPath::compile_lazy("$.a[");
+    assert!(!path.is_compiled());
+    assert!(path.find(&json).is_err());
+    assert!(path.is_compiled());
+    assert!(path.find_paths(&json).is_err());
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn yaml_backend() {
+    let manifest: serde_yaml::Value = serde_yaml::from_str(
+        r"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          image: web:1.2
+      containers:
+        - name: web
+          image: web:1.2
+          ports:
+            - containerPort: 80
+        - name: sidecar
+          image: !pinned proxy:3.0
+      1: non-string key
+",
+    )
+    .unwrap();
+
+    // Tagged values are matched along with their tag
+    let pinned: serde_yaml::Value = serde_yaml::from_str("!pinned proxy:3.0").unwrap();
+    let path = JsonPath::compile("$..containers[*].image").unwrap();
+    assert_eq!(
+        path.find_in(&manifest),
+        vec![&serde_yaml::Value::from("web:1.2"), &pinned]
+    );
+    assert_eq!(
+        path.find_paths_in(&manifest)[0].to_normalized_string(),
+        "$['spec']['template']['spec']['containers'][0]['image']"
+    );
+
+    let replaced = path.replace_in(&manifest, |_| serde_yaml::Value::from("web:2.0"));
+    assert_eq!(
+        path.find_in(&replaced),
+        vec![
+            &serde_yaml::Value::from("web:2.0"),
+            &serde_yaml::Value::from("web:2.0"),
+        ]
+    );
+
+    let path =
+        JsonPath::compile("$.spec.template.spec.containers[?(@.name == 'sidecar')]").unwrap();
+    let deleted = path.delete_in(&manifest);
+    assert_eq!(
+        JsonPath::compile("$..containers[*].name")
+            .unwrap()
+            .find_in(&deleted),
+        vec![&serde_yaml::Value::from("web")]
+    );
+
+    let path = JsonPath::compile("$.spec.template.spec.*").unwrap();
+    assert_eq!(path.find_in(&manifest).len(), 2);
+}
+
+#[test]
+fn nfa_find_all() {
+    use crate::nfa::PathNfa;
+
+    let json = json!({
+        "store": {
+            "book": [
+                {"title": "a", "price": 8},
+                {"title": "b", "price": 12},
+            ],
+            "bicycle": {"color": "red", "price": 20},
+        }
+    });
+
+    let paths = [
+        "$.store.book[*].title",
+        "$..price",
+        "$.store.book[?(@.price > 10)]",
+        "$..color.^",
+        "$",
+        "$.missing",
+        "$.store~",
+    ]
+    .map(|p| JsonPath::compile(p).unwrap());
+
+    let nfas = paths.iter().map(JsonPath::to_nfa).collect::<Vec<_>>();
+    assert_eq!(nfas[0].states().len(), 5);
+    assert_eq!(nfas[0].states()[4].accepts(), [0]);
+
+    let all = PathNfa::find_all(&nfas, &json);
+    assert_eq!(all.len(), paths.len());
+    assert!(all[6].is_empty());
+    for (path, found) in paths.iter().zip(all) {
+        assert_eq!(found, path.find(&json));
+    }
+
+    assert!(PathNfa::find_all::<Value>(&[], &json).is_empty());
+}
+
+#[test]
+fn nfa_shared_prefixes() {
+    use crate::nfa::PathNfa;
+
+    let json = json!({"store": {"book": [{"title": "a", "price": 8}], "bicycle": {"price": 20}}});
+
+    let paths = [
+        "$.store.book[*].title",
+        "$.store.book[*].price",
+        "$.store.bicycle",
+        "$.store.book",
+        "$.store.book",
+    ]
+    .map(|p| JsonPath::compile(p).unwrap());
+    let nfas = paths.iter().map(JsonPath::to_nfa).collect::<Vec<_>>();
+    let merged = PathNfa::merge(&nfas);
+
+    // Start, `.store`, `.book`, `[*]`, `.title`, `.price` and `.bicycle`, with each shared
+    // prefix evaluated through a single state
+    assert_eq!(merged.states().len(), 7);
+    assert_eq!(merged.path_count(), 5);
+    assert_eq!(merged.states()[0].transitions().len(), 1);
+    let (_, store) = merged.states()[0].transitions()[0];
+    assert_eq!(merged.states()[store].transitions().len(), 2);
+    let (_, book) = merged.states()[store].transitions()[0];
+    assert_eq!(merged.states()[book].accepts(), [3, 4]);
+
+    let found = merged.find(&json);
+    for (path, found) in paths.iter().zip(found) {
+        assert_eq!(found, path.find(&json));
+    }
+    assert_eq!(
+        PathNfa::merge(&[merged.clone(), nfas[2].clone()]).find(&json)[5],
+        [&json["store"]["bicycle"]]
+    );
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn toml_backend() {
+    let manifest: toml::Value = toml::from_str(
+        r#"
+[workspace]
+members = ["core", "cli"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+either = { version = "1.6" }
+local = { path = "../local" }
+
+[release]
+date = 2023-05-27T07:32:00Z
+"#,
+    )
+    .unwrap();
+
+    let path = JsonPath::compile("$.workspace.members[*]").unwrap();
+    assert_eq!(
+        path.find_in(&manifest),
+        vec![&toml::Value::from("core"), &toml::Value::from("cli")]
+    );
+
+    let path = JsonPath::compile("$.dependencies.*.version").unwrap();
+    assert_eq!(path.find_in(&manifest).len(), 2);
+    let bumped = path.replace_in(&manifest, |_| toml::Value::from("2.0"));
+    assert_eq!(
+        bumped["dependencies"]["serde"]["version"],
+        toml::Value::from("2.0")
+    );
+    assert_eq!(
+        bumped["dependencies"]["either"]["version"],
+        toml::Value::from("2.0")
+    );
+    assert!(bumped["dependencies"]["local"].get("version").is_none());
+
+    let path = JsonPath::compile("$.dependencies.local").unwrap();
+    let deleted = path.delete_in(&manifest);
+    assert!(deleted["dependencies"].get("local").is_none());
+
+    // Datetimes compare as strings
+    let path = JsonPath::compile("$.release[?(@ == '2023-05-27T07:32:00Z')]").unwrap();
+    assert_eq!(
+        path.find_paths_in(&manifest),
+        vec![IdxPath::new(vec![
+            Idx::Object("release".into()),
+            Idx::Object("date".into()),
+        ])]
+    );
+
+    assert_eq!(
+        JsonPath::compile("$").unwrap().delete_in(&manifest),
+        toml::Value::Table(toml::Table::new())
+    );
+}
+
+#[test]
+fn path_into_iter() {
+    use crate::ast::Segment;
+
+    let path = JsonPath::compile("$.a[0]..b").unwrap();
+    assert_eq!(path.as_segments_slice().len(), 3);
+
+    let mut count = 0;
+    for seg in &path {
+        count += 1;
+        assert!(!matches!(seg, Segment::Recursive(..)) || count == 3);
+    }
+    assert_eq!(count, 3);
+
+    let owned = path.into_iter().collect::<Vec<Segment>>();
+    assert!(matches!(owned[0], Segment::Dot(..)));
+    assert!(matches!(owned[1], Segment::Bracket(..)));
+    assert!(matches!(owned[2], Segment::Recursive(..)));
+}
+
+#[test]
+#[cfg(feature = "cbor")]
+fn cbor_backend() {
+    use ciborium::value::Value as Cbor;
+
+    let value = Cbor::Map(vec![
+        (
+            Cbor::Text("items".into()),
+            Cbor::Array(vec![
+                Cbor::Map(vec![
+                    (Cbor::Text("id".into()), Cbor::Integer(1.into())),
+                    (Cbor::Text("blob".into()), Cbor::Bytes(vec![0, 1, 2])),
+                ]),
+                Cbor::Map(vec![
+                    (Cbor::Text("id".into()), Cbor::Integer(u64::MAX.into())),
+                    (Cbor::Text("blob".into()), Cbor::Bytes(vec![3, 4])),
+                ]),
+            ]),
+        ),
+        (Cbor::Integer(7.into()), Cbor::Text("non-string key".into())),
+        (
+            Cbor::Text("tagged".into()),
+            Cbor::Tag(1, Box::new(Cbor::Integer(1_600_000_000.into()))),
+        ),
+    ]);
+
+    let path = JsonPath::compile("$.items[*].id").unwrap();
+    assert_eq!(
+        path.find_in(&value),
+        vec![&Cbor::Integer(1.into()), &Cbor::Integer(u64::MAX.into())]
+    );
+
+    // Byte strings have no JSON equivalent, but are reported as strings
+    let path = JsonPath::compile("$.items[0].blob").unwrap();
+    assert_eq!(path.find_in(&value)[0].ty(), crate::error::JsonTy::String);
+
+    // Only the targeted values change, leaving bytes, tags and non-string keys untouched
+    let path = JsonPath::compile("$.items[?(@.id == 1)].id").unwrap();
+    let replaced = path.replace_in(&value, |_| Cbor::Integer(2.into()));
+    let mut expected = value.clone();
+    if let Cbor::Map(m) = &mut expected {
+        if let Cbor::Array(items) = &mut m[0].1 {
+            if let Cbor::Map(item) = &mut items[0] {
+                item[0].1 = Cbor::Integer(2.into());
+            }
+        }
+    }
+    assert_eq!(replaced, expected);
+
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(&replaced, &mut bytes).unwrap();
+    let round_trip: Cbor = ciborium::de::from_reader(&*bytes).unwrap();
+    assert_eq!(round_trip, expected);
+
+    let path = JsonPath::compile("$.items[*].blob").unwrap();
+    assert_eq!(path.find_in(&value).len(), 2);
+    let deleted = path.delete_in(&value);
+    assert!(path.find_in(&deleted).is_empty());
+
+    assert_eq!(JsonPath::compile("$.*").unwrap().find_in(&value).len(), 2);
+    let path = JsonPath::compile("$.tagged").unwrap();
+    assert_eq!(
+        path.find_in(&value),
+        vec![&Cbor::Tag(1, Box::new(Cbor::Integer(1_600_000_000.into())))]
+    );
+}
+
+#[test]
+fn filter_expr_simplify() {
+    use crate::ast::{BinOp, BracketSelector, FilterExpr, Segment, UnOp};
+
+    fn filter_of(path: &str) -> FilterExpr {
+        let path = JsonPath::compile(path).unwrap();
+        match &path.segments()[0] {
+            Segment::Bracket(_, BracketSelector::Filter(f)) => f.expression().clone(),
+            _ => panic!("expected a filter"),
+        }
+    }
+
+    fn shape(expr: &FilterExpr) -> String {
+        match expr {
+            FilterExpr::Unary(UnOp::Neg(_), inner) => format!("-{}", shape(inner)),
+            FilterExpr::Unary(_, inner) => format!("!{}", shape(inner)),
+            FilterExpr::Binary(lhs, op, rhs) => {
+                let op = match op {
+                    BinOp::And(_) => "&&",
+                    BinOp::Or(_) => "||",
+                    BinOp::Gt(_) => ">",
+                    _ => "op",
+                };
+                format!("{} {} {}", shape(lhs), op, shape(rhs))
+            }
+            FilterExpr::Path(_) => "@".to_string(),
+            FilterExpr::Lit(lit) => match lit.as_bool() {
+                Some(b) => b.to_string(),
+                None => lit.as_int().map_or("lit".to_string(), |i| i.to_string()),
+            },
+            FilterExpr::Parens(_, inner) => format!("({})", shape(inner)),
+        }
+    }
+
+    let cases = [
+        ("$[?(!(!(@ > 3)))]", "(@ > 3)"),
+        ("$[?(@ > 3 && true)]", "@ > 3"),
+        ("$[?(true && @ > 3)]", "@ > 3"),
+        ("$[?(@ > 3 || false)]", "@ > 3"),
+        ("$[?(@ > 3 && false)]", "false"),
+        ("$[?(@ > 3 || (true))]", "true"),
+        ("$[?(!(!(!(!(@)))))]", "@"),
+        (
+            "$[?((@ > 3 && true) || (@ > 1 && !(!true)))]",
+            "(@ > 3) || (@ > 1)",
+        ),
+        ("$[?(@ > 3 && -(1))]", "@ > 3 && -1"),
+    ];
+
+    for (path, expected) in cases {
+        let simplified = filter_of(path).simplify();
+        assert_eq!(shape(&simplified), expected, "{}", path);
+        assert_eq!(shape(&simplified.simplify()), expected, "{}", path);
+    }
+
+    assert!(matches!(
+        filter_of("$[?(!(!(@ > 3)))]").simplify().unparen(),
+        FilterExpr::Binary(_, BinOp::Gt(_), _)
+    ));
+}
+
+/// Exercise the collection traits of a backend, given the value `{"a": 1, "b": [1, 2]}` and a new
+/// item to add
+fn check_collections<T: Json>(mut value: T, new: &T) {
+    use crate::json::{JsonArray, JsonMut, JsonObject};
+
+    let JsonMut::Object(obj) = value.as_mut() else {
+        panic!("expected an object");
+    };
+    obj.insert("c".to_string(), new.clone());
+    assert_eq!(obj.len(), 3);
+    assert!(obj.get("c").is_some_and(|c| c.json_eq(new)));
+
+    obj.insert("a".to_string(), new.clone());
+    assert_eq!(obj.len(), 3);
+    assert!(obj.get("a").is_some_and(|a| a.json_eq(new)));
+
+    let JsonMut::Array(arr) = obj.get_mut("b").unwrap().as_mut() else {
+        panic!("expected an array");
+    };
+    arr.push(new.clone());
+    arr.insert(0, new.clone());
+    arr.insert(2, new.clone());
+    assert_eq!(arr.len(), 5);
+    let expected = [true, false, true, false, true];
+    for (idx, is_new) in expected.into_iter().enumerate() {
+        assert_eq!(arr.get(idx).unwrap().json_eq(new), is_new);
+    }
+}
+
+#[test]
+fn collection_insert_push() {
+    check_collections(json!({"a": 1, "b": [1, 2]}), &json!("new"));
+
+    #[cfg(feature = "json")]
+    check_collections(
+        ::json::object! {"a": 1, "b": [1, 2]},
+        &::json::JsonValue::from("new"),
+    );
+    #[cfg(feature = "simd-json")]
+    check_collections::<simd_json::OwnedValue>(
+        simd_json::json!({"a": 1, "b": [1, 2]}),
+        &simd_json::OwnedValue::from("new"),
+    );
+    #[cfg(feature = "simd-json")]
+    check_collections::<simd_json::BorrowedValue<'_>>(
+        simd_json::json!({"a": 1, "b": [1, 2]}).into(),
+        &simd_json::BorrowedValue::from("new"),
+    );
+    #[cfg(feature = "yaml")]
+    check_collections(
+        serde_yaml::from_str::<serde_yaml::Value>("{a: 1, b: [1, 2]}").unwrap(),
+        &serde_yaml::Value::from("new"),
+    );
+    #[cfg(feature = "toml")]
+    check_collections(
+        toml::from_str::<toml::Value>("a = 1\nb = [1, 2]").unwrap(),
+        &toml::Value::from("new"),
+    );
+    #[cfg(feature = "cbor")]
+    check_collections(
+        ciborium::value::Value::serialized(&json!({"a": 1, "b": [1, 2]})).unwrap(),
+        &ciborium::value::Value::Text("new".into()),
+    );
+}
+
+#[test]
+fn parse_error_merge() {
+    let Err(short) = JsonPath::compile("$.") else {
+        panic!("expected a parse error");
+    };
+    let Err(long) = JsonPath::compile("$.a[?(@.b ==)]") else {
+        panic!("expected a parse error");
+    };
+    let (short_count, long_count) = (short.errors().len(), long.errors().len());
+
+    let merged = short.clone().merge(long.clone());
+    assert_eq!(merged.errors().len(), short_count + long_count);
+    assert!(merged.to_string().contains("$.a[?(@.b ==)]"));
+
+    let merged = ParseError::from_many(vec![long, short.clone(), short]);
+    assert_eq!(merged.errors().len(), long_count + 2 * short_count);
+    assert!(merged.to_string().contains("$.a[?(@.b ==)]"));
+
+    let empty = ParseError::from_many(Vec::new());
+    assert!(empty.errors().is_empty());
+    assert_eq!(empty.primary_span(), None);
+}
+
+/// Run the delete and replace regression cases against a backend, given a way to parse JSON text
+/// into it
+fn check_delete_replace<T: Json>(parse: impl Fn(&str) -> T) {
+    let deletes = [
+        (
+            "$.inner.list[1]",
+            r#"{"inner": {"list": ["one", "two", "three"]}, "outer": ["one", "two"]}"#,
+            r#"{"inner": {"list": ["one", "three"]}, "outer": ["one", "two"]}"#,
+        ),
+        (
+            "$.list[*]",
+            r#"{"list": ["one", "two", "three", "four"]}"#,
+            r#"{"list": []}"#,
+        ),
+        (
+            "$.a..",
+            r#"{"a": {"b": [1, 2, {"c": 3}]}, "d": 4}"#,
+            r#"{"d": 4}"#,
+        ),
+        (
+            "$.a.b[2, 2, 0]",
+            r#"{"a": {"b": [1, 2, {"c": 3}]}, "d": 4}"#,
+            r#"{"a": {"b": [2]}, "d": 4}"#,
+        ),
+        ("$", r#"{"a": 1}"#, "null"),
+    ];
+    for (path, input, expected) in deletes {
+        let path = JsonPath::compile(path).unwrap();
+        let value = parse(input);
+        assert!(path.delete_in(&value).json_eq(&parse(expected)));
+    }
+
+    let replaces = [
+        (
+            "$.list[*]",
+            r#"{"list": ["red", "green", "blue"]}"#,
+            r#"{"list": ["black", "black", "black"]}"#,
+        ),
+        (
+            "$.a..",
+            r#"{"a": {"b": [1, {"c": 2}]}}"#,
+            r#"{"a": "black"}"#,
+        ),
+        ("$", r#"{"a": 1}"#, r#""black""#),
+    ];
+    for (path, input, expected) in replaces {
+        let path = JsonPath::compile(path).unwrap();
+        let value = parse(input);
+        let result = path.replace_in(&value, |_| T::from_string("black".to_string()));
+        assert!(result.json_eq(&parse(expected)));
+    }
+}
+
+#[test]
+fn delete_replace_backends() {
+    check_delete_replace(|s| serde_json::from_str::<Value>(s).unwrap());
+
+    #[cfg(feature = "json")]
+    check_delete_replace(|s| ::json::parse(s).unwrap());
+    #[cfg(feature = "simd-json")]
+    check_delete_replace(|s| simd_json::to_owned_value(&mut s.as_bytes().to_vec()).unwrap());
+    #[cfg(feature = "yaml")]
+    check_delete_replace(|s| serde_yaml::from_str::<serde_yaml::Value>(s).unwrap());
+    #[cfg(feature = "cbor")]
+    check_delete_replace(|s| {
+        let value = serde_json::from_str::<Value>(s).unwrap();
+        ciborium::value::Value::serialized(&value).unwrap()
+    });
+}
+
+#[test]
+fn would_select_root() {
+    assert!(JsonPath::compile("$").unwrap().would_select_root());
+    assert!(!JsonPath::compile("$~").unwrap().would_select_root());
+    assert!(!JsonPath::compile("$.^").unwrap().would_select_root());
+    assert!(!JsonPath::compile("$.a").unwrap().would_select_root());
+}
+
+#[test]
+fn json_ref_accessors() {
+    use crate::json::{JsonArray, JsonMut, JsonNumber, JsonRef};
+
+    let mut value = json!({"a": [1, "two"], "b": true});
+    let r = Json::as_ref(&value);
+    let copy = r.clone();
+    assert!(copy.is_object() && !copy.is_array());
+    assert_eq!(copy.as_object().unwrap().len(), 2);
+    assert!(format!("{r:?}").starts_with("Object("));
+
+    let arr = Json::as_array(&value["a"]).unwrap();
+    assert_eq!(
+        arr.get(0).unwrap().as_ref().as_number(),
+        Some(JsonNumber::Int(1))
+    );
+    assert_eq!(arr.get(1).unwrap().as_ref().as_str(), Some("two"));
+    assert_eq!(Json::as_bool(&value["b"]), Some(true));
+    assert!(Json::is_null(&Value::Null));
+    assert!(matches!(Json::as_ref(&value["b"]), JsonRef::Bool(true)));
+
+    Json::as_array_mut(&mut value["a"]).unwrap().push(json!(3));
+    let JsonMut::Object(obj) = Json::as_mut(&mut value) else {
+        panic!("expected an object");
+    };
+    assert_eq!(obj.get("a").unwrap(), &json!([1, "two", 3]));
+    *Json::as_mut(obj.get_mut("b").unwrap()).into_bool().unwrap() = false;
+    assert_eq!(value["b"], json!(false));
+}
+
+#[test]
+fn eval_sub_path_and_filter() {
+    use crate::ast::Segment;
+
+    let json = json!({"key": "b", "items": [{"a": 1}, {"a": 5, "b": 2}]});
+    let bracket = |pattern| match JsonPath::compile(pattern).unwrap().segments() {
+        [Segment::Bracket(_, sel)] => sel.clone(),
+        _ => panic!("expected a single bracket segment"),
+    };
+
+    let sel = bracket("$[$.key]");
+    let sub = sel.as_path().unwrap();
+    assert_eq!(
+        sub.eval_against(&json, &json).unwrap().as_ref(),
+        &json!("b")
+    );
+
+    let sel = bracket("$[@.a~]");
+    let sub = sel.as_path().unwrap();
+    let item = &json["items"][0];
+    assert_eq!(sub.eval_against(&json, item).unwrap().as_ref(), &json!("a"));
+    let Err(err) = sub.eval_against(&json, &json["key"]) else {
+        panic!("expected an error");
+    };
+    assert!(err.is_structural());
+
+    let sel = bracket("$[?(@.a > 2)]");
+    let filter = sel.as_filter().unwrap();
+    assert!(!filter.test_value(&json, &json["items"][0]).unwrap());
+    assert!(filter.test_value(&json, &json["items"][1]).unwrap());
+    assert!(filter.test_value(&json, &json["key"]).is_err());
+}
+
+#[test]
+fn u64_precision() {
+    let json = json!([{"a": u64::MAX, "b": u64::MAX - 1}]);
+
+    // Both values round to the same float, so comparing them as floats would fail
+    assert_eq!(find("$[?(@.a > @.b)]", &json).unwrap(), [&json[0]]);
+    assert_eq!(find("$[?(@.a - @.b == 1)]", &json).unwrap(), [&json[0]]);
+    assert_eq!(find("$[?(@.b + 1 == @.a)]", &json).unwrap(), [&json[0]]);
+    assert_eq!(find("$[?(@.a % 10 == 5)]", &json).unwrap(), [&json[0]]);
+    assert!(find("$[?(@.a + 1 > 0)]", &json).unwrap().is_empty());
+    assert_eq!(find("$[?(@.a > -1)]", &json).unwrap(), [&json[0]]);
+
+    assert_eq!(<Value as Json>::from_u64(u64::MAX), json!(u64::MAX));
+    assert_eq!(<Value as Json>::from_idx(Idx::Array(3)), json!(3));
+}
+
+#[test]
+fn path_depth() {
+    let depth = |pattern| JsonPath::compile(pattern).unwrap().depth();
+
+    assert_eq!(depth("$"), Some(0));
+    assert_eq!(depth("$.a.b.c"), Some(3));
+    assert_eq!(depth("$.a[0]['b', 'c']"), Some(3));
+    assert_eq!(depth("$..b"), None);
+    assert_eq!(depth("$.a[?(@..b)]"), None);
+    assert_eq!(depth("$.a[?(@.b.c)]"), Some(4));
+    assert_eq!(depth("$.a[?(@.b == $.x.y.z.w.v)]"), Some(5));
+    assert_eq!(depth("$.a[@.b.c]"), Some(3));
+    assert_eq!(depth("$.a[1, ?(@.b.c.d)]"), Some(5));
+}
+
+/// Mutate every value of a backend's object through `iter_mut` and `values_mut`, given the value
+/// `{"a": 1, "b": [1, 2]}`, checking the keys are untouched
+fn check_object_iter_mut<T: Json>(mut value: T) {
+    use crate::json::{JsonMut, JsonObject};
+
+    let JsonMut::Object(obj) = value.as_mut() else {
+        panic!("expected an object");
+    };
+    for (key, val) in obj.iter_mut() {
+        *val = T::from_string(key.to_uppercase());
+    }
+    let mut entries = obj
+        .iter()
+        .map(|(key, val)| (key.to_string(), val.clone()))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+    assert_eq!(entries.len(), 2);
+    for (key, val) in &entries {
+        assert!(val.json_eq(&T::from_string(key.to_uppercase())));
+    }
+    assert_eq!(entries[0].0, "a");
+    assert_eq!(entries[1].0, "b");
+
+    obj.values_mut().for_each(|val| *val = T::null());
+    assert_eq!(obj.len(), 2);
+    assert!(obj.get("a").is_some_and(|a| a.json_eq(&T::null())));
+    assert!(obj.get("b").is_some_and(|b| b.json_eq(&T::null())));
+}
+
+#[test]
+fn object_iter_mut() {
+    check_object_iter_mut(json!({"a": 1, "b": [1, 2]}));
+
+    #[cfg(feature = "json")]
+    check_object_iter_mut(::json::object! {"a": 1, "b": [1, 2]});
+    #[cfg(feature = "simd-json")]
+    check_object_iter_mut::<simd_json::OwnedValue>(simd_json::json!({"a": 1, "b": [1, 2]}));
+    #[cfg(feature = "simd-json")]
+    check_object_iter_mut::<simd_json::BorrowedValue<'_>>(
+        simd_json::json!({"a": 1, "b": [1, 2]}).into(),
+    );
+    #[cfg(feature = "yaml")]
+    check_object_iter_mut(serde_yaml::from_str::<serde_yaml::Value>("{a: 1, b: [1, 2]}").unwrap());
+    #[cfg(feature = "toml")]
+    check_object_iter_mut(toml::from_str::<toml::Value>("a = 1\nb = [1, 2]").unwrap());
+    #[cfg(feature = "cbor")]
+    check_object_iter_mut(
+        ciborium::value::Value::serialized(&json!({"a": 1, "b": [1, 2]})).unwrap(),
+    );
+}
+
+#[test]
+fn find_with_context() {
+    let json = json!({"a": [1, 2, 3], "b": 4});
+    let path = JsonPath::compile("$..*").unwrap();
+
+    let mut total = 0;
+    let pointers = path.find_with_context(&json, &mut total, |val, path, total| {
+        *total += val.as_i64().unwrap_or(0);
+        path.to_pointer()
+    });
+    assert_eq!(total, 10);
+    assert_eq!(pointers.len(), 5);
+    assert!(pointers.contains(&"/a/2".to_string()));
+}
+
+/// Run a set of evaluation cases against a backend, given a way to parse JSON text into it. Each
+/// case is a path, an input, and the expected matches as a JSON array
+fn check_eval_cases<T: Json>(parse: impl Fn(&str) -> T) {
+    use crate::json::{JsonArray, JsonRef};
+
+    let input = r#"{
+        "store": {
+            "book": [
+                {"title": "A", "price": 8, "tags": ["x"]},
+                {"title": "B", "price": 12.5, "isbn": "1"},
+                {"title": "C", "price": 22, "isbn": "2"}
+            ],
+            "bike": {"color": "red", "price": 19}
+        },
+        "key": "bike"
+    }"#;
+    let cases = [
+        ("$.store.book[0].title", r#"["A"]"#),
+        ("$.store.book[-1].title", r#"["C"]"#),
+        ("$.store.book[1:].title", r#"["B", "C"]"#),
+        ("$.store.book[::2].title", r#"["A", "C"]"#),
+        ("$.store.book[0, 2].price", "[8, 22]"),
+        ("$.store.book[?(@.isbn == '2')].title", r#"["C"]"#),
+        ("$.store.book[?(@.price > 10)].title", r#"["B", "C"]"#),
+        ("$.store.book[?(@.price * 2 == 16)].title", r#"["A"]"#),
+        (
+            "$.store.book[?(@.title == 'B' || @.price == 8)].price",
+            "[8, 12.5]",
+        ),
+        ("$.store.book[?(!(@.price > 10))].title", r#"["A"]"#),
+        ("$.store[$.key].color", r#"["red"]"#),
+        ("$..color", r#"["red"]"#),
+        ("$..tags[0]", r#"["x"]"#),
+        ("$.store.bike.color.^.price", "[19]"),
+        ("$.store.book[0].*", r#"["A", 8, ["x"]]"#),
+        (
+            "$.store.book[?(@.price == $.store.bike.price - 11)].title",
+            r#"["A"]"#,
+        ),
+        ("$.missing", "[]"),
+    ];
+
+    let value = parse(input);
+    for (path, expected) in cases {
+        let found = JsonPath::compile(path).unwrap().find_in(&value);
+        let expected = parse(expected);
+        let JsonRef::Array(expected) = expected.as_ref() else {
+            panic!("expected an array");
+        };
+        assert_eq!(found.len(), expected.len(), "{path}");
+        for (found, expected) in found.into_iter().zip(expected.iter()) {
+            assert!(found.json_eq(expected), "{path}");
+        }
+    }
+}
+
+#[test]
+fn eval_cases_backends() {
+    check_eval_cases(|s| serde_json::from_str::<Value>(s).unwrap());
+
+    #[cfg(feature = "json")]
+    check_eval_cases(|s| ::json::parse(s).unwrap());
+    #[cfg(feature = "simd-json")]
+    check_eval_cases(|s| simd_json::to_owned_value(&mut s.as_bytes().to_vec()).unwrap());
+    #[cfg(feature = "yaml")]
+    check_eval_cases(|s| serde_yaml::from_str::<serde_yaml::Value>(s).unwrap());
+    #[cfg(feature = "cbor")]
+    check_eval_cases(|s| {
+        let value = serde_json::from_str::<Value>(s).unwrap();
+        ciborium::value::Value::serialized(&value).unwrap()
+    });
+}
+
+#[test]
+fn index_by_path() {
+    let json = json!({"a": [{"b": 1}, {"b": 2}], "c": null});
+
+    assert_eq!(json[&JsonPath::compile("$.a[*].b").unwrap()], json!(1));
+    assert_eq!(json[&JsonPath::compile("$.missing").unwrap()], Value::Null);
+
+    let null = JsonPath::compile("$.c").unwrap();
+    assert_eq!(null.find_first(&json), Some(&Value::Null));
+    assert_eq!(JsonPath::compile("$.d").unwrap().find_first(&json), None);
+}
+
+#[test]
+fn find_first_matches_find() {
+    let json = json!({
+        "a": [{"b": 1, "c": [3, 4]}, {"b": 2, "c": [5]}],
+        "d": {"e": [6, 7], "b": 8},
+    });
+
+    let patterns = [
+        "$.a[*].b",
+        "$..b",
+        "$.a[1, 0].c[*]",
+        "$.a[*]['c', 'b']",
+        "$.a[0, 1][?(@.b > 1)].c[0]",
+        "$..c[*].^.^.b",
+        "$.a[-1:0:-1].b",
+        "$.d[*][1]",
+        "$.missing[*]",
+        "$",
+    ];
+    for pattern in patterns {
+        let path = JsonPath::compile(pattern).unwrap();
+        assert_eq!(
+            path.find_first(&json),
+            path.find(&json).first().copied(),
+            "{pattern}"
+        );
+        assert_eq!(path.get(&json), path.find_first(&json), "{pattern}");
+    }
+}
+
+#[test]
+fn find_first_stops_early() {
+    let json = json!([[1], ["x"]]);
+    let path = JsonPath::compile("$[*][?(@ > 0)]").unwrap();
+    let strict = EvalOptions::new().strict_filter_errors(true);
+
+    // Finding every match reaches the string, which fails the filter
+    assert!(path.try_find_with(&json, strict).is_err());
+
+    // Stopping at the first match never evaluates the filter on the second array
+    let mut ctx = EvalCtx::new(&json);
+    ctx.set_options(strict);
+    assert_eq!(path.eval_first(&mut ctx), Some(&json[0][0]));
+    assert!(ctx.take_error().is_none());
+}
+
+#[test]
+fn eval_ctx_paths_and_values() {
+    let json = json!({"a": [1, 2]});
+    let mut ctx = EvalCtx::new(&json);
+    ctx.prepopulate_parents();
+    JsonPath::compile("$.a[1]").unwrap().eval(&mut ctx);
+    let found = ctx.into_paths_and_values();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0.to_pointer(), "/a/1");
+    assert_eq!(found[0].1, &json!(2));
+}
+
+#[test]
+#[should_panic = "parents must be prepopulated"]
+fn eval_ctx_paths_without_parents() {
+    let json = json!({"a": 1});
+    let _ = EvalCtx::new(&json).into_paths_and_values();
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn stream_extract() {
+    use crate::stream::PathExtractor;
+    use serde::de::DeserializeSeed;
+
+    let input = r#"{
+        "a": [{"b": 1, "c": {"b": 2}}, {"b": 3}, 4, {"d": [5, 6, 7]}],
+        "b": {"x": null}
+    }"#;
+    let json = serde_json::from_str::<Value>(input).unwrap();
+
+    // Paths whose matches come back in document order from `find`
+    let paths = [
+        "$.a[0].b",
+        "$.a[*].b",
+        "$['a'][1:3]",
+        "$.a[::2]",
+        "$.a[3].d[0, 2]",
+        "$.a..",
+        "$.*",
+        "$",
+        "$.missing",
+    ];
+    for pattern in paths {
+        let path = JsonPath::compile(pattern).unwrap();
+        let extractor = PathExtractor::new(&path).unwrap();
+        let mut de = serde_json::Deserializer::from_str(input);
+        let found = extractor.deserialize(&mut de).unwrap();
+        let expected = path.find(&json).into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(found, expected, "{pattern}");
+    }
+
+    let unsupported = [
+        "$.a[?(@.b == 1)]",
+        "$.a[$.b]",
+        "$.a.^",
+        "$.a[-1]",
+        "$.a[::-1]",
+    ];
+    for pattern in unsupported {
+        let path = JsonPath::compile(pattern).unwrap();
+        let Err(err) = PathExtractor::new(&path) else {
+            panic!("expected {pattern} to be unsupported");
+        };
+        assert!(matches!(
+            err.kind(),
+            crate::error::EvalErrorKind::Unsupported(_)
+        ));
+    }
+}
+
+#[test]
+#[cfg(feature = "json5")]
+fn json5_comments() {
+    let json = json!({"users": [{"name": "a // b"}, {"name": "c"}]});
+
+    let path = JsonPath::compile(
+        "$ // select all users
+          .users[*] /* get each user */
+          .name // get the name",
+    )
+    .unwrap();
+    assert_eq!(path.find(&json), [&json!("a // b"), &json!("c")]);
+
+    let path = JsonPath::compile("$.users[?(@.name == 'a // b')] /* ünïcödé */").unwrap();
+    assert_eq!(path.find(&json), [&json["users"][0]]);
+    assert_eq!(
+        JsonPath::strip_comments("$['/* x */'] /* y */"),
+        "$['/* x */']"
+    );
+    assert_eq!(JsonPath::strip_comments("$[\"\\\"//\"]"), "$[\"\\\"//\"]");
+
+    assert!(JsonPath::compile("$.a /* unterminated").is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn find_typed() {
+    use crate::error::FindTypedError;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    let json = json!({"users": [
+        {"name": "a", "age": 1},
+        {"name": "b", "age": 2},
+    ]});
+
+    let users = JsonPath::compile("$.users[*]")
+        .unwrap()
+        .find_typed::<User>(&json)
+        .unwrap();
+    assert_eq!(
+        users,
+        [
+            User {
+                name: "a".to_string(),
+                age: 1
+            },
+            User {
+                name: "b".to_string(),
+                age: 2
+            },
+        ]
+    );
+    assert!(JsonPath::compile("$.users[*].name")
+        .unwrap()
+        .find_typed::<u32>(&json)
+        .is_err());
+
+    let age = JsonPath::compile("$.users[1].age")
+        .unwrap()
+        .find_one_typed::<u32>(&json)
+        .unwrap();
+    assert_eq!(age, 2);
+
+    assert!(matches!(
+        JsonPath::compile("$.missing")
+            .unwrap()
+            .find_one_typed::<u32>(&json),
+        Err(FindTypedError::NoMatch)
+    ));
+    assert!(matches!(
+        JsonPath::compile("$.users[*].age")
+            .unwrap()
+            .find_one_typed::<u32>(&json),
+        Err(FindTypedError::ManyMatches(2))
+    ));
+    assert!(matches!(
+        JsonPath::compile("$.users[0].name")
+            .unwrap()
+            .find_one_typed::<u32>(&json),
+        Err(FindTypedError::Deserialize(_))
+    ));
+}
+
+#[test]
+fn ast_display_round_trip() {
+    let paths = [
+        "$",
+        "$.a.*.^",
+        "$..b",
+        "$..",
+        "$['a', \"b\", 0, -1]",
+        "$[1:5:2][:3][1:][::-1]",
+        "$[*][^]~",
+        "$[$.a.b, @.c~]",
+        "$[?(@.a == 'x' && (@.b + 2) * 3 < -@.c)]",
+        "$[?(!(@.a || false) && @.b * 2 % 3 >= null)]",
+    ];
+
+    for pattern in paths {
+        let path = JsonPath::compile(pattern).unwrap();
+        assert_eq!(path.to_string(), pattern);
+    }
+
+    let path = JsonPath::compile("$[0,'a']").unwrap();
+    let ast::Segment::Bracket(_, selector) = &path.segments()[0] else {
+        panic!("First segment wasn't a bracket")
+    };
+    assert_eq!(selector.to_string(), "[0, 'a']");
+    let component = &selector.as_union().unwrap()[1];
+    assert_eq!(component.to_string(), "'a'");
+}
+
+#[test]
+fn path_segment_indexing() {
+    let path = JsonPath::compile("$.a[0]..b").unwrap();
+
+    let segments: &[ast::Segment] = path.as_ref();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(path[0].to_string(), ".a");
+    assert_eq!(path[2].to_string(), "..b");
+    assert_eq!(path[0..2].len(), 2);
+    assert_eq!(path[1..].len(), 2);
+}
+
+#[test]
+fn parse_error_caret() {
+    let Err(err) = JsonPath::compile("$.a[1") else {
+        panic!("Path should fail to parse")
+    };
+    let rendered = err.to_string_with_caret();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Error Parsing JSON Path:");
+    assert_eq!(lines[1], "$.a[1");
+    assert_eq!(lines.len(), 2 + 2 * err.errors().len());
+    for (idx, fail) in err.errors().iter().enumerate() {
+        let span = fail.span().as_range();
+        let caret = lines[2 + idx * 2];
+        assert_eq!(caret.trim_start().len(), span.len().max(1));
+        assert_eq!(caret.len() - caret.trim_start().len(), span.start);
+        assert!(caret.trim_start().chars().all(|c| c == '^'));
+        assert_eq!(lines[3 + idx * 2], fail.to_string());
+    }
+}
+
+#[test]
+fn replace_with_path() {
+    let json = json!({"a": [{"b": 1}, {"b": 2}], "c": {"b": 3}});
+    let path = JsonPath::compile("$..b").unwrap();
+
+    let expected = json!({
+        "a": [{"b": "$['a'][0]['b']=1"}, {"b": "$['a'][1]['b']=2"}],
+        "c": {"b": "$['c']['b']=3"},
+    });
+    let label = |val: &Value, path: &IdxPath| Value::from(format!("{path}={val}"));
+    assert_eq!(path.replace_with_path(&json, label), expected);
+
+    let mut in_place = json.clone();
+    path.replace_on_with_path(&mut in_place, label);
+    assert_eq!(in_place, expected);
+
+    let root = JsonPath::compile("$").unwrap();
+    assert_eq!(
+        root.replace_with_path(&json, label),
+        json!(format!("$={json}"))
+    );
+}
+
+#[test]
+fn filter_introspection() {
+    let cases = [
+        ("$", false, false),
+        ("$.a[0]..b", false, false),
+        ("$[?(@.a == 1)]", true, true),
+        ("$..[?(@.a == 1)][?(@.b == 2), ?(@.c == 3)]", true, false),
+        ("$[?(@.a == 1)][?(@.b == 2), ?(@.c == 3)]", true, true),
+        ("$[?(@.a == 1), 'b']", true, false),
+        ("$.a[?(@.a == 1)]", true, false),
+        ("$[$.a[?(@ == 1)]]", true, false),
+        ("$[0, @.a[?(@ == 1)]]", true, false),
+    ];
+
+    for (pattern, has_filter, is_filter_only) in cases {
+        let path = JsonPath::compile(pattern).unwrap();
+        assert_eq!(path.has_filter(), has_filter, "{pattern}");
+        assert_eq!(path.is_filter_only(), is_filter_only, "{pattern}");
+    }
+}
+
+#[test]
+fn negative_slice_bounds() {
+    use crate::ast::{BracketSelector, Segment};
+
+    // Expected indices are those Python selects with `list(range(len))[slice]`, for arrays of
+    // length 0, 2 and 5
+    let cases: [(&str, [&[usize]; 3]); 20] = [
+        ("-3:-1", [&[], &[0], &[2, 3]]),
+        ("-1:-3", [&[], &[], &[]]),
+        ("-3:", [&[], &[0, 1], &[2, 3, 4]]),
+        ("-10:-1", [&[], &[0], &[0, 1, 2, 3]]),
+        ("-10:-8", [&[], &[], &[]]),
+        (":-1", [&[], &[0], &[0, 1, 2, 3]]),
+        (":-10", [&[], &[], &[]]),
+        ("-1:", [&[], &[1], &[4]]),
+        ("1:-1", [&[], &[], &[1, 2, 3]]),
+        ("10:-1", [&[], &[], &[]]),
+        ("-2:10", [&[], &[0, 1], &[3, 4]]),
+        ("0:0", [&[], &[], &[]]),
+        ("3:3", [&[], &[], &[]]),
+        ("-3:-1:1", [&[], &[0], &[2, 3]]),
+        ("-4:-1:2", [&[], &[0], &[1, 3]]),
+        ("-10::2", [&[], &[0], &[0, 2, 4]]),
+        (":-2:2", [&[], &[], &[0, 2]]),
+        ("-1:-3:1", [&[], &[], &[]]),
+        ("1:-1:3", [&[], &[], &[1]]),
+        ("-6:6:1", [&[], &[0, 1], &[0, 1, 2, 3, 4]]),
+    ];
+
+    for (slice, expected) in cases {
+        let path = JsonPath::compile(&format!("$[{slice}]")).unwrap();
+        for (len, expected) in [0, 2, 5].into_iter().zip(expected) {
+            let indices = match &path.segments()[0] {
+                Segment::Bracket(_, BracketSelector::StepRange(sr)) => sr.to_explicit_indices(len),
+                Segment::Bracket(_, BracketSelector::Range(r)) => r.to_explicit_indices(len),
+                _ => panic!("Expected a range selector for {slice}"),
+            };
+            assert_eq!(indices, expected, "{slice} with length {len}");
+
+            let json = Value::from((0..len).collect::<Vec<_>>());
+            let found = path
+                .find(&json)
+                .into_iter()
+                .map(|v| v.as_u64().unwrap() as usize)
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected, "{slice} with length {len}");
+        }
+    }
+}
+
+#[test]
+fn delete_counts() {
+    let json = json!({"a": [{"b": 1}, {"b": 2, "c": {"b": 3}}], "d": 4});
+
+    let (out, count) = JsonPath::compile("$..b").unwrap().delete_count(&json);
+    assert_eq!(out, json!({"a": [{}, {"c": {}}], "d": 4}));
+    assert_eq!(count, 3);
+
+    // Nested matches are removed along with their parent, so only the parent is counted
+    let mut in_place = json.clone();
+    let path = JsonPath::compile("$.a..").unwrap();
+    assert_eq!(path.delete_on_count(&mut in_place), 1);
+    assert_eq!(in_place, json!({"d": 4}));
+
+    let mut unchanged = json.clone();
+    let missing = JsonPath::compile("$.missing").unwrap();
+    assert_eq!(missing.delete_on_count(&mut unchanged), 0);
+    assert_eq!(unchanged, json);
+
+    let root = JsonPath::compile("$").unwrap();
+    assert_eq!(root.delete_count(&json), (Value::Null, 1));
+}
+
+#[test]
+fn replace_counts() {
+    let json = json!({"a": [1, 2, 3, 4], "b": {"c": 5}});
+
+    let mut doubled = json.clone();
+    let path = JsonPath::compile("$.a[*]").unwrap();
+    let count = path.replace_on_count(&mut doubled, |v| json!(v.as_i64().unwrap() * 2));
+    assert_eq!(count, 4);
+    assert_eq!(doubled, json!({"a": [2, 4, 6, 8], "b": {"c": 5}}));
+
+    let mut unchanged = json.clone();
+    let missing = JsonPath::compile("$.missing").unwrap();
+    assert_eq!(missing.replace_on_count(&mut unchanged, |v| v.clone()), 0);
+    assert_eq!(unchanged, json);
+
+    let mut mixed = json.clone();
+    let counts = path.try_replace_on_count(&mut mixed, |v| {
+        let i = v.as_i64().unwrap();
+        (i % 2 == 0).then(|| json!(i * 10))
+    });
+    assert_eq!(counts, (2, 2));
+    assert_eq!(mixed, json!({"a": [20, 40], "b": {"c": 5}}));
+}
+
+#[test]
+fn ast_debug() {
+    let path =
+        JsonPath::compile("$.foo..bar[0, 'baz'][1:2:3][?(@.a == -1 && !true)][$.b]~").unwrap();
+    let debug = format!("{path:?}");
+
+    for part in [
+        "Path",
+        "Dollar",
+        "Ident",
+        "\"foo\"",
+        "DotDot",
+        "Union",
+        "\"baz\"",
+        "StepRange",
+        "Filter",
+        "Binary",
+        "EqEq",
+        "Not",
+        "Relative",
+        "Root",
+        "Tilde",
+    ] {
+        assert!(debug.contains(part), "{part} missing from {debug}");
+    }
+
+    #[cfg(feature = "spanned")]
+    assert!(debug.contains("Dollar(Span(0..1))"), "{debug}");
+    #[cfg(not(feature = "spanned"))]
+    assert!(debug.contains("Dollar,"), "{debug}");
+}
+
+#[test]
+fn path_exists_and_count() {
+    let json = json!({"a": [1, 2, 3], "b": null});
+
+    assert!(path_exists("$.a[0]", &json).unwrap());
+    assert!(path_exists("$.b", &json).unwrap());
+    assert!(!path_exists("$.c", &json).unwrap());
+    assert!(path_exists("$.a[", &json).is_err());
+
+    assert_eq!(count_matches("$.a[*]", &json).unwrap(), 3);
+    assert_eq!(count_matches("$.c", &json).unwrap(), 0);
+    assert!(count_matches("$.a[", &json).is_err());
+}
+
+#[test]
+fn ast_equality() {
+    let compact = JsonPath::compile("$.a[1,'b'][?(@.c==1)]").unwrap();
+    let spaced = JsonPath::compile("$.a[ 1 , 'b' ][?(@.c == 1)]").unwrap();
+    assert_eq!(compact, spaced);
+    assert_ne!(compact, JsonPath::compile("$.a[2,'b'][?(@.c==1)]").unwrap());
+    assert_ne!(
+        compact,
+        JsonPath::compile("$.a[1,\"b\"][?(@.c==1)]").unwrap()
+    );
+    assert_ne!(
+        compact,
+        JsonPath::compile("$.a[1,'b'][?(@.c==1)]~").unwrap()
+    );
+
+    let replacement = JsonPath::compile("$.x").unwrap()[0].clone();
+    let swapped = compact.map_segments(|seg| {
+        if *seg == compact[0] {
+            Some(replacement.clone())
+        } else {
+            Some(seg.clone())
+        }
+    });
+    assert_eq!(swapped, JsonPath::compile("$.x[1,'b'][?(@.c==1)]").unwrap());
+    assert_eq!(swapped.clone(), swapped);
+}
+
+#[test]
+fn ast_visitor() {
+    use crate::ast::visit::{walk_filter_expr, walk_path, Visit};
+    use crate::ast::{BinOp, FilterExpr, IntLit, StringLit};
+
+    #[derive(Default)]
+    struct Collect {
+        ints: Vec<i64>,
+        strings: Vec<String>,
+        ops: usize,
+        exprs: usize,
+    }
+
+    impl<'ast> Visit<'ast> for Collect {
+        fn visit_int_lit(&mut self, node: &'ast IntLit) {
+            self.ints.push(node.as_int());
+        }
+
+        fn visit_string_lit(&mut self, node: &'ast StringLit) {
+            self.strings.push(node.as_str().to_string());
+        }
+
+        fn visit_bin_op(&mut self, _node: &'ast BinOp) {
+            self.ops += 1;
+        }
+
+        fn visit_filter_expr(&mut self, node: &'ast FilterExpr) {
+            self.exprs += 1;
+            walk_filter_expr(node, self);
+        }
+    }
+
+    let path = JsonPath::compile("$[0, 'a'][1:2:3][$.b['c']][?(@.d == 4 && (@[5] < 6))]").unwrap();
+    let mut collect = Collect::default();
+    walk_path(&path, &mut collect);
+
+    assert_eq!(collect.ints, [0, 1, 2, 4, 5, 6]);
+    assert_eq!(collect.strings, ["a", "c"]);
+    assert_eq!(collect.ops, 3);
+    assert_eq!(collect.exprs, 8);
+}
+
+#[test]
+fn parent_in_union() {
+    let json = json!({"a": {"b": 1}});
+    let path = JsonPath::compile("$.a.b[^, 0]").unwrap();
+    assert_eq!(path.find(&json), [&json["a"]]);
+}
+
+#[test]
+fn for_each_match() {
+    let json = json!({"a": [1, 2, 3], "b": {"a": 4}});
+    let path = JsonPath::compile("$..a").unwrap();
+
+    let mut seen = Vec::new();
+    path.for_each(&json, |v| seen.push(v));
+    assert_eq!(seen, path.find(&json));
+
+    let mut with_paths = Vec::new();
+    path.for_each_with_path(&json, |v, p| with_paths.push((p.to_string(), v)));
+    assert_eq!(
+        with_paths,
+        [
+            ("$['a']".to_string(), &json["a"]),
+            ("$['b']['a']".to_string(), &json["b"]["a"]),
+        ]
+    );
+
+    for pattern in [
+        "$.a[2, 0]",
+        "$..a[*].^",
+        "$[*][?(@ > 1)]",
+        "$.a[:2][?(@ > 1)]",
+    ] {
+        let path = JsonPath::compile(pattern).unwrap();
+        let mut seen = Vec::new();
+        path.for_each_with_path(&json, |v, p| seen.push((p, v)));
+        assert_eq!(seen, path.find_paths_and_values(&json), "{pattern}");
+    }
+}
+
+#[test]
+fn for_each_before_evaluation_finishes() {
+    use core::ops::ControlFlow;
+
+    let json = json!([[1], ["x"]]);
+    let path = JsonPath::compile("$[*][?(@ > 0)]").unwrap();
+    let mut ctx = EvalCtx::new(&json);
+    ctx.set_options(EvalOptions::new().strict_filter_errors(true));
+
+    // The first match is passed on before the filter fails on the string in the second array
+    let mut calls = 0;
+    let _ = path.eval_each(&mut ctx, |ctx, val| {
+        assert_eq!(val, &json!(1));
+        assert!(ctx.take_error().is_none());
+        calls += 1;
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(calls, 1);
+    assert!(ctx.take_error().is_some());
+}
+
+#[test]
+fn schema_validation() {
+    use crate::schema::{PathValidator, ValidationWarningKind};
+
+    let validator = PathValidator::new(json!({
+        "type": "object",
+        "properties": {
+            "users": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/user" }
+            },
+            "count": { "type": "integer" }
+        },
+        "additionalProperties": false,
+        "$defs": {
+            "user": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                }
+            }
+        }
+    }));
+
+    let kinds = |path: &str| {
+        validator
+            .validate(&JsonPath::compile(path).unwrap())
+            .iter()
+            .map(|w| (w.segment(), w.kind()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(kinds("$.users[*].name"), []);
+    assert_eq!(kinds("$.users[?(@.name)]['tags'][0]"), []);
+    assert_eq!(
+        kinds("$.users[0].email"),
+        [(2, ValidationWarningKind::UnknownProperty)]
+    );
+    assert_eq!(
+        kinds("$['missing']"),
+        [(0, ValidationWarningKind::UnknownProperty)]
+    );
+    assert_eq!(
+        kinds("$.count.a"),
+        [(1, ValidationWarningKind::NotAnObject)]
+    );
+    assert_eq!(
+        kinds("$.count[0]"),
+        [(1, ValidationWarningKind::NotAnArray)]
+    );
+    assert_eq!(kinds("$..name"), []);
+    assert_eq!(
+        kinds("$..email"),
+        [(0, ValidationWarningKind::UnknownProperty)]
+    );
+    assert_eq!(kinds("$.users[0].^.anything"), []);
+}
+
+#[test]
+fn ast_visitor_mut() {
+    use crate::ast::visit_mut::{walk_bracket_lit_mut, walk_path_mut, VisitMut};
+    use crate::ast::{BracketLit, Ident, IntLit, Segment, StringLit};
+
+    struct Rewrite;
+
+    impl VisitMut for Rewrite {
+        fn visit_ident_mut(&mut self, node: &mut Ident) {
+            *node = Ident::new(node.as_str().to_uppercase());
+        }
+
+        fn visit_bracket_lit_mut(&mut self, node: &mut BracketLit) {
+            if node.as_str() == Some("b") {
+                *node = BracketLit::String(StringLit::new("it's"));
+            }
+            walk_bracket_lit_mut(node, self);
+        }
+
+        fn visit_int_lit_mut(&mut self, node: &mut IntLit) {
+            *node = IntLit::new(node.as_int() + 1);
+        }
+    }
+
+    let mut path = JsonPath::compile("$.a['b'][0:2][?(@.c == 1)]").unwrap();
+    walk_path_mut(&mut path, &mut Rewrite);
+    assert_eq!(path.to_string(), "$.A['it\\'s'][1:3][?(@.C == 2)]");
+
+    path.segments_mut().truncate(1);
+    path.segments_mut().push(Segment::key("x y"));
+    path.segments_mut().push(Segment::index(-1));
+    assert_eq!(path, JsonPath::compile("$.A['x y'][-1]").unwrap());
+    assert_eq!(
+        JsonPath::new(vec![Segment::name("a"), Segment::index(0)]),
+        JsonPath::compile("$.a[0]").unwrap()
+    );
+
+    let json = json!({"A": {"x y": [1, 2, 3]}});
+    assert_eq!(path.find(&json), [&json!(3)]);
+}
+
+#[test]
+fn error_source_chain() {
+    use crate::error::Error;
+    use std::error::Error as _;
+
+    fn chain(err: &dyn std::error::Error) -> Vec<String> {
+        let mut out = Vec::new();
+        let mut cur = err.source();
+        while let Some(err) = cur {
+            out.push(err.to_string());
+            cur = err.source();
+        }
+        out
+    }
+
+    let parse = JsonPath::compile("$.a[").unwrap_err();
+    assert!(parse.source().is_none());
+    let err = Error::from(parse.clone());
+    assert!(matches!(err, Error::Parse(_)));
+    assert_eq!(chain(&err), [parse.to_string()]);
+
+    let json = serde_json::from_str::<Value>("{").unwrap_err();
+    let message = json.to_string();
+    assert!(json.source().is_none());
+    let err = Error::from(json);
+    assert!(matches!(err, Error::Json(_)));
+    assert_eq!(chain(&err), [message]);
+
+    let eval = JsonPath::compile("$[?(@.a > 0)]")
+        .unwrap()
+        .try_find_with(
+            &json!([{"a": "b"}]),
+            EvalOptions::new().strict_filter_errors(true),
+        )
+        .unwrap_err();
+    assert!(eval.source().is_none());
+    let message = eval.to_string();
+    let err = Error::from(eval);
+    assert_eq!(chain(&err), [message]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn parse_or_json_error_compat() {
+    use crate::error::{Error, ParseOrJsonError};
+
+    // Existing exhaustive matches on the deprecated type keep compiling
+    let describe = |err: &ParseOrJsonError| match err {
+        ParseOrJsonError::Parse(_) => "parse",
+        ParseOrJsonError::Json(_) => "json",
+    };
+
+    let parse = ParseOrJsonError::from(JsonPath::compile("$.a[").unwrap_err());
+    assert_eq!(describe(&parse), "parse");
+    assert!(matches!(Error::from(parse), Error::Parse(_)));
+
+    let json = ParseOrJsonError::from(serde_json::from_str::<Value>("{").unwrap_err());
+    assert_eq!(describe(&json), "json");
+    assert!(matches!(Error::from(json), Error::Json(_)));
+}
+
+#[test]
+fn ast_node_display() {
+    let paths = [
+        "$.a.*.^",
+        "$..b..*..",
+        "$['it\\'s', \"say \\\"hi\\\"\", 'back\\\\slash\\\\', 0, -1]",
+        "$[1:5:2][:3][1:][::-1][-2:]",
+        "$[*][^]~",
+        "$[$.a['b'], @.c~, ^, ?(@.d)]",
+        "$[?(@.a == 'x\\'y' && (@.b + 2) * 3 < -@.c)]",
+        "$[?(!(@.a || false) && @.b * 2 % 3 >= null)]",
+    ];
+
+    let bracket = |seg: &ast::Segment| match seg {
+        ast::Segment::Bracket(_, selector) => selector.clone(),
+        _ => panic!("Segment wasn't a bracket"),
+    };
+
+    for pattern in paths {
+        let path = JsonPath::compile(pattern).unwrap();
+        let mut rendered = String::from("$");
+        for seg in &path {
+            rendered.push_str(&seg.to_string());
+        }
+        if path.is_id() {
+            rendered.push('~');
+        }
+        assert_eq!(JsonPath::compile(&rendered).unwrap(), path, "{}", pattern);
+    }
+
+    let path = JsonPath::compile(paths[2]).unwrap();
+    let selector = bracket(&path[0]);
+    let lits = selector
+        .as_union()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_literal().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lits[0].as_str(), Some("it's"));
+    assert_eq!(lits[0].to_string(), "'it\\'s'");
+    assert_eq!(lits[1].as_str(), Some("say \"hi\""));
+    assert_eq!(lits[2].as_str(), Some("back\\slash\\"));
+    assert_eq!(lits[2].to_string(), "'back\\\\slash\\\\'");
+    assert_eq!(lits[4].to_string(), "-1");
+
+    let path = JsonPath::compile(paths[6]).unwrap();
+    let selector = bracket(&path[0]);
+    let filter = selector.as_filter().unwrap();
+    assert_eq!(
+        filter.to_string(),
+        "?(@.a == 'x\\'y' && (@.b + 2) * 3 < -@.c)"
+    );
+    assert_eq!(
+        filter.expression().to_string(),
+        "@.a == 'x\\'y' && (@.b + 2) * 3 < -@.c"
+    );
+    assert_eq!(path[0].to_string(), format!("[{}]", filter));
+}
+
+#[test]
+fn compile_from_reader() {
+    use crate::error::Error;
+
+    let path = JsonPath::compile_from_reader("  $.a[?(@.b)]\n".as_bytes()).unwrap();
+    assert_eq!(path, JsonPath::compile("$.a[?(@.b)]").unwrap());
+
+    let err = JsonPath::compile_from_reader("$.a[".as_bytes()).unwrap_err();
+    assert!(matches!(err, Error::Parse(_)));
+
+    let err = JsonPath::compile_from_reader(&[b'$', b'.', 0xFF][..]).unwrap_err();
+    assert!(matches!(&err, Error::Io(io) if io.kind() == std::io::ErrorKind::InvalidData));
+}
+
+#[test]
+fn span_source_offsets() {
+    let source = "$['ඞ']\n.b";
+    let lit = Span::from(2..5);
+    assert_eq!(lit.to_byte_range(source), 2..7);
+    assert_eq!(lit.get_span(source), "'ඞ'");
+    assert_eq!(lit.to_line_col(source), ((0, 2), (0, 5)));
+
+    let end = Span::from(7..9);
+    assert_eq!(end.to_byte_range(source), 9..11);
+    assert_eq!(end.try_get_span(source), Some(".b"));
+    assert_eq!(end.to_line_col(source), ((1, 0), (1, 2)));
+
+    let eof = Span::from(9..9);
+    assert_eq!(eof.get_span(source), "");
+    assert_eq!(eof.to_byte_range(source), 11..11);
+    assert_eq!(eof.to_line_col(source), ((1, 2), (1, 2)));
+
+    assert_eq!(Span::from(8..10).try_get_span(source), None);
+    assert_eq!(Span::from(10..10).try_get_span(source), None);
+}
+
+#[test]
+fn union_ordering() {
+    let json = json!([10, 20, 30]);
+    assert_eq!(
+        find("$[2, 0, 1]", &json).unwrap(),
+        [&json!(30), &json!(10), &json!(20)]
+    );
+    assert_eq!(
+        find("$[1:, 0, 1]", &json).unwrap(),
+        [&json!(20), &json!(30), &json!(10), &json!(20)]
+    );
+
+    let json = json!({"a": [1, 2], "b": [3, 4]});
+    let path = JsonPath::compile("$.*[1, 0]").unwrap();
+    assert_eq!(
+        path.find(&json),
+        [&json!(2), &json!(4), &json!(1), &json!(3)]
+    );
+    assert_eq!(
+        path.find_paths(&json)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["$['a'][1]", "$['b'][1]", "$['a'][0]", "$['b'][0]"]
+    );
+}
+
+#[test]
+fn ast_accessors() {
+    let path = JsonPath::compile("$.a..b..[1:2, 0:4:2, ^][?(@.c)].*~").unwrap();
+    assert!(path.is_id());
+    assert!(path.tilde_token().is_some());
+    assert!(JsonPath::compile("$.a").unwrap().tilde_token().is_none());
+
+    let raw = path
+        .segments()
+        .iter()
+        .map(|seg| seg.as_raw_selector().map(ToString::to_string))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        raw,
+        [
+            Some("a".to_string()),
+            Some("b".to_string()),
+            None,
+            None,
+            None,
+            Some("*".to_string()),
+        ]
+    );
+    assert_eq!(
+        path.segments()
+            .iter()
+            .map(ast::Segment::is_recursive)
+            .collect::<Vec<_>>(),
+        [false, true, true, false, false, false]
+    );
+    assert_eq!(
+        path[0]
+            .as_raw_selector()
+            .unwrap()
+            .as_name()
+            .unwrap()
+            .as_str(),
+        "a"
+    );
+    assert!(path[5].as_raw_selector().unwrap().is_wildcard());
+    assert!(path[0].as_bracket_selector().is_none());
+
+    let union = path[3].as_bracket_selector().unwrap().as_union().unwrap();
+    assert_eq!(union[0].as_range().unwrap().end(), Some(2));
+    assert!(union[0].as_step_range().is_none());
+    assert_eq!(union[1].as_step_range().unwrap().step().unwrap().get(), 2);
+    assert!(union[2].is_parent());
+
+    let filter = path[4].as_bracket_selector().unwrap().as_filter().unwrap();
+    assert_eq!(filter.expression().to_string(), "@.c");
+    let _: (&ast::token::Question, &ast::token::Paren) =
+        (filter.question_token(), filter.paren_token());
+}
@@ -1,152 +1,293 @@
 use crate::idx::IdxPath;
+use crate::json::{Json, JsonArray, JsonMut, JsonObject};
 use crate::Idx;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use serde_json::Value;
-use std::iter::FusedIterator;
+use std::borrow::Cow;
+use std::collections::HashMap;
 
-pub enum ValueIter<'a> {
-    Array(std::slice::Iter<'a, Value>),
-    Object(serde_json::map::Values<'a>),
-    Other,
-}
-
-impl<'a> ValueIter<'a> {
-    pub fn new(val: &'a Value) -> ValueIter<'a> {
-        match val {
-            Value::Array(v) => ValueIter::Array(v.iter()),
-            Value::Object(m) => ValueIter::Object(m.values()),
-            _ => ValueIter::Other,
+fn hash_val<H: Hasher>(val: &Value, state: &mut H) {
+    match val {
+        Value::Null => state.write_u8(0),
+        Value::Bool(b) => {
+            state.write_u8(1);
+            state.write_u8(u8::from(*b));
+        }
+        Value::Number(n) => {
+            state.write_u8(2);
+            // Zero and negative zero compare equal, so must hash the same
+            let n = n.as_f64().unwrap();
+            let n = if n == 0.0 { 0.0 } else { n };
+            state.write(&n.to_ne_bytes());
+        }
+        Value::String(s) => {
+            state.write_u8(3);
+            state.write(s.as_bytes());
+        }
+        Value::Array(a) => {
+            state.write_u8(4);
+            state.write_usize(a.len());
+            for v in a {
+                hash_val(v, state);
+            }
+        }
+        Value::Object(m) => {
+            state.write_u8(5);
+            state.write_usize(m.len());
+            for (key, val) in m {
+                state.write(key.as_bytes());
+                hash_val(val, state);
+            }
         }
     }
 }
 
-impl<'a> Iterator for ValueIter<'a> {
-    type Item = &'a Value;
+/// A wrapper for a JSON value, borrowed or owned, which hashes and compares by deep value
+/// equality
+#[derive(PartialEq, Eq)]
+pub struct ValueKey<'a>(Cow<'a, Value>);
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            ValueIter::Array(iter) => iter.next(),
-            ValueIter::Object(iter) => iter.next(),
-            ValueIter::Other => None,
-        }
+impl fmt::Debug for ValueKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <Value as fmt::Debug>::fmt(&self.0, f)
     }
+}
 
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len(), Some(self.len()))
+impl Hash for ValueKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_val(&self.0, state);
     }
 }
 
-impl<'a> FusedIterator for ValueIter<'a>
-where
-    std::slice::Iter<'a, Value>: FusedIterator,
-    serde_json::map::Values<'a>: FusedIterator,
-{
+impl From<Value> for ValueKey<'_> {
+    fn from(val: Value) -> Self {
+        ValueKey(Cow::Owned(val))
+    }
 }
 
-impl DoubleEndedIterator for ValueIter<'_> {
-    #[inline]
-    fn next_back(&mut self) -> Option<Self::Item> {
-        match self {
-            ValueIter::Array(iter) => iter.next_back(),
-            ValueIter::Object(iter) => iter.next_back(),
-            ValueIter::Other => None,
-        }
+impl<'a> From<&'a Value> for ValueKey<'a> {
+    fn from(val: &'a Value) -> Self {
+        ValueKey(Cow::Borrowed(val))
     }
 }
 
-impl ExactSizeIterator for ValueIter<'_> {
-    #[inline]
-    fn len(&self) -> usize {
-        match self {
-            ValueIter::Array(iter) => iter.len(),
-            ValueIter::Object(iter) => iter.len(),
-            ValueIter::Other => 0,
+/// Remove duplicate paths, and paths which are descendants of other paths in the set. Deleting
+/// an item deletes all of its children, so these paths are redundant.
+fn remove_descendants(mut paths: Vec<IdxPath>) -> Vec<IdxPath> {
+    // Lexicographic order places each path directly before all of its descendants
+    paths.sort_unstable();
+    paths.dedup();
+
+    let mut out: Vec<IdxPath> = Vec::with_capacity(paths.len());
+    for path in paths {
+        if !out.last().is_some_and(|last| path.starts_with(last)) {
+            out.push(path);
         }
     }
+    out
 }
 
-pub trait ValueExt {
-    fn iter(&self) -> ValueIter<'_>;
-    fn remove(&mut self, key: &Idx) -> Option<Value>;
+/// Resolve mutable references to the items at many paths at once. Mutable references can't
+/// overlap, so duplicate paths and paths nested inside another path are skipped, with the
+/// outermost path's item being returned instead. Items are returned in the order their paths
+/// first appeared.
+pub fn resolve_paths_mut(paths: Vec<IdxPath>, value: &mut Value) -> Vec<&mut Value> {
+    let mut paths = paths.into_iter().enumerate().collect::<Vec<_>>();
+    paths.sort_unstable_by(|(pos1, path1), (pos2, path2)| path1.cmp(path2).then(pos1.cmp(pos2)));
+
+    // Sorting places each path directly before its duplicates and descendants
+    let mut disjoint: Vec<(&[Idx], usize)> = Vec::with_capacity(paths.len());
+    for (pos, path) in &paths {
+        if !disjoint
+            .last()
+            .is_some_and(|(last, _)| path.raw_path().starts_with(last))
+        {
+            disjoint.push((path, *pos));
+        }
+    }
+
+    let mut out = Vec::with_capacity(disjoint.len());
+    collect_disjoint_mut(value, &disjoint, &mut out);
+    out.sort_unstable_by_key(|(pos, _)| *pos);
+    out.into_iter().map(|(_, val)| val).collect()
 }
 
-impl ValueExt for Value {
-    #[inline]
-    fn iter(&self) -> ValueIter<'_> {
-        ValueIter::new(self)
+/// Walk down a value, splitting the mutable borrow between children, and collect the items at
+/// each of a set of non-overlapping paths along with their original positions
+fn collect_disjoint_mut<'a>(
+    value: &'a mut Value,
+    paths: &[(&[Idx], usize)],
+    out: &mut Vec<(usize, &'a mut Value)>,
+) {
+    // Paths don't overlap, so an empty path is only ever alone
+    if let [([], pos)] = paths {
+        out.push((*pos, value));
+        return;
     }
 
-    #[inline]
-    fn remove(&mut self, key: &Idx) -> Option<Value> {
-        match (self, key) {
-            (Value::Array(v), Idx::Array(idx)) => {
-                if v.len() > *idx {
-                    Some(v.remove(*idx))
-                } else {
-                    None
+    let mut by_index: HashMap<usize, Vec<(&[Idx], usize)>> = HashMap::new();
+    let mut by_key: HashMap<&str, Vec<(&[Idx], usize)>> = HashMap::new();
+    for &(path, pos) in paths {
+        match path.split_first() {
+            Some((Idx::Array(i), rest)) => by_index.entry(*i).or_default().push((rest, pos)),
+            Some((Idx::Object(key), rest)) => by_key.entry(key).or_default().push((rest, pos)),
+            None => (),
+        }
+    }
+
+    match value {
+        Value::Array(v) => {
+            for (i, item) in v.iter_mut().enumerate() {
+                if let Some(sub) = by_index.remove(&i) {
+                    collect_disjoint_mut(item, &sub, out);
+                }
+            }
+        }
+        Value::Object(m) => {
+            for (key, item) in m.iter_mut() {
+                if let Some(sub) = by_key.remove(key.as_str()) {
+                    collect_disjoint_mut(item, &sub, out);
                 }
             }
-            (Value::Object(m), Idx::Object(idx)) => m.remove(idx),
-            _ => None,
         }
+        _ => (),
     }
 }
 
-pub fn delete_paths(mut paths: Vec<IdxPath>, out: &mut Value) {
-    // Ensure we always resolve paths longest to shortest, so if we match paths that are children
-    // of other paths, they get resolved first and don't cause panics
+/// Order paths so the most specific are applied first. Paths which are later siblings or
+/// children of other paths then get resolved before them, and aren't shifted or detached by
+/// earlier mutations
+fn order_for_mutation(mut paths: Vec<IdxPath>) -> Vec<IdxPath> {
     paths.sort_unstable_by(IdxPath::sort_specific_last);
-    for path in paths {
-        let delete_on = path
-            .remove(1)
-            .resolve_on_mut(out)
-            .expect("Could resolve path");
-        let last_idx = &path.raw_path()[path.len() - 1];
-        delete_on
-            .remove(last_idx)
-            .expect("Provided path should resolve");
+    paths
+}
+
+/// Resolve a mutable reference to the item at a path in any JSON value
+fn resolve_in_mut<'a, T: Json>(path: &[Idx], value: &'a mut T) -> Option<&'a mut T> {
+    path.iter()
+        .try_fold(value, |cur, idx| match (cur.as_mut(), idx) {
+            (JsonMut::Array(arr), Idx::Array(i)) => arr.get_mut(*i),
+            (JsonMut::Object(obj), Idx::Object(key)) => obj.get_mut(key),
+            _ => None,
+        })
+}
+
+/// Remove the child at an index from any JSON value, returning it if it existed
+pub fn remove_in<T: Json>(value: &mut T, idx: &Idx) -> Option<T> {
+    match (value.as_mut(), idx) {
+        (JsonMut::Array(arr), Idx::Array(i)) => arr.remove(*i),
+        (JsonMut::Object(obj), Idx::Object(key)) => obj.remove(key),
+        _ => None,
     }
 }
 
-pub fn replace_paths(mut paths: Vec<IdxPath>, out: &mut Value, mut f: impl FnMut(&Value) -> Value) {
-    // Ensure we always resolve paths longest to shortest, so if we match paths that are children
-    // of other paths, they get resolved first and don't cause panics
-    paths.sort_unstable_by(IdxPath::sort_specific_last);
-    for path in paths {
-        let replace_on = path
-            .remove(1)
-            .resolve_on_mut(out)
-            .expect("Could resolve path");
-        let last_idx = &path.raw_path()[path.len() - 1];
-        let new = f(&replace_on[last_idx]);
-        replace_on[last_idx] = new;
+/// Remove the item at a path, replacing the value itself with `null` if the path is empty
+fn delete_at<T: Json>(path: &IdxPath, out: &mut T) -> Option<T> {
+    match path.raw_path().split_last() {
+        Some((last, parent)) => remove_in(resolve_in_mut(parent, out)?, last),
+        None => Some(core::mem::replace(out, T::null())),
     }
 }
 
-pub fn try_replace_paths(
-    mut paths: Vec<IdxPath>,
-    out: &mut Value,
-    mut f: impl FnMut(&Value) -> Option<Value>,
+/// Delete the provided paths, returning the number of items removed. Items nested inside another
+/// deleted item aren't counted separately, and paths which don't resolve are skipped
+pub fn delete_paths<T: Json>(paths: Vec<IdxPath>, out: &mut T) -> usize {
+    order_for_mutation(remove_descendants(paths))
+        .into_iter()
+        .filter(|path| delete_at(path, out).is_some())
+        .count()
+}
+
+/// Delete the provided paths, returning each deleted path along with the value that was removed
+/// from it, in the order the deletions were applied. Paths which don't resolve are skipped
+pub fn delete_paths_collect<T: Json>(paths: Vec<IdxPath>, out: &mut T) -> Vec<(IdxPath, T)> {
+    order_for_mutation(remove_descendants(paths))
+        .into_iter()
+        .filter_map(|path| {
+            let old = delete_at(&path, out)?;
+            Some((path, old))
+        })
+        .collect()
+}
+
+/// Replace the provided paths, returning the number of items replaced. Paths which don't resolve
+/// are skipped
+pub fn replace_paths<T: Json>(
+    paths: Vec<IdxPath>,
+    out: &mut T,
+    mut f: impl FnMut(&T) -> T,
+) -> usize {
+    order_for_mutation(paths)
+        .into_iter()
+        .filter(|path| match resolve_in_mut(path.raw_path(), out) {
+            Some(target) => {
+                *target = f(target);
+                true
+            }
+            None => false,
+        })
+        .count()
+}
+
+/// Replace the provided paths, returning each replaced path along with the value it held before
+/// replacement, in the order the replacements were applied. Restoring these values in reverse
+/// order undoes the replacement. Paths which don't resolve are skipped
+pub fn replace_paths_collect<T: Json>(
+    paths: Vec<IdxPath>,
+    out: &mut T,
+    mut f: impl FnMut(&T) -> T,
+) -> Vec<(IdxPath, T)> {
+    order_for_mutation(paths)
+        .into_iter()
+        .filter_map(|path| {
+            let target = resolve_in_mut(path.raw_path(), out)?;
+            let new = f(target);
+            let old = core::mem::replace(target, new);
+            Some((path, old))
+        })
+        .collect()
+}
+
+/// Replace the provided paths, passing each matched path to the provided function along with the
+/// value it holds. Paths which don't resolve are skipped
+pub fn replace_paths_with_path<T: Json>(
+    paths: Vec<IdxPath>,
+    out: &mut T,
+    mut f: impl FnMut(&T, &IdxPath) -> T,
 ) {
-    // Ensure we always resolve paths longest to shortest, so if we match paths that are children
-    // of other paths, they get resolved first and don't cause panics
-    paths.sort_unstable_by(IdxPath::sort_specific_last);
-    for path in paths {
-        let replace_on = path
-            .remove(1)
-            .resolve_on_mut(out)
-            .expect("Could resolve path");
-        let last_idx = &path.raw_path()[path.len() - 1];
-
-        let new = f(&replace_on[last_idx]);
-        match new {
-            Some(new) => replace_on[last_idx] = new,
+    for path in order_for_mutation(paths) {
+        if let Some(target) = resolve_in_mut(path.raw_path(), out) {
+            *target = f(target, &path);
+        }
+    }
+}
+
+/// Replace or delete the provided paths, returning the number of items replaced and the number
+/// deleted. Paths which don't resolve, such as those inside an already deleted item, are skipped
+pub fn try_replace_paths<T: Json>(
+    paths: Vec<IdxPath>,
+    out: &mut T,
+    mut f: impl FnMut(&T) -> Option<T>,
+) -> (usize, usize) {
+    let mut replaced = 0;
+    let mut deleted = 0;
+    for path in order_for_mutation(paths) {
+        let Some(target) = resolve_in_mut(path.raw_path(), out) else {
+            continue;
+        };
+        match f(target) {
+            Some(new) => {
+                *target = new;
+                replaced += 1;
+            }
+            // Deleting the root itself leaves `null` behind
             None => {
-                replace_on
-                    .remove(last_idx)
-                    .expect("Provided path should resolve");
+                if delete_at(&path, out).is_some() {
+                    deleted += 1;
+                }
             }
         }
     }
+    (replaced, deleted)
 }
@@ -0,0 +1,69 @@
+//! Warnings produced when compiling paths which are valid, but likely mistakes
+
+use core::fmt;
+
+#[cfg(feature = "spanned")]
+use crate::ast::Span;
+
+/// The category of a [`CompileWarning`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A slice which can never select any items, such as `[5:2]`
+    EmptySlice,
+    /// A union which contains the same literal more than once, such as `[1, 1]`
+    DuplicateUnion,
+    /// A filter expression which compares two literals, such as `?(1 == 2)`
+    ConstantFilter,
+    /// A recursive descent immediately followed by another, such as `$....a`
+    RedundantRecursive,
+}
+
+/// A warning about a path which compiled successfully, but is suspicious
+#[derive(Clone, Debug)]
+pub struct CompileWarning {
+    kind: WarningKind,
+    message: String,
+    #[cfg(feature = "spanned")]
+    span: Span,
+}
+
+impl CompileWarning {
+    pub(crate) fn new(
+        kind: WarningKind,
+        message: &str,
+        #[cfg(feature = "spanned")] span: Span,
+    ) -> CompileWarning {
+        CompileWarning {
+            kind,
+            message: message.to_string(),
+            #[cfg(feature = "spanned")]
+            span,
+        }
+    }
+
+    /// Get the category of this warning
+    #[must_use]
+    pub fn kind(&self) -> WarningKind {
+        self.kind
+    }
+
+    /// Get the human-readable message for this warning
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the source span of the item this warning refers to
+    #[cfg(feature = "spanned")]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for CompileWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}